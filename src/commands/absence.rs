@@ -1,14 +1,31 @@
 use super::Command;
 use crate::config::Config;
+use crate::utils::absence_plan::{PlannedDay, plan_absences};
+use crate::utils::absence_summary::{absence_days, summarize_absences};
+use crate::utils::date::{DateArg, DateRange};
 use crate::utils::editor::EditorSession;
 use crate::utils::formatting::{self, AbsenceHoursColor, AbsenceIdColor, AbsenceTypeFormat};
-use crate::utils::selection::SelectionMenu;
-use crate::wad_data::{AbsenceRecord, AbsenceStorage, AbsenceType, JsonDataStore, WadDataStore};
-use crate::watson::WatsonClient;
+use crate::utils::ical::{CalendarEvent, read_events};
+use crate::utils::output::{AbsenceRow, OutputFormat, PathRecord, Record};
+use crate::utils::schedule::{ScheduleHistory, parse_hours};
+use crate::utils::selection::{SelectionMenu, confirm};
+use crate::utils::vacation::VacationYear;
+use crate::wad_data::{
+    AbsenceRecord, AbsenceStorage, AbsenceType, ContractStorage, DataStores, group_by_date,
+};
+use crate::watson::FrameSources;
 use anyhow::Result;
-use chrono::{Local, NaiveDate};
+use chrono::{Datelike, NaiveDate};
 use clap::{Parser, Subcommand};
 use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::{Alignment, Style};
 use ulid::Ulid;
 
 // UI color aliases
@@ -25,28 +42,93 @@ enum AbsenceAction {
     /// Show all absences for a specific date
     Show {
         /// Date to show absences for (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
-        #[arg(value_parser = parse_date)]
-        date: NaiveDate,
+        date: DateArg,
     },
-    /// Add a new absence record
+    /// List absences over a period, with totals per type and year
+    List {
+        /// First day to list (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow'; default: start of the current year)
+        #[arg(long, conflicts_with = "year")]
+        from: Option<DateArg>,
+        /// Last day to list (default: end of the current year)
+        #[arg(long, conflicts_with = "year")]
+        to: Option<DateArg>,
+        /// Calendar year to list
+        #[arg(long)]
+        year: Option<i32>,
+        /// Only list absences of this type (vacation, sick, overtime-reduction, holiday, other:custom)
+        #[arg(long = "type")]
+        absence_type: Option<AbsenceType>,
+    },
+    /// Show vacation days taken, planned and remaining, including carry-over
+    Balance {
+        /// Calendar year to show (default: current year)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Add a new absence record, or one per working day of a range
+    ///
+    /// Takes `DATE HOURS TYPE` for a single day, or --from, --to and `TYPE`
+    /// for a range. Without --hours, each day of a range gets its target.
     Add {
-        /// Date for the absence (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
-        #[arg(value_parser = parse_date)]
-        date: NaiveDate,
+        /// Date for the absence (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow'), or the type of the absences of a range
+        #[arg(value_name = "DATE|TYPE", value_parser = parse_date_or_type)]
+        date: DateOrType,
         /// Hours for the absence
-        hours: f64,
+        #[arg(
+            required_unless_present = "from",
+            conflicts_with = "from",
+            value_parser = parse_positive_hours
+        )]
+        hours: Option<f64>,
         /// Type of absence (vacation, sick, overtime-reduction, holiday, other:custom)
-        #[arg(name = "type", value_parser = parse_absence_type)]
-        absence_type: AbsenceType,
+        #[arg(
+            name = "type",
+            required_unless_present = "from",
+            conflicts_with = "from"
+        )]
+        absence_type: Option<AbsenceType>,
+        /// First day of a range of absences (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
+        #[arg(long, requires = "to")]
+        from: Option<DateArg>,
+        /// Last day of a range of absences
+        #[arg(long, requires = "from")]
+        to: Option<DateArg>,
+        /// Hours per day of a range (default: each day's target)
+        #[arg(
+            long = "hours",
+            id = "range_hours",
+            value_name = "HOURS",
+            requires = "from",
+            value_parser = parse_positive_hours
+        )]
+        range_hours: Option<f64>,
         /// Optional note for the absence
         #[arg(long)]
         note: Option<String>,
+        /// Add a range without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Import absences from the events of an iCalendar (.ics) file
+    ///
+    /// Each working day covered by an event gets an absence, like with
+    /// `add --from/--to`. Events imported before are recognized by their UID
+    /// and skipped. Nothing is stored unless --apply is given.
+    Import {
+        /// Path to the .ics file
+        #[arg(long, value_name = "FILE")]
+        ics: PathBuf,
+        /// Type of the imported absences (vacation, sick, overtime-reduction, holiday, other:custom)
+        #[arg(long = "type")]
+        absence_type: AbsenceType,
+        /// Store the absences instead of only showing what would be imported
+        #[arg(long)]
+        apply: bool,
     },
     /// Remove a specific absence record
     Remove {
         /// Date of the absence (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
-        #[arg(value_parser = parse_date)]
-        date: NaiveDate,
+        date: DateArg,
         /// ULID of the specific absence record to remove (optional if only one exists)
         #[arg(long, value_parser = parse_ulid)]
         id: Option<Ulid>,
@@ -54,49 +136,52 @@ enum AbsenceAction {
     /// Edit a specific absence record
     Edit {
         /// Date of the absence (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
-        #[arg(value_parser = parse_date)]
-        date: NaiveDate,
+        date: DateArg,
         /// ULID of the specific absence record to edit (optional if only one exists)
         #[arg(long, value_parser = parse_ulid)]
         id: Option<Ulid>,
     },
-    /// Show the path to the absence data directory
+    /// Show the path to the absence data directory, or the database when using SQLite
     Path,
 }
 
-fn parse_date(s: &str) -> Result<NaiveDate, String> {
-    match s.to_lowercase().as_str() {
-        "today" => Ok(Local::now().date_naive()),
-        "yesterday" => Ok(Local::now().date_naive() - chrono::Duration::days(1)),
-        "tomorrow" => Ok(Local::now().date_naive() + chrono::Duration::days(1)),
-        _ => NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
-            "Invalid date format. Use YYYY-MM-DD, 'today', 'yesterday', or 'tomorrow'".to_string()
-        }),
-    }
+fn parse_ulid(s: &str) -> Result<Ulid, String> {
+    Ulid::from_string(s).map_err(|_| "Invalid ULID format".to_string())
 }
 
-fn parse_absence_type(s: &str) -> Result<AbsenceType, String> {
-    match s.to_lowercase().as_str() {
-        "vacation" => Ok(AbsenceType::Vacation),
-        "sick" => Ok(AbsenceType::Sick),
-        "overtime-reduction" => Ok(AbsenceType::OvertimeReduction),
-        "holiday" => Ok(AbsenceType::Holiday),
-        _ => {
-            if let Some(custom) = s.strip_prefix("other:") {
-                Ok(AbsenceType::Other(custom.to_string()))
-            } else {
-                Err("Invalid absence type. Use: vacation, sick, overtime-reduction, holiday, or other:custom".to_string())
-            }
-        }
-    }
+/// The first positional argument of `absence add`
+///
+/// A single absence starts with its date, while a range given by --from and
+/// --to only takes the type of its absences.
+#[derive(Clone)]
+enum DateOrType {
+    Date(DateArg),
+    Type(AbsenceType),
 }
 
-fn parse_ulid(s: &str) -> Result<Ulid, String> {
-    Ulid::from_string(s).map_err(|_| "Invalid ULID format".to_string())
+fn parse_date_or_type(s: &str) -> Result<DateOrType, String> {
+    // Dates and absence types never overlap, so the order doesn't matter
+    s.parse::<DateArg>()
+        .map(DateOrType::Date)
+        .or_else(|_| s.parse::<AbsenceType>().map(DateOrType::Type))
+        .map_err(|_| {
+            "Expected a date (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow') or an absence type (vacation, sick, overtime-reduction, holiday, other:custom)".to_string()
+        })
+}
+
+fn parse_positive_hours(s: &str) -> Result<f64, String> {
+    parse_hours(s)
+        .ok()
+        .filter(|hours| *hours > 0.0)
+        .ok_or_else(|| format!("Invalid hours '{s}', expected a positive number"))
 }
 
-fn select_absence_record(date: NaiveDate, id: Option<Ulid>) -> Result<AbsenceRecord> {
-    let store = JsonDataStore::open()?;
+fn select_absence_record(
+    date: NaiveDate,
+    id: Option<Ulid>,
+    config: &Config,
+) -> Result<AbsenceRecord> {
+    let store = DataStores::open(config.data_store)?;
     let absences = store.get_absence(date)?;
 
     if absences.is_empty() {
@@ -136,10 +221,15 @@ fn select_absence_record(date: NaiveDate, id: Option<Ulid>) -> Result<AbsenceRec
     Ok(selected_record)
 }
 
-fn show_absences(date: NaiveDate) -> Result<()> {
-    let store = JsonDataStore::open()?;
+fn show_absences(date: NaiveDate, config: &Config, output: OutputFormat) -> Result<()> {
+    let store = DataStores::open(config.data_store)?;
     let absences = store.get_absence(date)?;
 
+    if !output.is_text() {
+        let rows: Vec<AbsenceRow> = absences.iter().map(AbsenceRow::from).collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
     let formatted_date = date
         .format("%Y-%m-%d")
         .to_string()
@@ -157,13 +247,227 @@ fn show_absences(date: NaiveDate) -> Result<()> {
     Ok(())
 }
 
+/// Format a number of days without needless decimals, e.g. `2` or `1.5`
+fn format_days(days: f64) -> String {
+    format!("{}", (days * 100.0).round() / 100.0)
+}
+
+fn list_absences(
+    range: DateRange,
+    absence_type: Option<AbsenceType>,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let store = DataStores::open(config.data_store)?;
+    let records: Vec<AbsenceRecord> = store
+        .get_absences(&range)?
+        .into_iter()
+        .filter(|record| {
+            absence_type
+                .as_ref()
+                .is_none_or(|absence_type| record.absence_type == *absence_type)
+        })
+        .collect();
+
+    if !output.is_text() {
+        let rows: Vec<AbsenceRow> = records.iter().map(AbsenceRow::from).collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
+    if records.is_empty() {
+        println!("No absences found in {}", range);
+        return Ok(());
+    }
+
+    let mut b = Builder::new();
+    b.push_record(["Date", "Type", "Hours", "Note", "ID"]);
+    for record in &records {
+        b.push_record([
+            record
+                .date
+                .format("%a %Y-%m-%d")
+                .to_string()
+                .fg::<AbsenceDateColor>()
+                .to_string(),
+            record.absence_type.to_string_colored(),
+            record.hours.to_string(),
+            record.note.clone().unwrap_or_default(),
+            record.id.to_string().fg::<AbsenceIdColor>().to_string(),
+        ]);
+    }
+    let mut table = b.build();
+    table
+        .with(Style::modern_rounded())
+        .modify(Columns::new(2..3), Alignment::right());
+    println!("{}", table);
+
+    let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+    let mut b = Builder::new();
+    b.push_record(["Year", "Type", "Days", "Hours"]);
+    for summary in summarize_absences(&records, &schedules) {
+        for (absence_type, total) in &summary.by_type {
+            b.push_record([
+                summary.year.to_string(),
+                absence_type.to_string_colored(),
+                format_days(total.days),
+                total.hours.to_string(),
+            ]);
+        }
+        let total = summary.total();
+        b.push_record([
+            summary.year.to_string(),
+            formatting::header_text("Total"),
+            format_days(total.days),
+            total.hours.to_string(),
+        ]);
+    }
+    let mut table = b.build();
+    table
+        .with(Style::modern_rounded())
+        .modify(Columns::new(2..), Alignment::right());
+    println!("{}", table);
+
+    Ok(())
+}
+
+/// A year's vacation account, as emitted in machine-readable output
+#[derive(Serialize)]
+struct VacationRecord {
+    year: i32,
+    entitlement_days: f64,
+    carry_over_days: f64,
+    taken_days: f64,
+    planned_days: f64,
+    expired_days: f64,
+    expiring_days: f64,
+    remaining_days: f64,
+    carry_over_expires: Option<NaiveDate>,
+}
+
+impl Record for VacationRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "year",
+        "entitlement_days",
+        "carry_over_days",
+        "taken_days",
+        "planned_days",
+        "expired_days",
+        "expiring_days",
+        "remaining_days",
+        "carry_over_expires",
+    ];
+}
+
+impl From<&VacationYear> for VacationRecord {
+    fn from(year: &VacationYear) -> Self {
+        Self {
+            year: year.year,
+            entitlement_days: year.entitlement,
+            carry_over_days: year.carry_over,
+            taken_days: year.taken,
+            planned_days: year.planned,
+            expired_days: year.expired,
+            expiring_days: year.expiring,
+            remaining_days: year.remaining(),
+            carry_over_expires: year.carry_over_expires,
+        }
+    }
+}
+
+fn show_vacation_balance(year: Option<i32>, config: &Config, output: OutputFormat) -> Result<()> {
+    let today = config.timezone.today();
+    let year = year.unwrap_or(today.year());
+
+    // Carry-over is calculated year by year, starting with the first tracked year
+    let start = config
+        .vacation_start
+        .or_else(|| NaiveDate::from_ymd_opt(year, 1, 1))
+        .ok_or_else(|| anyhow::anyhow!("Invalid year: {}", year))?;
+    let end = NaiveDate::from_ymd_opt(year, 12, 31)
+        .ok_or_else(|| anyhow::anyhow!("Invalid year: {}", year))?;
+    if start > end {
+        return Err(anyhow::anyhow!(
+            "Vacation tracking starts on {}, see `wad config get vacation_start`",
+            start.format("%Y-%m-%d")
+        ));
+    }
+
+    let store = DataStores::open(config.data_store)?;
+    let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+    let vacation: Vec<(NaiveDate, f64)> = store
+        .get_absences(&DateRange::new(start, end).map_err(anyhow::Error::msg)?)?
+        .iter()
+        .filter(|record| record.absence_type == AbsenceType::Vacation)
+        .map(|record| (record.date, absence_days(record, &schedules)))
+        .collect();
+
+    let years = config
+        .vacation_policy()
+        .years(start.year(), year, &vacation, today);
+
+    if !output.is_text() {
+        let records: Vec<VacationRecord> = years.iter().map(VacationRecord::from).collect();
+        return Ok(output.print_records(&records)?);
+    }
+
+    let mut b = Builder::new();
+    b.push_record([
+        "Year",
+        "Entitlement",
+        "Carry-over",
+        "Taken",
+        "Planned",
+        "Expired",
+        "Remaining",
+    ]);
+    for vacation_year in &years {
+        let remaining = format_days(vacation_year.remaining());
+        b.push_record([
+            vacation_year.year.to_string(),
+            format_days(vacation_year.entitlement),
+            format_days(vacation_year.carry_over),
+            format_days(vacation_year.taken),
+            format_days(vacation_year.planned),
+            format_days(vacation_year.expired),
+            if vacation_year.remaining() < 0.0 {
+                formatting::error_text(&remaining)
+            } else {
+                formatting::success_text(&remaining)
+            },
+        ]);
+    }
+    let mut table = b.build();
+    table
+        .with(Style::modern_rounded())
+        .modify(Columns::new(1..), Alignment::right());
+    println!("{}", table);
+
+    if let Some(current) = years.last()
+        && current.expiring > 0.0
+        && let Some(expires) = current.carry_over_expires
+    {
+        println!(
+            "{}",
+            formatting::warning_text(&format!(
+                "{} carried over day(s) expire on {} unless taken before",
+                format_days(current.expiring),
+                expires.format("%Y-%m-%d")
+            ))
+        );
+    }
+
+    Ok(())
+}
+
 fn add_absence(
     date: NaiveDate,
     hours: f64,
     absence_type: AbsenceType,
     note: Option<String>,
+    config: &Config,
+    output: OutputFormat,
 ) -> Result<()> {
-    let store = JsonDataStore::open()?;
+    let store = DataStores::open(config.data_store)?;
 
     let record = AbsenceRecord {
         id: Ulid::new(),
@@ -171,9 +475,14 @@ fn add_absence(
         hours,
         absence_type,
         note,
+        ical_uid: None,
     };
 
     store.add_absence(record.clone())?;
+    if !output.is_text() {
+        return Ok(output.print_records(&[AbsenceRow::from(&record)])?);
+    }
+
     println!(
         "{} {} | {} | {} on {}",
         formatting::success_text("Added absence:"),
@@ -185,11 +494,248 @@ fn add_absence(
     Ok(())
 }
 
-fn remove_absence(date: NaiveDate, id: Option<Ulid>) -> Result<()> {
-    let record = select_absence_record(date, id)?;
-    let store = JsonDataStore::open()?;
+fn add_absence_range(
+    range: DateRange,
+    hours: Option<f64>,
+    absence_type: AbsenceType,
+    note: Option<String>,
+    config: &Config,
+    confirmed: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let store = DataStores::open(config.data_store)?;
+    let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+
+    // Public holidays are skipped like stored ones
+    let existing = group_by_date(config.holiday_calendar().apply(
+        store.get_absences(&range)?,
+        &range,
+        &schedules,
+    ));
+    let plan = plan_absences(&range, hours, &schedules, |date| {
+        existing.get(&date).cloned().unwrap_or_default()
+    });
+
+    let records: Vec<AbsenceRecord> = plan
+        .iter()
+        .filter_map(|day| match day {
+            PlannedDay::Add { date, hours } => Some(AbsenceRecord {
+                id: Ulid::new(),
+                date: *date,
+                hours: *hours,
+                absence_type: absence_type.clone(),
+                note: note.clone(),
+                ical_uid: None,
+            }),
+            PlannedDay::Skip { .. } => None,
+        })
+        .collect();
+
+    // The preview goes to stderr, so machine-readable output stays clean
+    print_plan(&plan, &absence_type);
+
+    if records.is_empty() {
+        eprintln!(
+            "{}",
+            formatting::info_text(&format!("No working days to add absences on in {}", range))
+        );
+        if !output.is_text() {
+            output.print_records::<AbsenceRow>(&[])?;
+        }
+        return Ok(());
+    }
+
+    let total_hours: f64 = records.iter().map(|record| record.hours).sum();
+    if !confirmed
+        && !confirm(format!(
+            "Add {} absence(s) with {} hours in total?",
+            records.len(),
+            total_hours
+        ))?
+    {
+        eprintln!("{}", formatting::info_text("Nothing added"));
+        return Ok(());
+    }
+
+    for record in &records {
+        store.add_absence(record.clone())?;
+    }
+
+    if !output.is_text() {
+        let rows: Vec<AbsenceRow> = records.iter().map(AbsenceRow::from).collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
+    println!(
+        "{} {} absence(s) with {} hours in total in {}",
+        formatting::success_text("Added"),
+        records.len(),
+        format!("{}", total_hours).fg::<AbsenceHoursColor>(),
+        range
+    );
+    Ok(())
+}
+
+fn import_absences(
+    path: &Path,
+    absence_type: AbsenceType,
+    apply: bool,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let mut events = read_events(BufReader::new(file), config.timezone)?;
+    events.sort_by_key(|event| event.as_ref().ok().map(|event| event.dates.from));
+
+    let imported: Vec<&CalendarEvent> = events
+        .iter()
+        .filter_map(|event| event.as_ref().ok())
+        .collect();
+    let (Some(first_day), Some(last_day)) = (
+        imported.iter().map(|event| event.dates.from).min(),
+        imported.iter().map(|event| event.dates.to).max(),
+    ) else {
+        return Err(anyhow::anyhow!(
+            "No importable events in {}",
+            path.display()
+        ));
+    };
+    let span = DateRange::new(first_day, last_day).map_err(anyhow::Error::msg)?;
+
+    let store = DataStores::open(config.data_store)?;
+    let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+    let stored = store.get_absences(&span)?;
+    // An event's absences lie within its days, so the span holds all earlier imports
+    let imported_uids: HashSet<String> = stored
+        .iter()
+        .filter_map(|record| record.ical_uid.clone())
+        .collect();
+    // Public holidays are skipped like stored ones
+    let mut existing = group_by_date(config.holiday_calendar().apply(stored, &span, &schedules));
+
+    // The preview goes to stderr, so machine-readable output stays clean
+    let mut records = Vec::new();
+    for event in &events {
+        let event = match event {
+            Ok(event) => event,
+            Err(rejected) => {
+                eprintln!(
+                    "{} {}",
+                    rejected.summary.as_deref().unwrap_or("Untitled event"),
+                    format!("(skipped, {})", rejected.error).dimmed()
+                );
+                continue;
+            }
+        };
+
+        let title = event.summary.as_deref().unwrap_or("Untitled event");
+        if imported_uids.contains(&event.uid) {
+            eprintln!("{} {}", title, "(skipped, already imported)".dimmed());
+            continue;
+        }
+
+        eprintln!("{} ({})", title, event.dates);
+        let plan = plan_absences(&event.dates, event.hours, &schedules, |date| {
+            existing.get(&date).cloned().unwrap_or_default()
+        });
+        print_plan(&plan, &absence_type);
+
+        for day in plan {
+            if let PlannedDay::Add { date, hours } = day {
+                let record = AbsenceRecord {
+                    id: Ulid::new(),
+                    date,
+                    hours,
+                    absence_type: absence_type.clone(),
+                    note: event.summary.clone(),
+                    ical_uid: Some(event.uid.clone()),
+                };
+                // Overlapping events in the same file don't book a day twice
+                existing.entry(date).or_default().push(record.clone());
+                records.push(record);
+            }
+        }
+    }
+
+    if apply {
+        for record in &records {
+            store.add_absence(record.clone())?;
+        }
+    }
+
+    // Without --apply, the records that would be added are emitted
+    if !output.is_text() {
+        let rows: Vec<AbsenceRow> = records.iter().map(AbsenceRow::from).collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
+    let total_hours: f64 = records.iter().map(|record| record.hours).sum();
+    if apply {
+        println!(
+            "{} {} absence(s) with {} hours in total from {}",
+            formatting::success_text("Imported"),
+            records.len(),
+            format!("{}", total_hours).fg::<AbsenceHoursColor>(),
+            path.display()
+        );
+    } else if records.is_empty() {
+        println!("{}", formatting::info_text("Nothing to import"));
+    } else {
+        println!(
+            "{}",
+            formatting::info_text(&format!(
+                "Dry run: {} absence(s) with {} hours in total would be imported, run again with --apply to store them",
+                records.len(),
+                total_hours
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// Print what happens on each day of a planned range to stderr
+fn print_plan(plan: &[PlannedDay], absence_type: &AbsenceType) {
+    for day in plan {
+        let date = day
+            .date()
+            .format("%a %Y-%m-%d")
+            .to_string()
+            .fg::<AbsenceDateColor>()
+            .to_string();
+        match day {
+            PlannedDay::Add { hours, .. } => eprintln!(
+                "  {} | {} | {}",
+                date,
+                format!("{} hours", hours).fg::<AbsenceHoursColor>(),
+                absence_type.to_string_colored()
+            ),
+            PlannedDay::Skip { reason, .. } => {
+                eprintln!("  {} | {}", date, format!("skipped, {}", reason).dimmed())
+            }
+        }
+    }
+}
+
+fn remove_absence(
+    date: NaiveDate,
+    id: Option<Ulid>,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let record = select_absence_record(date, id, config)?;
+    let store = DataStores::open(config.data_store)?;
 
     let removed = store.remove_absence(date, record.id)?;
+    if !output.is_text() {
+        // Only the records actually removed are emitted
+        let rows: Vec<AbsenceRow> = removed
+            .then(|| AbsenceRow::from(&record))
+            .into_iter()
+            .collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
     if removed {
         println!(
             "{} {} from {}",
@@ -208,15 +754,23 @@ fn remove_absence(date: NaiveDate, id: Option<Ulid>) -> Result<()> {
     Ok(())
 }
 
-fn edit_absence(date: NaiveDate, id: Option<Ulid>) -> Result<()> {
-    let original_record = select_absence_record(date, id)?;
-    let store = JsonDataStore::open()?;
+fn edit_absence(
+    date: NaiveDate,
+    id: Option<Ulid>,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let original_record = select_absence_record(date, id, config)?;
+    let store = DataStores::open(config.data_store)?;
 
     // Create editor session and edit the record
     let editor_session = EditorSession::new(original_record.clone());
     let edited_record = match editor_session.edit() {
         Ok(record) => record,
         Err(crate::utils::editor::EditorError::NoChanges) => {
+            if !output.is_text() {
+                return Ok(output.print_records(&[AbsenceRow::from(&original_record)])?);
+            }
             println!(
                 "{} No changes made to absence {}",
                 formatting::info_text("Info:"),
@@ -229,6 +783,9 @@ fn edit_absence(date: NaiveDate, id: Option<Ulid>) -> Result<()> {
 
     // Update the record in storage
     store.update_absence(date, edited_record.clone())?;
+    if !output.is_text() {
+        return Ok(output.print_records(&[AbsenceRow::from(&edited_record)])?);
+    }
 
     println!(
         "{} {} | {} | {} on {}",
@@ -246,26 +803,112 @@ fn edit_absence(date: NaiveDate, id: Option<Ulid>) -> Result<()> {
     Ok(())
 }
 
-fn show_absence_path() -> Result<()> {
-    let store = JsonDataStore::open()?;
-    let absences_dir = store.absences_dir();
-    println!("{}", absences_dir.display());
+fn show_absence_path(config: &Config, output: OutputFormat) -> Result<()> {
+    let path = match DataStores::open(config.data_store)? {
+        DataStores::Json(store) => store.absences_dir(),
+        DataStores::Sqlite(store) => store.database_path(),
+    };
+    if !output.is_text() {
+        return Ok(output.print_records(&[PathRecord {
+            path: path.display().to_string(),
+        }])?);
+    }
+    println!("{}", path.display());
     Ok(())
 }
 
 impl Command for AbsenceCommand {
-    fn run(&self, _watson_client: &WatsonClient, _config: &Config, _verbose: bool) -> Result<()> {
+    fn run(
+        &self,
+        _frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        _verbose: bool,
+    ) -> Result<()> {
+        let timezone = config.timezone;
+
         match &self.action {
-            AbsenceAction::Show { date } => show_absences(*date),
+            AbsenceAction::Show { date } => show_absences(date.resolve(timezone), config, output),
+            AbsenceAction::Balance { year } => show_vacation_balance(*year, config, output),
+            AbsenceAction::List {
+                from,
+                to,
+                year,
+                absence_type,
+            } => {
+                let year = year.unwrap_or(timezone.today().year());
+                let (Some(first_day), Some(last_day)) = (
+                    NaiveDate::from_ymd_opt(year, 1, 1),
+                    NaiveDate::from_ymd_opt(year, 12, 31),
+                ) else {
+                    return Err(anyhow::anyhow!("Invalid year: {}", year));
+                };
+                let range = DateRange::new(
+                    from.map_or(first_day, |date| date.resolve(timezone)),
+                    to.map_or(last_day, |date| date.resolve(timezone)),
+                )
+                .map_err(anyhow::Error::msg)?;
+                list_absences(range, absence_type.clone(), config, output)
+            }
             AbsenceAction::Add {
                 date,
                 hours,
                 absence_type,
+                from,
+                to,
+                range_hours,
                 note,
-            } => add_absence(*date, *hours, absence_type.clone(), note.clone()),
-            AbsenceAction::Remove { date, id } => remove_absence(*date, *id),
-            AbsenceAction::Edit { date, id } => edit_absence(*date, *id),
-            AbsenceAction::Path => show_absence_path(),
+                yes,
+            } => match (date, from.zip(*to)) {
+                // clap requires --to together with --from
+                (DateOrType::Type(absence_type), Some((from, to))) => {
+                    let range = DateRange::new(from.resolve(timezone), to.resolve(timezone))
+                        .map_err(anyhow::Error::msg)?;
+                    add_absence_range(
+                        range,
+                        *range_hours,
+                        absence_type.clone(),
+                        note.clone(),
+                        config,
+                        *yes,
+                        output,
+                    )
+                }
+                (DateOrType::Date(_), Some(_)) => {
+                    anyhow::bail!("A range takes the type of absence instead of a date")
+                }
+                (DateOrType::Date(date), None) => {
+                    // clap requires HOURS and TYPE without --from
+                    let (Some(hours), Some(absence_type)) = (hours, absence_type) else {
+                        unreachable!("HOURS and TYPE are required without --from");
+                    };
+                    add_absence(
+                        date.resolve(timezone),
+                        *hours,
+                        absence_type.clone(),
+                        note.clone(),
+                        config,
+                        output,
+                    )
+                }
+                (DateOrType::Type(_), None) => {
+                    anyhow::bail!(
+                        "A single absence takes a date, or use --from and --to for a range"
+                    )
+                }
+            },
+            AbsenceAction::Remove { date, id } => {
+                remove_absence(date.resolve(timezone), *id, config, output)
+            }
+            AbsenceAction::Edit { date, id } => {
+                edit_absence(date.resolve(timezone), *id, config, output)
+            }
+            AbsenceAction::Import {
+                ics,
+                absence_type,
+                apply,
+            } => import_absences(ics, absence_type.clone(), *apply, config, output),
+            AbsenceAction::Path => show_absence_path(config, output),
         }
     }
 }
@@ -1,9 +1,22 @@
 use super::Command;
 use crate::config::Config;
 use crate::utils::formatting;
-use crate::watson::WatsonClient;
+use crate::utils::output::{OutputFormat, PathRecord, Record};
+use crate::watson::FrameSources;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+/// A configuration entry, as emitted in machine-readable output
+#[derive(Serialize)]
+struct ConfigValue {
+    key: String,
+    value: String,
+}
+
+impl Record for ConfigValue {
+    const FIELDS: &'static [&'static str] = &["key", "value"];
+}
 
 #[derive(Parser)]
 pub struct ConfigCommand {
@@ -32,16 +45,35 @@ enum ConfigAction {
 }
 
 impl Command for ConfigCommand {
-    fn run(&self, _watson_client: &WatsonClient, config: &Config, verbose: bool) -> Result<()> {
+    fn run(
+        &self,
+        _frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
         match &self.action {
             ConfigAction::Path => {
                 let config_dir = Config::config_dir()
                     .map_err(|e| anyhow::anyhow!("Failed to get config directory: {}", e))?;
-                println!("{}", config_dir.display());
+                if output.is_text() {
+                    println!("{}", config_dir.display());
+                } else {
+                    output.print_records(&[PathRecord {
+                        path: config_dir.display().to_string(),
+                    }])?;
+                }
             }
             ConfigAction::Get { key } => {
                 if let Some(value) = config.get_value(key) {
-                    println!("{}", value);
+                    if output.is_text() {
+                        println!("{}", value);
+                    } else {
+                        output.print_records(&[ConfigValue {
+                            key: key.clone(),
+                            value,
+                        }])?;
+                    }
                 } else {
                     return Err(anyhow::anyhow!("Unknown config key: {}", key));
                 }
@@ -58,7 +90,12 @@ impl Command for ConfigCommand {
                     .save()
                     .map_err(|e| anyhow::anyhow!("Failed to save config: {}", e))?;
 
-                if verbose {
+                if !output.is_text() {
+                    output.print_records(&[ConfigValue {
+                        key: key.clone(),
+                        value: new_config.get_value(key).unwrap_or_default(),
+                    }])?;
+                } else if verbose {
                     println!(
                         "{}",
                         formatting::success_text(&format!("Set {} = {}", key, value))
@@ -67,8 +104,16 @@ impl Command for ConfigCommand {
             }
             ConfigAction::List => {
                 let values = config.list_values();
-                for (key, value) in values {
-                    println!("{} = {}", key, value);
+                if output.is_text() {
+                    for (key, value) in values {
+                        println!("{} = {}", key, value);
+                    }
+                } else {
+                    let records: Vec<ConfigValue> = values
+                        .into_iter()
+                        .map(|(key, value)| ConfigValue { key, value })
+                        .collect();
+                    output.print_records(&records)?;
                 }
             }
         }
@@ -0,0 +1,336 @@
+use super::Command;
+use crate::config::Config;
+use crate::utils::date::DateArg;
+use crate::utils::formatting::{self, AbsenceIdColor};
+use crate::utils::output::{OutputFormat, PathRecord, Record};
+use crate::utils::schedule::{WeeklySchedule, parse_hours};
+use crate::utils::selection::SelectionMenu;
+use crate::wad_data::{ContractPeriod, ContractStorage, DataStores};
+use crate::watson::FrameSources;
+use anyhow::Result;
+use chrono::{NaiveDate, Weekday};
+use clap::{Args, Parser, Subcommand};
+use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::{Alignment, Style};
+use ulid::Ulid;
+
+// UI color aliases
+type ContractDateColor = Cyan;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A contract period, as emitted in machine-readable output
+#[derive(Serialize)]
+struct ContractRow {
+    id: String,
+    valid_from: NaiveDate,
+    monday_hours: f64,
+    tuesday_hours: f64,
+    wednesday_hours: f64,
+    thursday_hours: f64,
+    friday_hours: f64,
+    saturday_hours: f64,
+    sunday_hours: f64,
+    weekly_hours: f64,
+    note: Option<String>,
+}
+
+impl Record for ContractRow {
+    const FIELDS: &'static [&'static str] = &[
+        "id",
+        "valid_from",
+        "monday_hours",
+        "tuesday_hours",
+        "wednesday_hours",
+        "thursday_hours",
+        "friday_hours",
+        "saturday_hours",
+        "sunday_hours",
+        "weekly_hours",
+        "note",
+    ];
+}
+
+impl From<&ContractPeriod> for ContractRow {
+    fn from(contract: &ContractPeriod) -> Self {
+        let [
+            monday,
+            tuesday,
+            wednesday,
+            thursday,
+            friday,
+            saturday,
+            sunday,
+        ] = WEEKDAYS.map(|weekday| contract.schedule.hours(weekday));
+        Self {
+            id: contract.id.to_string(),
+            valid_from: contract.valid_from,
+            monday_hours: monday,
+            tuesday_hours: tuesday,
+            wednesday_hours: wednesday,
+            thursday_hours: thursday,
+            friday_hours: friday,
+            saturday_hours: saturday,
+            sunday_hours: sunday,
+            weekly_hours: contract.schedule.weekly_hours(),
+            note: contract.note.clone(),
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct ContractCommand {
+    #[command(subcommand)]
+    action: ContractAction,
+}
+
+#[derive(Subcommand)]
+enum ContractAction {
+    /// List all contract periods
+    List,
+    /// Add a contract period starting on a date
+    Add {
+        /// First day the contract is valid (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
+        valid_from: DateArg,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        /// Optional note for the contract period
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Remove a contract period
+    Remove {
+        /// ULID of the contract period to remove (optional, select interactively otherwise)
+        #[arg(long, value_parser = parse_ulid)]
+        id: Option<Ulid>,
+    },
+    /// Show the path to the contracts file, or the database when using SQLite
+    Path,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct ScheduleArgs {
+    /// Weekly hours, spread evenly over Monday to Friday
+    #[arg(long, value_parser = parse_hours)]
+    hours: Option<f64>,
+    /// Hours per weekday starting on Monday (e.g. 8,8,8,8,4)
+    #[arg(long)]
+    schedule: Option<WeeklySchedule>,
+}
+
+impl ScheduleArgs {
+    fn to_schedule(&self) -> WeeklySchedule {
+        match (self.hours, self.schedule) {
+            (_, Some(schedule)) => schedule,
+            (Some(hours), None) => WeeklySchedule::spread_over_weekdays(hours),
+            // Enforced by the clap group
+            (None, None) => unreachable!("either --hours or --schedule is required"),
+        }
+    }
+}
+
+fn parse_ulid(s: &str) -> Result<Ulid, String> {
+    Ulid::from_string(s).map_err(|_| "Invalid ULID format".to_string())
+}
+
+fn format_hours(hours: f64) -> String {
+    format!("{}h", hours)
+}
+
+fn list_contracts(config: &Config, output: OutputFormat) -> Result<()> {
+    let store = DataStores::open(config.data_store)?;
+    let contracts = store.list_contracts()?;
+
+    if !output.is_text() {
+        let rows: Vec<ContractRow> = contracts.iter().map(ContractRow::from).collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
+    if contracts.is_empty() {
+        println!(
+            "No contract periods defined, the configured schedule ({}) applies to all dates",
+            format_hours(config.schedule().weekly_hours())
+        );
+        return Ok(());
+    }
+
+    let mut b = Builder::new();
+    b.push_record([
+        "ID",
+        "Valid from",
+        "Mon",
+        "Tue",
+        "Wed",
+        "Thu",
+        "Fri",
+        "Sat",
+        "Sun",
+        "Week",
+        "Note",
+    ]);
+    for contract in &contracts {
+        let schedule = contract.schedule;
+        let mut record = vec![
+            contract.id.to_string().fg::<AbsenceIdColor>().to_string(),
+            contract
+                .valid_from
+                .format("%Y-%m-%d")
+                .to_string()
+                .fg::<ContractDateColor>()
+                .to_string(),
+        ];
+        record.extend(WEEKDAYS.map(|weekday| format_hours(schedule.hours(weekday))));
+        record.push(format_hours(schedule.weekly_hours()));
+        record.push(contract.note.clone().unwrap_or_default());
+        b.push_record(record);
+    }
+
+    let mut table = b.build();
+    table
+        .with(Style::modern_rounded())
+        .modify(Columns::new(2..10), Alignment::right());
+    println!("{}", table);
+    println!(
+        "{}",
+        formatting::info_text(&format!(
+            "Dates before {} use the configured schedule ({})",
+            contracts[0].valid_from.format("%Y-%m-%d"),
+            format_hours(config.schedule().weekly_hours())
+        ))
+    );
+
+    Ok(())
+}
+
+fn add_contract(
+    valid_from: NaiveDate,
+    schedule: WeeklySchedule,
+    note: Option<String>,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let store = DataStores::open(config.data_store)?;
+
+    let contract = ContractPeriod {
+        id: Ulid::new(),
+        valid_from,
+        schedule,
+        note,
+    };
+
+    store.add_contract(contract.clone())?;
+    if !output.is_text() {
+        return Ok(output.print_records(&[ContractRow::from(&contract)])?);
+    }
+
+    println!(
+        "{} {} | {} per week from {}",
+        formatting::success_text("Added contract period:"),
+        contract.id.to_string().fg::<AbsenceIdColor>(),
+        format_hours(schedule.weekly_hours()),
+        valid_from
+            .format("%Y-%m-%d")
+            .to_string()
+            .fg::<ContractDateColor>()
+    );
+    Ok(())
+}
+
+fn remove_contract(id: Option<Ulid>, config: &Config, output: OutputFormat) -> Result<()> {
+    let store = DataStores::open(config.data_store)?;
+    let contracts = store.list_contracts()?;
+
+    let id = match id {
+        Some(id) => id,
+        None => {
+            if contracts.is_empty() {
+                return Err(anyhow::anyhow!("No contract periods defined"));
+            }
+            SelectionMenu::from_display_items(
+                "Select a contract period to remove:",
+                contracts.clone(),
+            )
+            .prompt()?
+            .id
+        }
+    };
+
+    let removed = store.remove_contract(id)?;
+    if !output.is_text() {
+        // Only the contract periods actually removed are emitted
+        let rows: Vec<ContractRow> = contracts
+            .iter()
+            .filter(|contract| removed && contract.id == id)
+            .map(ContractRow::from)
+            .collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
+    if removed {
+        println!(
+            "{} {}",
+            formatting::success_text("Removed contract period"),
+            id.to_string().fg::<AbsenceIdColor>()
+        );
+    } else {
+        println!(
+            "{} {}",
+            formatting::warning_text("No contract period found with ULID"),
+            id.to_string().fg::<AbsenceIdColor>()
+        );
+    }
+    Ok(())
+}
+
+fn show_contracts_path(config: &Config, output: OutputFormat) -> Result<()> {
+    let path = match DataStores::open(config.data_store)? {
+        DataStores::Json(store) => store.contracts_file_path(),
+        DataStores::Sqlite(store) => store.database_path(),
+    };
+    if !output.is_text() {
+        return Ok(output.print_records(&[PathRecord {
+            path: path.display().to_string(),
+        }])?);
+    }
+    println!("{}", path.display());
+    Ok(())
+}
+
+impl Command for ContractCommand {
+    fn run(
+        &self,
+        _frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        _verbose: bool,
+    ) -> Result<()> {
+        match &self.action {
+            ContractAction::List => list_contracts(config, output),
+            ContractAction::Add {
+                valid_from,
+                schedule,
+                note,
+            } => add_contract(
+                valid_from.resolve(config.timezone),
+                schedule.to_schedule(),
+                note.clone(),
+                config,
+                output,
+            ),
+            ContractAction::Remove { id } => remove_contract(*id, config, output),
+            ContractAction::Path => show_contracts_path(config, output),
+        }
+    }
+}
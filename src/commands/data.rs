@@ -0,0 +1,281 @@
+use super::Command;
+use crate::config::{Config, DataStoreKind};
+use crate::utils::formatting;
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::selection::confirm;
+use crate::wad_data::schema::{self, CURRENT_VERSION};
+use crate::wad_data::{
+    AbsenceStorage, ContractStorage, DataStores, JsonDataStore, SQLITE_SCHEMA_VERSION,
+    SqliteDataStore, default_data_dir,
+};
+use crate::watson::FrameSources;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+#[derive(Parser)]
+pub struct DataCommand {
+    #[command(subcommand)]
+    action: DataAction,
+}
+
+#[derive(Subcommand)]
+enum DataAction {
+    /// Move all absences and contract periods to the other data store
+    ///
+    /// Data is copied from the configured store, then the config is switched
+    /// to the target store. The source is left untouched and can be
+    /// removed by hand once the migration looks right.
+    MigrateStore {
+        /// Store to move the data to
+        #[arg(long, value_enum)]
+        to: DataStoreKind,
+        /// Overwrite data already in the target store
+        #[arg(long)]
+        replace: bool,
+        /// Overwrite without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Upgrade the data directory and the SQLite database to the layout of this version of wad
+    ///
+    /// This happens by itself when the data is opened, a backup of the data
+    /// directory is taken first. Use --dry-run to see what would change beforehand.
+    Migrate {
+        /// Only show the pending migrations
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// The outcome of a store migration, as emitted in machine-readable output
+#[derive(Serialize)]
+struct StoreMigrationRecord {
+    from: DataStoreKind,
+    to: DataStoreKind,
+    absences: usize,
+    contracts: usize,
+}
+
+impl Record for StoreMigrationRecord {
+    const FIELDS: &'static [&'static str] = &["from", "to", "absences", "contracts"];
+}
+
+/// A pending migration of stored data, as emitted in machine-readable output
+#[derive(Serialize)]
+struct SchemaMigrationRecord {
+    /// The JSON store's migrations change the layout of the data directory
+    store: DataStoreKind,
+    from_version: u32,
+    to_version: u32,
+    description: String,
+}
+
+impl Record for SchemaMigrationRecord {
+    const FIELDS: &'static [&'static str] = &["store", "from_version", "to_version", "description"];
+}
+
+fn migrate_schema(dry_run: bool, output: OutputFormat) -> Result<()> {
+    let data_dir =
+        default_data_dir().ok_or_else(|| anyhow::anyhow!("No data directory available"))?;
+    let version = schema::current_data_version(&data_dir)?;
+    let directory_migrations = schema::pending_migrations(&data_dir)?;
+    let database_migrations = SqliteDataStore::pending_migrations_in(&data_dir)?;
+    let records: Vec<SchemaMigrationRecord> = directory_migrations
+        .iter()
+        .map(|migration| SchemaMigrationRecord {
+            store: DataStoreKind::Json,
+            from_version: migration.from,
+            to_version: migration.from + 1,
+            description: migration.description.to_string(),
+        })
+        .chain(
+            database_migrations
+                .iter()
+                .map(|migration| SchemaMigrationRecord {
+                    store: DataStoreKind::Sqlite,
+                    from_version: migration.from,
+                    to_version: migration.from + 1,
+                    description: migration.description.to_string(),
+                }),
+        )
+        .collect();
+
+    // The backup of the data directory includes the database as it was
+    let backup = if dry_run || directory_migrations.is_empty() {
+        None
+    } else {
+        JsonDataStore::migrate_in(&data_dir)?
+    };
+    if !dry_run && !database_migrations.is_empty() {
+        SqliteDataStore::open_in(&data_dir)?;
+    }
+
+    if !output.is_text() {
+        return Ok(output.print_records(&records)?);
+    }
+
+    if records.is_empty() {
+        println!(
+            "{}",
+            formatting::info_text(&format!(
+                "The data directory is up to date (version {})",
+                version
+            ))
+        );
+        return Ok(());
+    }
+
+    for record in &records {
+        println!(
+            "  {} {} -> {} | {}",
+            record.store, record.from_version, record.to_version, record.description
+        );
+    }
+    if dry_run {
+        println!(
+            "{}",
+            formatting::info_text(&format!(
+                "{} migration(s) pending, nothing changed",
+                records.len()
+            ))
+        );
+        return Ok(());
+    }
+
+    if !directory_migrations.is_empty() {
+        println!(
+            "{} the data directory from version {} to {}",
+            formatting::success_text("Migrated"),
+            version,
+            CURRENT_VERSION
+        );
+    }
+    if !database_migrations.is_empty() {
+        println!(
+            "{} the SQLite database to schema version {}",
+            formatting::success_text("Migrated"),
+            SQLITE_SCHEMA_VERSION
+        );
+    }
+    if let Some(backup) = backup {
+        println!(
+            "{}",
+            formatting::info_text(&format!(
+                "Backup of the previous data: {}",
+                backup.display()
+            ))
+        );
+    }
+    Ok(())
+}
+
+fn migrate_store(
+    to: DataStoreKind,
+    replace: bool,
+    confirmed: bool,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let from = config.data_store;
+    if from == to {
+        return Err(anyhow::anyhow!(
+            "The {} store is already configured, there is nothing to migrate",
+            to
+        ));
+    }
+    let source = DataStores::open(from)?;
+    let target = DataStores::open(to)?;
+
+    let absences = source.all_absences()?;
+    let contracts = source.list_contracts()?;
+
+    let existing_absences = target.all_absences()?.len();
+    let existing_contracts = target.list_contracts()?.len();
+    if existing_absences > 0 || existing_contracts > 0 {
+        if !replace {
+            return Err(anyhow::anyhow!(
+                "The {} store already holds {} absence(s) and {} contract period(s), pass --replace to overwrite them",
+                to,
+                existing_absences,
+                existing_contracts
+            ));
+        }
+        if !confirmed
+            && !confirm(format!(
+                "Overwrite {} absence(s) and {} contract period(s) in the {} store?",
+                existing_absences, existing_contracts, to
+            ))?
+        {
+            eprintln!("{}", formatting::info_text("Nothing migrated"));
+            return Ok(());
+        }
+    }
+
+    target.replace_contents(&absences, &contracts)?;
+
+    // Only switch over once everything arrived
+    let copied_absences = target.all_absences()?.len();
+    let copied_contracts = target.list_contracts()?.len();
+    if copied_absences != absences.len() || copied_contracts != contracts.len() {
+        return Err(anyhow::anyhow!(
+            "The {} store holds {} absence(s) and {} contract period(s) after copying {} and {}, keeping the {} store configured",
+            to,
+            copied_absences,
+            copied_contracts,
+            absences.len(),
+            contracts.len(),
+            config.data_store
+        ));
+    }
+
+    // We can clone and write a "new" config, as the command is finished after this
+    let mut new_config = config.clone();
+    new_config.data_store = to;
+    new_config
+        .save()
+        .map_err(|e| anyhow::anyhow!("Failed to save config: {}", e))?;
+
+    if !output.is_text() {
+        return Ok(output.print_records(&[StoreMigrationRecord {
+            from,
+            to,
+            absences: absences.len(),
+            contracts: contracts.len(),
+        }])?);
+    }
+
+    println!(
+        "{} {} absence(s) and {} contract period(s) from the {} to the {} store",
+        formatting::success_text("Migrated"),
+        absences.len(),
+        contracts.len(),
+        from,
+        to
+    );
+    println!(
+        "{}",
+        formatting::info_text(&format!(
+            "Now using the {} store, the {} data was left in place",
+            to, from
+        ))
+    );
+    Ok(())
+}
+
+impl Command for DataCommand {
+    fn run(
+        &self,
+        _frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        _verbose: bool,
+    ) -> Result<()> {
+        match &self.action {
+            DataAction::MigrateStore { to, replace, yes } => {
+                migrate_store(*to, *replace, *yes, config, output)
+            }
+            DataAction::Migrate { dry_run } => migrate_schema(*dry_run, output),
+        }
+    }
+}
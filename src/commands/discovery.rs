@@ -5,8 +5,8 @@ use std::fmt;
 use super::{Command as CommandTrait, Commands};
 use crate::{
     config::Config,
-    utils::{formatting, selection::SelectionMenu},
-    watson::WatsonClient,
+    utils::{formatting, output::OutputFormat, selection::SelectionMenu},
+    watson::FrameSources,
 };
 
 /// Metadata for a command extracted from clap introspection
@@ -42,8 +42,9 @@ fn get_all_commands() -> Vec<CommandMetadata> {
 
 /// Show a command selection menu for all commands and execute the selected one.
 pub fn show_command_selection_menu(
-    watson_client: &WatsonClient,
+    frame_source: &FrameSources,
     config: &Config,
+    output: OutputFormat,
     verbose: bool,
 ) -> Result<()> {
     println!(
@@ -67,7 +68,7 @@ pub fn show_command_selection_menu(
             let args = vec![program_name, command_metadata.name.clone()];
             let matches = Commands::command().try_get_matches_from(args)?;
             let command = Commands::from_arg_matches(&matches)?;
-            command.run(watson_client, config, verbose)
+            command.run(frame_source, config, output, verbose)
         }
         Err(_) => {
             println!("{}", formatting::info_text("Selection cancelled"));
@@ -0,0 +1,449 @@
+use super::Command;
+use crate::config::{Config, DataStoreKind, FrameSourceKind};
+use crate::utils::date::{DateRange, hours_to_duration};
+use crate::utils::doctor::{self, Area, Finding, Severity};
+use crate::utils::formatting;
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::wad_data::schema;
+use crate::wad_data::{
+    AbsenceRecord, AbsenceStorage, ContractStorage, DataStores, JsonDataStore,
+    SQLITE_SCHEMA_VERSION, SqliteDataStore, default_data_dir,
+};
+use crate::watson::{FrameSource, FrameSources, LogQuery, WatsonClient};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use clap::Parser;
+use serde::Serialize;
+
+#[derive(Parser)]
+pub struct DoctorCommand {
+    /// Apply the fixes that can't lose data, like moving misplaced absences to their day
+    #[arg(long)]
+    fix: bool,
+    /// Number of days back to check frames and daily totals for
+    #[arg(long, default_value = "365")]
+    days: u32,
+    /// Warn about frames that have been running for longer than this many hours
+    #[arg(long, default_value = "12")]
+    max_active_hours: f64,
+}
+
+/// A finding of the doctor, as emitted in machine-readable output
+#[derive(Serialize)]
+struct FindingRecord {
+    area: Area,
+    severity: Severity,
+    message: String,
+    suggestion: Option<String>,
+    fixable: bool,
+    fixed: bool,
+}
+
+impl Record for FindingRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "area",
+        "severity",
+        "message",
+        "suggestion",
+        "fixable",
+        "fixed",
+    ];
+}
+
+fn check_watson(frame_source: &FrameSources, config: &Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    let client = WatsonClient::new();
+    match client
+        .get_version()
+        .and_then(|version| Ok((version, client.get_path()?)))
+    {
+        Ok((version, path)) => findings.push(Finding::ok(
+            Area::Watson,
+            format!(
+                "Watson {}.{}.{} at {}",
+                version.major, version.minor, version.patch, path
+            ),
+        )),
+        Err(e) => {
+            let message = format!("The Watson CLI isn't usable: {}", e);
+            findings.push(match config.frame_source {
+                FrameSourceKind::Cli => Finding::error(
+                    Area::Watson,
+                    message,
+                    "Install Watson and make sure it's in your PATH, or read Watson's data files directly with `wad config set frame_source files`",
+                ),
+                FrameSourceKind::Files => Finding::warning(
+                    Area::Watson,
+                    message,
+                    "wad reads Watson's data files, but tracking time still needs the Watson CLI",
+                ),
+            });
+        }
+    }
+
+    if let FrameSources::Files(files) = frame_source {
+        let dir = files.watson_dir();
+        findings.push(if dir.is_dir() {
+            Finding::ok(
+                Area::Watson,
+                format!("Reading frames from {}", dir.display()),
+            )
+        } else {
+            Finding::error(
+                Area::Watson,
+                format!("Watson's data directory {} doesn't exist", dir.display()),
+                "Set WATSON_DIR to the directory Watson keeps its frames in, or use the Watson CLI with `wad config set frame_source cli`",
+            )
+        });
+    }
+
+    findings
+}
+
+fn check_config(config: &Config) -> Vec<Finding> {
+    let findings = doctor::check_config(config);
+    if !findings.is_empty() {
+        return findings;
+    }
+
+    vec![Finding::ok(
+        Area::Config,
+        format!(
+            "Weekly target of {} hours, daily thresholds {} < {} < {}",
+            config.schedule().weekly_hours(),
+            config.daily_worktime_low,
+            config.daily_worktime_medium,
+            config.daily_worktime_good
+        ),
+    )]
+}
+
+/// Open the configured store without changing the data in it
+///
+/// The JSON store is neither migrated nor is an unfinished move finished,
+/// `check_files` reports both and `--fix` applies them. Opening a SQLite
+/// database migrates it, so one with pending migrations is left closed.
+fn inspect_store(kind: DataStoreKind) -> Result<DataStores, Box<Finding>> {
+    let cant_open = |error: String| {
+        Box::new(Finding::error(
+            Area::Data,
+            format!("The {} store can't be opened: {}", kind, error),
+            match kind {
+                DataStoreKind::Json => "Check the data directory shown by `wad absence path`",
+                DataStoreKind::Sqlite => "Check the database shown by `wad absence path`",
+            },
+        ))
+    };
+    let data_dir =
+        default_data_dir().ok_or_else(|| cant_open("No data directory available".to_string()))?;
+
+    match kind {
+        DataStoreKind::Json => JsonDataStore::inspect_in(&data_dir)
+            .map(DataStores::Json)
+            .map_err(|e| cant_open(e.to_string())),
+        DataStoreKind::Sqlite => {
+            let pending = SqliteDataStore::pending_migrations_in(&data_dir)
+                .map_err(|e| cant_open(e.to_string()))?;
+            if let Some(first) = pending.first() {
+                return Err(Box::new(Finding::warning(
+                    Area::Data,
+                    format!(
+                        "The SQLite database is at schema version {} and needs migrating to version {}",
+                        first.from, SQLITE_SCHEMA_VERSION
+                    ),
+                    "Migrate it with `wad data migrate`, a backup is taken first",
+                )));
+            }
+            SqliteDataStore::open_in(&data_dir)
+                .map(DataStores::Sqlite)
+                .map_err(|e| cant_open(e.to_string()))
+        }
+    }
+}
+
+/// Check the stored data, returning the findings and every absence that could be read
+fn check_data(store: &DataStores) -> (Vec<Finding>, Vec<AbsenceRecord>) {
+    let mut findings = Vec::new();
+
+    if let Some(data_dir) = default_data_dir()
+        && let Ok(version) = schema::current_data_version(&data_dir)
+    {
+        findings.push(Finding::ok(
+            Area::Data,
+            format!(
+                "Data directory {} at version {}",
+                data_dir.display(),
+                version
+            ),
+        ));
+    }
+
+    let records = match store {
+        DataStores::Json(store) => match store.check_files() {
+            Ok(check) => {
+                findings.extend(doctor::check_files(&check.issues));
+                Ok(check.records)
+            }
+            Err(e) => Err(e.to_string()),
+        },
+        DataStores::Sqlite(_) => store.all_absences().map_err(|e| e.to_string()),
+    };
+    let records = match records {
+        Ok(records) => records,
+        Err(e) => {
+            findings.push(Finding::error(
+                Area::Data,
+                format!("Absences can't be read: {}", e),
+                "Check the permissions of the data directory",
+            ));
+            return (findings, Vec::new());
+        }
+    };
+
+    let absence_findings = doctor::check_absences(&records);
+    if absence_findings.is_empty() && !findings.iter().any(Finding::is_problem) {
+        findings.push(Finding::ok(
+            Area::Data,
+            format!("{} absence(s) without problems", records.len()),
+        ));
+    }
+    findings.extend(absence_findings);
+
+    if let Err(e) = store.list_contracts() {
+        findings.push(Finding::error(
+            Area::Data,
+            format!("Contract periods can't be read: {}", e),
+            "Correct the contract periods by hand, `wad contract path` shows where they are stored",
+        ));
+    }
+
+    (findings, records)
+}
+
+impl DoctorCommand {
+    fn check_frames(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        absences: &[AbsenceRecord],
+    ) -> Vec<Finding> {
+        let timezone = config.timezone;
+        let today = timezone.today();
+        let range = DateRange {
+            from: today - Duration::days(i64::from(self.days.max(1)) - 1),
+            to: today,
+        };
+
+        let frames = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let query = LogQuery::range(&range, timezone).with_current();
+            frame_source.log(query)
+        };
+        let frames = match frames {
+            Ok(frames) => frames,
+            Err(e) => {
+                return vec![Finding::error(
+                    Area::Frames,
+                    format!("Frames can't be read: {}", e),
+                    "Fix the Watson setup reported above",
+                )];
+            }
+        };
+
+        let mut findings = doctor::check_frames(
+            &frames,
+            timezone,
+            Utc::now(),
+            hours_to_duration(self.max_active_hours),
+        );
+        let absences: Vec<AbsenceRecord> = absences
+            .iter()
+            .filter(|record| range.contains(record.date))
+            .cloned()
+            .collect();
+        findings.extend(doctor::check_day_totals(&frames, &absences, timezone));
+
+        if findings.is_empty() {
+            findings.push(Finding::ok(
+                Area::Frames,
+                format!(
+                    "{} frame(s) in {} without problems",
+                    frames.frames.len(),
+                    range
+                ),
+            ));
+        }
+        findings
+    }
+}
+
+/// Apply the repairs of the findings, returning which ones were fixed
+fn apply_repairs(store: &DataStores, findings: &[Finding]) -> Vec<bool> {
+    findings
+        .iter()
+        .map(|finding| match (&finding.repair, store) {
+            (Some(issue), DataStores::Json(store)) => match store.repair(issue) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!(
+                        "{}",
+                        formatting::warning_text(&format!(
+                            "Could not fix \"{}\": {}",
+                            finding.message, e
+                        ))
+                    );
+                    false
+                }
+            },
+            _ => false,
+        })
+        .collect()
+}
+
+fn print_report(findings: &[Finding], fixed: &[bool]) {
+    let mut area = None;
+    for (finding, fixed) in findings.iter().zip(fixed) {
+        if area != Some(finding.area) {
+            println!("{}", formatting::header_text(&finding.area.to_string()));
+            area = Some(finding.area);
+        }
+
+        if *fixed {
+            println!(
+                "  {} {}",
+                formatting::success_text("✓ Fixed:"),
+                finding.message
+            );
+            continue;
+        }
+        let marker = match finding.severity {
+            Severity::Ok => formatting::success_text("✓"),
+            Severity::Warning => formatting::warning_text("!"),
+            Severity::Error => formatting::error_text("✗"),
+        };
+        println!("  {} {}", marker, finding.message);
+        if let Some(suggestion) = &finding.suggestion {
+            let fixable = if finding.repair.is_some() {
+                " (fixed by `wad doctor --fix`)"
+            } else {
+                ""
+            };
+            println!(
+                "    {}",
+                formatting::info_text(&format!("→ {}{}", suggestion, fixable))
+            );
+        }
+    }
+
+    let open: Vec<&Finding> = findings
+        .iter()
+        .zip(fixed)
+        .filter(|(finding, fixed)| finding.is_problem() && !**fixed)
+        .map(|(finding, _)| finding)
+        .collect();
+    let fixed_count = fixed.iter().filter(|fixed| **fixed).count();
+
+    println!();
+    if fixed_count > 0 {
+        println!(
+            "{}",
+            formatting::success_text(&format!("Fixed {} problem(s)", fixed_count))
+        );
+    }
+    if open.is_empty() {
+        println!("{}", formatting::success_text("No problems found"));
+        return;
+    }
+
+    let errors = open
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    let summary = format!(
+        "{} error(s) and {} warning(s) found",
+        errors,
+        open.len() - errors
+    );
+    println!(
+        "{}",
+        if errors > 0 {
+            formatting::error_text(&summary)
+        } else {
+            formatting::warning_text(&summary)
+        }
+    );
+    let fixable = open
+        .iter()
+        .filter(|finding| finding.repair.is_some())
+        .count();
+    if fixable > 0 {
+        println!(
+            "{}",
+            formatting::info_text(&format!(
+                "{} of them can be fixed with `wad doctor --fix`",
+                fixable
+            ))
+        );
+    }
+}
+
+impl Command for DoctorCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running doctor command in verbose mode")
+            );
+        }
+
+        let mut findings = check_watson(frame_source, config);
+        findings.extend(check_config(config));
+
+        let store = inspect_store(config.data_store);
+        let absences = match &store {
+            Ok(store) => {
+                let (data_findings, absences) = check_data(store);
+                findings.extend(data_findings);
+                absences
+            }
+            Err(finding) => {
+                findings.push(*finding.clone());
+                Vec::new()
+            }
+        };
+
+        findings.extend(self.check_frames(frame_source, config, &absences));
+
+        let fixed = match &store {
+            Ok(store) if self.fix => apply_repairs(store, &findings),
+            _ => vec![false; findings.len()],
+        };
+
+        if !output.is_text() {
+            let records: Vec<FindingRecord> = findings
+                .into_iter()
+                .zip(fixed)
+                .map(|(finding, fixed)| FindingRecord {
+                    area: finding.area,
+                    severity: finding.severity,
+                    fixable: finding.repair.is_some(),
+                    message: finding.message,
+                    suggestion: finding.suggestion,
+                    fixed,
+                })
+                .collect();
+            return Ok(output.print_records(&records)?);
+        }
+
+        print_report(&findings, &fixed);
+        Ok(())
+    }
+}
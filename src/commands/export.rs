@@ -0,0 +1,172 @@
+use super::Command;
+use super::worktime::DateRangeArgs;
+use crate::config::Config;
+use crate::utils::date::{DateRange, Month};
+use crate::utils::formatting;
+use crate::utils::ical::{EventSpan, IcsEvent, write_calendar};
+use crate::utils::output::{OutputFormat, PathRecord};
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::wad_data::{AbsenceRecord, AbsenceStorage, DataStores};
+use crate::watson::frame::Frame;
+use crate::watson::{FrameSource, FrameSources, LogQuery};
+use anyhow::Result;
+use chrono::Utc;
+use clap::{Parser, Subcommand};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+pub struct ExportCommand {
+    #[command(subcommand)]
+    format: ExportFormat,
+}
+
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// Write frames and absences of a range as an iCalendar (.ics) file
+    ///
+    /// Frames become timed events named after their project and tags,
+    /// absences become all-day events. Event UIDs stay the same across
+    /// exports, so calendar apps update events instead of duplicating them.
+    Ics {
+        #[command(flatten)]
+        range: DateRangeArgs,
+        /// File to write to (default: stdout)
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+        /// Leave out Watson frames, e.g. to share planned vacations
+        #[arg(long, conflicts_with = "no_absences")]
+        no_frames: bool,
+        /// Leave out absences
+        #[arg(long)]
+        no_absences: bool,
+    },
+}
+
+/// Name a frame like `watson` does: the project followed by its tags
+fn frame_event(frame: &Frame) -> IcsEvent {
+    let mut summary = frame.project.clone();
+    for tag in &frame.tags {
+        summary.push_str(&format!(" +{}", tag));
+    }
+
+    IcsEvent {
+        uid: format!("{}@watson", frame.id),
+        summary,
+        description: None,
+        span: EventSpan::Timed {
+            start: frame.start,
+            end: frame.end(),
+        },
+    }
+}
+
+fn absence_event(record: &AbsenceRecord) -> IcsEvent {
+    let label = record.absence_type.label();
+    IcsEvent {
+        // Imported records share their event's UID, so the ULID is used instead
+        uid: format!("{}@wad", record.id),
+        summary: match &record.note {
+            Some(note) => format!("{}: {}", label, note),
+            None => label,
+        },
+        description: Some(format!("{} hours", record.hours)),
+        span: EventSpan::AllDay(DateRange::day(record.date)),
+    }
+}
+
+fn export_ics(
+    range: DateRange,
+    file: Option<&PathBuf>,
+    include_frames: bool,
+    include_absences: bool,
+    frame_source: &FrameSources,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut events = Vec::new();
+
+    if include_frames {
+        let frames = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let query = LogQuery::range(&range, config.timezone).with_partial_frames();
+            frame_source.log(query)?
+        };
+        // A running frame has no end yet, so it can't be placed in a calendar
+        events.extend(
+            frames
+                .frames
+                .iter()
+                .filter(|frame| !frame.is_active())
+                .map(frame_event),
+        );
+    }
+
+    if include_absences {
+        let store = DataStores::open(config.data_store)?;
+        events.extend(store.get_absences(&range)?.iter().map(absence_event));
+    }
+
+    let stamp = Utc::now();
+    let Some(path) = file else {
+        write_calendar(io::stdout().lock(), &events, stamp)?;
+        return Ok(());
+    };
+
+    let file = File::create(path)
+        .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", path.display(), e))?;
+    write_calendar(BufWriter::new(file), &events, stamp)?;
+
+    if !output.is_text() {
+        return Ok(output.print_records(&[PathRecord {
+            path: path.display().to_string(),
+        }])?);
+    }
+
+    println!(
+        "{} {} event(s) for {} to {}",
+        formatting::success_text("Exported"),
+        events.len(),
+        range,
+        path.display()
+    );
+    Ok(())
+}
+
+impl Command for ExportCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        _verbose: bool,
+    ) -> Result<()> {
+        match &self.format {
+            ExportFormat::Ics {
+                range,
+                file,
+                no_frames,
+                no_absences,
+            } => {
+                // Without a range the current month is exported
+                let range = match range.resolve(config.timezone).map_err(anyhow::Error::msg)? {
+                    Some(range) => range,
+                    None => {
+                        let month = Month::current(config.timezone);
+                        DateRange::months(&month, &month)
+                    }
+                };
+                export_ics(
+                    range,
+                    file.as_ref(),
+                    !no_frames,
+                    !no_absences,
+                    frame_source,
+                    config,
+                    output,
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use super::Command;
+use crate::config::Config;
+use crate::utils::date::DateRange;
+use crate::utils::formatting::{self, AbsenceHoursColor, AbsenceTypeFormat};
+use crate::utils::holidays::{COUNTRIES, Holiday, HolidayCalendar, HolidayRegion};
+use crate::utils::output::{AbsenceRow, OutputFormat, Record};
+use crate::utils::schedule::ScheduleHistory;
+use crate::utils::selection::confirm;
+use crate::wad_data::{AbsenceStorage, ContractStorage, DataStores};
+use crate::watson::FrameSources;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use clap::{Parser, Subcommand};
+use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::{Alignment, Style};
+
+// UI color aliases
+type HolidayDateColor = Cyan;
+
+#[derive(Parser)]
+pub struct HolidayCommand {
+    #[command(subcommand)]
+    action: HolidayAction,
+}
+
+#[derive(Subcommand)]
+enum HolidayAction {
+    /// List the public holidays of the configured region
+    List {
+        /// Calendar year to list (default: current year)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// List the countries and regions with a built-in holiday calendar
+    Regions,
+    /// Store the public holidays of a year as holiday absences
+    ///
+    /// Holidays are applied to targets without being stored. Storing them
+    /// allows editing single ones, e.g. to give a half day a different
+    /// number of hours. Days already having a stored holiday are skipped.
+    Materialize {
+        /// Calendar year to store (default: current year)
+        #[arg(long)]
+        year: Option<i32>,
+        /// Store the holidays without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// A public holiday, as emitted in machine-readable output
+#[derive(Serialize)]
+struct HolidayRecord {
+    date: NaiveDate,
+    name: String,
+    day_share: f64,
+    /// Hours credited on that day, zero on days off
+    hours: f64,
+}
+
+impl Record for HolidayRecord {
+    const FIELDS: &'static [&'static str] = &["date", "name", "day_share", "hours"];
+}
+
+/// A supported holiday region, as emitted in machine-readable output
+#[derive(Serialize)]
+struct RegionRecord {
+    code: String,
+    name: String,
+}
+
+impl Record for RegionRecord {
+    const FIELDS: &'static [&'static str] = &["code", "name"];
+}
+
+fn holiday_region(calendar: &HolidayCalendar) -> Result<HolidayRegion> {
+    calendar.region.ok_or_else(|| {
+        anyhow::anyhow!(
+            "No holiday region configured, set one with `wad config set holiday_region DE-BY` (see `wad holiday regions`)"
+        )
+    })
+}
+
+fn format_share(share: f64) -> &'static str {
+    if share < 1.0 { "half day" } else { "full day" }
+}
+
+fn list_holidays(range: DateRange, config: &Config, output: OutputFormat) -> Result<()> {
+    let calendar = config.holiday_calendar();
+    let region = holiday_region(&calendar)?;
+
+    let store = DataStores::open(config.data_store)?;
+    let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+    let credited_hours = |holiday: &Holiday| {
+        schedules
+            .schedule_on(holiday.date)
+            .hours(holiday.date.weekday())
+            * holiday.share
+    };
+    let holidays = calendar.holidays(&range);
+
+    if !output.is_text() {
+        let records: Vec<HolidayRecord> = holidays
+            .iter()
+            .map(|holiday| HolidayRecord {
+                date: holiday.date,
+                name: holiday.name.to_string(),
+                day_share: holiday.share,
+                hours: credited_hours(holiday),
+            })
+            .collect();
+        return Ok(output.print_records(&records)?);
+    }
+
+    println!(
+        "{}",
+        formatting::header_text(&format!(
+            "Public holidays in {} ({}), {}",
+            region.name(),
+            region,
+            range
+        ))
+    );
+
+    let mut b = Builder::new();
+    b.push_record(["Date", "Holiday", "Day", "Hours"]);
+    for holiday in &holidays {
+        let hours = credited_hours(holiday);
+        b.push_record([
+            holiday
+                .date
+                .format("%a %Y-%m-%d")
+                .to_string()
+                .fg::<HolidayDateColor>()
+                .to_string(),
+            holiday.name.to_string(),
+            format_share(holiday.share).to_string(),
+            if hours > 0.0 {
+                hours.to_string()
+            } else {
+                "day off".dimmed().to_string()
+            },
+        ]);
+    }
+    let mut table = b.build();
+    table
+        .with(Style::modern_rounded())
+        .modify(Columns::new(3..4), Alignment::right());
+    println!("{}", table);
+
+    if !config.holiday_half_days {
+        println!(
+            "{}",
+            formatting::info_text("Half days are left out, see `wad config get holiday_half_days`")
+        );
+    }
+
+    Ok(())
+}
+
+fn list_regions(output: OutputFormat) -> Result<()> {
+    let records: Vec<RegionRecord> = COUNTRIES
+        .iter()
+        .flat_map(|country| {
+            let country_record = RegionRecord {
+                code: country.code.to_string(),
+                name: format!("{} (nationwide holidays only)", country.name),
+            };
+            let region_records = country.regions.iter().map(|(code, name)| RegionRecord {
+                code: format!("{}-{}", country.code, code),
+                name: format!("{}, {}", name, country.name),
+            });
+            std::iter::once(country_record).chain(region_records)
+        })
+        .collect();
+
+    if !output.is_text() {
+        return Ok(output.print_records(&records)?);
+    }
+
+    let mut b = Builder::new();
+    b.push_record(["Code", "Region"]);
+    for record in &records {
+        b.push_record([record.code.clone(), record.name.clone()]);
+    }
+    let mut table = b.build();
+    table.with(Style::modern_rounded());
+    println!("{}", table);
+
+    Ok(())
+}
+
+fn materialize_holidays(
+    range: DateRange,
+    config: &Config,
+    confirmed: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let calendar = config.holiday_calendar();
+    let region = holiday_region(&calendar)?;
+
+    let store = DataStores::open(config.data_store)?;
+    let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+    let records = calendar.missing_absences(&range, &schedules, &store.get_absences(&range)?);
+
+    if records.is_empty() {
+        eprintln!(
+            "{}",
+            formatting::info_text(&format!(
+                "No holidays left to store for {} in {}",
+                region, range
+            ))
+        );
+        if !output.is_text() {
+            output.print_records::<AbsenceRow>(&[])?;
+        }
+        return Ok(());
+    }
+
+    // The preview goes to stderr, so machine-readable output stays clean
+    for record in &records {
+        eprintln!(
+            "  {} | {} | {} | {}",
+            record
+                .date
+                .format("%a %Y-%m-%d")
+                .to_string()
+                .fg::<HolidayDateColor>(),
+            format!("{} hours", record.hours).fg::<AbsenceHoursColor>(),
+            record.absence_type.to_string_colored(),
+            record.note.clone().unwrap_or_default()
+        );
+    }
+
+    if !confirmed
+        && !confirm(format!(
+            "Store {} holiday(s) for {}?",
+            records.len(),
+            region
+        ))?
+    {
+        eprintln!("{}", formatting::info_text("Nothing stored"));
+        return Ok(());
+    }
+
+    for record in &records {
+        store.add_absence(record.clone())?;
+    }
+
+    if !output.is_text() {
+        let rows: Vec<AbsenceRow> = records.iter().map(AbsenceRow::from).collect();
+        return Ok(output.print_records(&rows)?);
+    }
+
+    println!(
+        "{} {} holiday(s) for {} in {}",
+        formatting::success_text("Stored"),
+        records.len(),
+        region,
+        range
+    );
+    Ok(())
+}
+
+impl Command for HolidayCommand {
+    fn run(
+        &self,
+        _frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        _verbose: bool,
+    ) -> Result<()> {
+        let this_year = config.timezone.today().year();
+
+        match &self.action {
+            HolidayAction::List { year } => list_holidays(
+                DateRange::year(year.unwrap_or(this_year)).map_err(anyhow::Error::msg)?,
+                config,
+                output,
+            ),
+            HolidayAction::Regions => list_regions(output),
+            HolidayAction::Materialize { year, yes } => materialize_holidays(
+                DateRange::year(year.unwrap_or(this_year)).map_err(anyhow::Error::msg)?,
+                config,
+                *yes,
+                output,
+            ),
+        }
+    }
+}
@@ -2,10 +2,20 @@ use crate::{
     commands::{
         absence::AbsenceCommand,
         config::ConfigCommand,
-        worktime::{WorktimeTodayCommand, WorktimeWeeklyCommand},
+        contract::ContractCommand,
+        data::DataCommand,
+        doctor::DoctorCommand,
+        export::ExportCommand,
+        holiday::HolidayCommand,
+        report::ReportCommand,
+        worktime::{
+            WorktimeBalanceCommand, WorktimeComplianceCommand, WorktimeMonthlyCommand,
+            WorktimeTodayCommand, WorktimeWeeklyCommand, WorktimeYearlyCommand,
+        },
     },
     config::Config,
-    watson::WatsonClient,
+    utils::output::OutputFormat,
+    watson::FrameSources,
 };
 use anyhow::Result;
 use clap::Parser;
@@ -13,12 +23,24 @@ use enum_dispatch::enum_dispatch;
 
 pub mod absence;
 pub mod config;
+pub mod contract;
+pub mod data;
 pub mod discovery;
+pub mod doctor;
+pub mod export;
+pub mod holiday;
+pub mod report;
 pub mod worktime;
 
 #[enum_dispatch]
 pub trait Command {
-    fn run(&self, watson_client: &WatsonClient, config: &Config, verbose: bool) -> Result<()>;
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()>;
 }
 
 #[derive(Parser)]
@@ -30,10 +52,40 @@ pub enum Commands {
     /// Configuration management
     #[command(name = "config")]
     Config(ConfigCommand),
+    /// Contract period management
+    #[command(name = "contract")]
+    Contract(ContractCommand),
+    /// Data store management
+    #[command(name = "data")]
+    Data(DataCommand),
+    /// Check Watson, the config and the stored data for problems
+    #[command(name = "doctor")]
+    Doctor(DoctorCommand),
+    /// Export frames and absences to other tools
+    #[command(name = "export")]
+    Export(ExportCommand),
+    /// Public holiday calendars
+    #[command(name = "holiday")]
+    Holiday(HolidayCommand),
+    /// Show tracked time per project and tag over any range
+    #[command(name = "report")]
+    Report(ReportCommand),
+    /// Show overtime/flextime balance
+    #[command(name = "worktime:balance")]
+    WorktimeBalance(WorktimeBalanceCommand),
+    /// Check work time against break, maximum and rest rules
+    #[command(name = "worktime:compliance")]
+    WorktimeCompliance(WorktimeComplianceCommand),
+    /// Show monthly work time calendar
+    #[command(name = "worktime:monthly")]
+    WorktimeMonthly(WorktimeMonthlyCommand),
     /// Show today's work time
     #[command(name = "worktime:today")]
     WorktimeToday(WorktimeTodayCommand),
     /// Show weekly work time overview
     #[command(name = "worktime:weekly")]
     WorktimeWeekly(WorktimeWeeklyCommand),
+    /// Show yearly work time heatmap
+    #[command(name = "worktime:yearly")]
+    WorktimeYearly(WorktimeYearlyCommand),
 }
@@ -0,0 +1,223 @@
+use super::Command;
+use super::worktime::DateRangeArgs;
+use crate::config::Config;
+use crate::utils::date::DateRange;
+use crate::utils::formatting::{self, DurationFormat};
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::report::{ReportGrouping, ReportNode, UNTAGGED, build_tree};
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::watson::frame::FrameFilter;
+use crate::watson::{FrameSource, FrameSources, LogQuery};
+use anyhow::Result;
+use chrono::{Datelike, Duration};
+use clap::{Args, Parser};
+use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
+
+/// Project and tag selection of the frames to report on
+#[derive(Args)]
+struct FrameFilterArgs {
+    /// Only include frames of this project (can be given several times)
+    #[arg(long = "project", value_name = "PROJECT")]
+    projects: Vec<String>,
+    /// Leave out frames of this project (can be given several times)
+    #[arg(long = "exclude-project", value_name = "PROJECT")]
+    exclude_projects: Vec<String>,
+    /// Only include frames with this tag (can be given several times)
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+    /// Leave out frames with this tag (can be given several times)
+    #[arg(long = "exclude-tag", value_name = "TAG")]
+    exclude_tags: Vec<String>,
+}
+
+impl From<&FrameFilterArgs> for FrameFilter {
+    fn from(args: &FrameFilterArgs) -> Self {
+        Self {
+            projects: args.projects.clone(),
+            exclude_projects: args.exclude_projects.clone(),
+            tags: args.tags.clone(),
+            exclude_tags: args.exclude_tags.clone(),
+            window: None,
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct ReportCommand {
+    #[command(flatten)]
+    range: DateRangeArgs,
+    #[command(flatten)]
+    filter: FrameFilterArgs,
+    /// Group by tag first and split each tag up by project, e.g. for totals per ticket
+    ///
+    /// Frames with several tags count for each of them, so tags can add up to more than 100%.
+    #[arg(long)]
+    by_tag: bool,
+}
+
+/// Time of a project and tag, as emitted in machine-readable output
+#[derive(Serialize)]
+struct ReportRecord {
+    project: String,
+    /// Empty for frames without tags
+    tag: Option<String>,
+    minutes: i64,
+    /// Share of all time in the report
+    percent: f64,
+}
+
+impl Record for ReportRecord {
+    const FIELDS: &'static [&'static str] = &["project", "tag", "minutes", "percent"];
+}
+
+fn round_percent(percent: f64) -> f64 {
+    (percent * 10.0).round() / 10.0
+}
+
+fn to_records(tree: &[ReportNode], grouping: ReportGrouping, total: Duration) -> Vec<ReportRecord> {
+    let tag = |name: &str| (name != UNTAGGED).then(|| name.to_string());
+
+    tree.iter()
+        .flat_map(|node| {
+            node.children.iter().map(move |child| {
+                let (project, tag) = match grouping {
+                    ReportGrouping::ProjectThenTag => (node.name.clone(), tag(&child.name)),
+                    ReportGrouping::TagThenProject => (child.name.clone(), tag(&node.name)),
+                };
+                ReportRecord {
+                    project,
+                    tag,
+                    minutes: child.duration.num_minutes(),
+                    percent: round_percent(child.percent_of(total)),
+                }
+            })
+        })
+        .collect()
+}
+
+fn print_tree(tree: &[ReportNode], total: Duration) {
+    // Tree prefixes are three characters wide
+    let width = tree
+        .iter()
+        .flat_map(|node| {
+            std::iter::once(node.name.chars().count()).chain(
+                node.children
+                    .iter()
+                    .map(|child| child.name.chars().count() + 3),
+            )
+        })
+        .chain(std::iter::once("Total".len()))
+        .max()
+        .unwrap_or_default();
+
+    let line = |label: String, colored: String, duration: Duration, percent: f64| {
+        let padding = " ".repeat(width - label.chars().count());
+        println!(
+            "{}{}  {}  {:>5.1}%",
+            colored,
+            padding,
+            duration.to_string_hhmm().fg::<Blue>(),
+            percent
+        );
+    };
+
+    for node in tree {
+        line(
+            node.name.clone(),
+            node.name.fg::<Cyan>().to_string(),
+            node.duration,
+            node.percent_of(total),
+        );
+        for (index, child) in node.children.iter().enumerate() {
+            let prefix = if index + 1 == node.children.len() {
+                "└─ "
+            } else {
+                "├─ "
+            };
+            let label = format!("{}{}", prefix, child.name);
+            line(
+                label.clone(),
+                label,
+                child.duration,
+                child.percent_of(total),
+            );
+        }
+    }
+
+    println!();
+    let label = "Total".to_string();
+    line(label.clone(), formatting::header_text(&label), total, 100.0);
+}
+
+impl Command for ReportCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running report command in verbose mode")
+            );
+        }
+
+        let timezone = config.timezone;
+        let today = timezone.today();
+        let range = self
+            .range
+            .resolve(timezone)
+            .map_err(anyhow::Error::msg)?
+            .unwrap_or(DateRange {
+                from: today.with_day(1).unwrap(),
+                to: today,
+            });
+
+        let frames = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let query = LogQuery::range(&range, timezone)
+                .with_current()
+                .with_partial_frames();
+            // Partial frames only count with their part inside the range
+            let filter = FrameFilter {
+                window: Some(query.span()),
+                ..FrameFilter::from(&self.filter)
+            };
+            frame_source.log(query)?.filter(&filter)
+        };
+
+        let grouping = if self.by_tag {
+            ReportGrouping::TagThenProject
+        } else {
+            ReportGrouping::ProjectThenTag
+        };
+        let tree = build_tree(&frames, grouping);
+        let total = frames.total_duration();
+
+        if !output.is_text() {
+            return Ok(output.print_records(&to_records(&tree, grouping, total))?);
+        }
+
+        if tree.is_empty() {
+            println!(
+                "{}",
+                formatting::info_text(&format!("No time tracked in {}", range))
+            );
+            return Ok(());
+        }
+
+        let title = match grouping {
+            ReportGrouping::ProjectThenTag => "Time per project and tag",
+            ReportGrouping::TagThenProject => "Time per tag and project",
+        };
+        println!(
+            "{}",
+            formatting::header_text(&format!("{} in {}", title, range))
+        );
+        print_tree(&tree, total);
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use super::super::Command;
+use crate::config::Config;
+use crate::utils::balance::{BalanceDay, BalanceEntry, BalanceLedger};
+use crate::utils::date::{DateRange, DayTimeBreakdown, Week, WeeklyWorktime, hours_to_duration};
+use crate::utils::formatting::{self, DurationFormat, WeekFormat};
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::schedule::ScheduleHistory;
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::wad_data::{AbsenceStorage, ContractStorage, DataStores, group_by_date};
+use crate::watson::{FrameSource, FrameSources, LogQuery};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::{Alignment, Style};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LedgerPeriod {
+    Week,
+    Month,
+}
+
+/// A ledger period, as emitted in machine-readable output
+#[derive(Serialize)]
+struct BalanceRecord {
+    from: NaiveDate,
+    to: NaiveDate,
+    worked_minutes: i64,
+    expected_minutes: i64,
+    delta_minutes: i64,
+    /// Running balance at the end of the period, including the initial balance
+    balance_minutes: i64,
+}
+
+impl Record for BalanceRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "from",
+        "to",
+        "worked_minutes",
+        "expected_minutes",
+        "delta_minutes",
+        "balance_minutes",
+    ];
+}
+
+impl From<&BalanceEntry> for BalanceRecord {
+    fn from(entry: &BalanceEntry) -> Self {
+        Self {
+            from: entry.from,
+            to: entry.to,
+            worked_minutes: entry.worked.num_minutes(),
+            expected_minutes: entry.expected.num_minutes(),
+            delta_minutes: entry.delta().num_minutes(),
+            balance_minutes: entry.balance.num_minutes(),
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct WorktimeBalanceCommand {
+    /// Period to summarize the ledger by
+    #[arg(long, value_enum, default_value = "month")]
+    period: LedgerPeriod,
+    /// Include today in the balance (by default only completed days are counted)
+    #[arg(long)]
+    include_today: bool,
+}
+
+impl WorktimeBalanceCommand {
+    fn period_label(&self, entry: &BalanceEntry) -> String {
+        match self.period {
+            LedgerPeriod::Week => Week::containing(entry.from).to_string_long(),
+            LedgerPeriod::Month => entry.from.format("%B %Y").to_string(),
+        }
+    }
+
+    fn worked_label(&self, entry: &BalanceEntry, schedules: &ScheduleHistory) -> String {
+        match self.period {
+            LedgerPeriod::Week => WeeklyWorktime::from(entry.worked)
+                .to_string_colored(schedules.weekly_target(&Week::containing(entry.from))),
+            LedgerPeriod::Month => entry.worked.to_string_hhmm(),
+        }
+    }
+}
+
+impl Command for WorktimeBalanceCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running worktime:balance command in verbose mode")
+            );
+        }
+
+        let timezone = config.timezone;
+        let today = timezone.today();
+        let end = if self.include_today {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        // Without a configured start the balance covers the current year
+        let start = config
+            .balance_start
+            .unwrap_or_else(|| today.with_ordinal(1).unwrap());
+
+        if start > end {
+            if !output.is_text() {
+                return Ok(output.print_records::<BalanceRecord>(&[])?);
+            }
+            println!(
+                "{}",
+                formatting::info_text(&format!(
+                    "No completed days to balance since {}",
+                    start.format("%Y-%m-%d")
+                ))
+            );
+            return Ok(());
+        }
+
+        let frames_by_date = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let mut query = LogQuery::new(start, end, timezone).with_partial_frames();
+            if self.include_today {
+                query = query.with_current();
+            }
+            frame_source.log(query)?.by_date(timezone)
+        };
+
+        let store = DataStores::open(config.data_store)?;
+        let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+        let range = DateRange::new(start, end).map_err(anyhow::Error::msg)?;
+        let mut absences_by_date = group_by_date(config.holiday_calendar().apply(
+            store.get_absences(&range)?,
+            &range,
+            &schedules,
+        ));
+        let mut days = Vec::new();
+        for date in range.dates() {
+            let watson_duration = frames_by_date
+                .get(&date)
+                .map(|day_frames| day_frames.total_duration())
+                .unwrap_or_else(Duration::zero);
+            let absences = absences_by_date.remove(&date).unwrap_or_default();
+
+            days.push(BalanceDay::new(
+                date,
+                DayTimeBreakdown::new(watson_duration, absences),
+                schedules.daily_target(date),
+            ));
+        }
+
+        let ledger = BalanceLedger::new(hours_to_duration(config.balance_initial_hours), days);
+        let entries = match self.period {
+            LedgerPeriod::Week => ledger.by_week(),
+            LedgerPeriod::Month => ledger.by_month(),
+        };
+
+        if !output.is_text() {
+            let records: Vec<BalanceRecord> = entries.iter().map(BalanceRecord::from).collect();
+            return Ok(output.print_records(&records)?);
+        }
+
+        let mut b = Builder::new();
+        b.push_record(["Period", "Worked", "Expected", "Delta", "Balance"]);
+        b.push_record([
+            "Initial balance".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            ledger.initial_balance.to_string_balance_colored(),
+        ]);
+        for entry in &entries {
+            b.push_record([
+                self.period_label(entry),
+                self.worked_label(entry, &schedules),
+                entry.expected.to_string_hhmm(),
+                entry.delta().to_string_balance_colored(),
+                entry.balance.to_string_balance_colored(),
+            ]);
+        }
+
+        let mut table = b.build();
+        table
+            .with(Style::modern_rounded())
+            .modify(Columns::new(1..), Alignment::right());
+        println!("{}", table);
+
+        let balance = ledger.balance();
+        println!(
+            "Balance on {}: {} ({})",
+            end.format("%Y-%m-%d"),
+            balance.to_string_balance_colored(),
+            balance.abs().to_string_long_hhmm()
+        );
+
+        Ok(())
+    }
+}
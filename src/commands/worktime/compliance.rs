@@ -0,0 +1,157 @@
+use super::super::Command;
+use super::range::DateRangeArgs;
+use crate::config::Config;
+use crate::utils::compliance::Violation;
+use crate::utils::date::DateRange;
+use crate::utils::formatting;
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::watson::{FrameSource, FrameSources, LogQuery};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::Parser;
+use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
+use tabled::builder::Builder;
+use tabled::settings::Style;
+
+/// A single violation, as emitted in machine-readable output
+#[derive(Serialize)]
+struct ViolationRecord {
+    date: NaiveDate,
+    rule: &'static str,
+    /// Break taken, time worked or rest had, depending on the rule
+    actual_minutes: i64,
+    /// Break required, maximum work time or minimum rest, depending on the rule
+    limit_minutes: i64,
+    description: String,
+}
+
+impl Record for ViolationRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "date",
+        "rule",
+        "actual_minutes",
+        "limit_minutes",
+        "description",
+    ];
+}
+
+impl ViolationRecord {
+    fn new(date: NaiveDate, violation: &Violation) -> Self {
+        let (rule, actual, limit) = match violation {
+            Violation::MissingBreak {
+                required, taken, ..
+            } => ("break", taken, required),
+            Violation::MaxDailyExceeded { worked, maximum } => ("max_daily", worked, maximum),
+            Violation::InsufficientRest { rest, minimum } => ("rest", rest, minimum),
+        };
+        Self {
+            date,
+            rule,
+            actual_minutes: actual.num_minutes(),
+            limit_minutes: limit.num_minutes(),
+            description: violation.to_string(),
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct WorktimeComplianceCommand {
+    /// Days to check (default: the current month up to today)
+    #[command(flatten)]
+    range: DateRangeArgs,
+}
+
+impl Command for WorktimeComplianceCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running worktime:compliance command in verbose mode")
+            );
+        }
+
+        let timezone = config.timezone;
+        let today = timezone.today();
+        let range = self
+            .range
+            .resolve(timezone)
+            .map_err(anyhow::Error::msg)?
+            .unwrap_or(DateRange {
+                from: today.with_day(1).unwrap(),
+                to: today,
+            });
+
+        // Include the day before, so the rest before the first day can be checked
+        let frames = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let query = LogQuery::new(range.from - Duration::days(1), range.to, timezone)
+                .with_current()
+                .with_partial_frames();
+            frame_source.log(query)?
+        };
+
+        let violations: Vec<_> = config
+            .compliance_rules()
+            .check(&frames, timezone)
+            .into_iter()
+            .filter(|day| range.contains(day.date))
+            .collect();
+
+        if !output.is_text() {
+            let records: Vec<ViolationRecord> = violations
+                .iter()
+                .flat_map(|day| {
+                    day.violations
+                        .iter()
+                        .map(|violation| ViolationRecord::new(day.date, violation))
+                })
+                .collect();
+            return Ok(output.print_records(&records)?);
+        }
+
+        if violations.is_empty() {
+            println!(
+                "{}",
+                formatting::success_text(&format!("No violations in {}", range))
+            );
+            return Ok(());
+        }
+
+        let mut b = Builder::new();
+        b.push_record(["Date", "Violation"]);
+        for day in &violations {
+            for violation in &day.violations {
+                b.push_record([
+                    day.date
+                        .format("%a %Y-%m-%d")
+                        .to_string()
+                        .fg::<Cyan>()
+                        .to_string(),
+                    violation.to_string(),
+                ]);
+            }
+        }
+
+        let mut table = b.build();
+        table.with(Style::modern_rounded());
+        println!("{}", table);
+        println!(
+            "{}",
+            formatting::warning_text(&format!(
+                "{} day(s) with violations in {}",
+                violations.len(),
+                range
+            ))
+        );
+
+        Ok(())
+    }
+}
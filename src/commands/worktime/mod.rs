@@ -1,5 +1,15 @@
+mod balance;
+mod compliance;
+mod monthly;
+mod range;
 mod today;
 mod weekly;
+mod yearly;
 
+pub use balance::WorktimeBalanceCommand;
+pub use compliance::WorktimeComplianceCommand;
+pub use monthly::WorktimeMonthlyCommand;
+pub use range::DateRangeArgs;
 pub use today::WorktimeTodayCommand;
 pub use weekly::WorktimeWeeklyCommand;
+pub use yearly::WorktimeYearlyCommand;
@@ -0,0 +1,196 @@
+use super::super::Command;
+use super::range::DateRangeArgs;
+use crate::config::Config;
+use crate::utils::date::{DateRange, DayTimeBreakdown, Month, MonthSelector};
+use crate::utils::formatting::{self, DurationFormat, TimeBreakdownFormat};
+use crate::utils::output::{DayRecord, OutputFormat};
+use crate::utils::schedule::ScheduleHistory;
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::wad_data::{AbsenceRecord, AbsenceStorage, ContractStorage, DataStores, group_by_date};
+use crate::watson::frame::Frames;
+use crate::watson::{FrameSource, FrameSources, LogQuery};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::Parser;
+use std::collections::HashMap;
+use tabled::Table;
+use tabled::builder::Builder;
+use tabled::settings::formatting::AlignmentStrategy;
+use tabled::settings::themes::BorderCorrection;
+use tabled::settings::{Alignment, Span, Style};
+
+pub struct MonthlyTableBuilder;
+
+impl MonthlyTableBuilder {
+    pub fn build(
+        month: &Month,
+        frames_by_date: &HashMap<NaiveDate, Frames>,
+        today: NaiveDate,
+        config: &Config,
+        absences_by_date: &HashMap<NaiveDate, Vec<AbsenceRecord>>,
+        schedules: &ScheduleHistory,
+    ) -> Table {
+        let mut b = Builder::new();
+        b.push_record([month.first_day().format("%B %Y").to_string()]);
+        b.push_record(["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]);
+
+        let mut worked = Duration::zero();
+        let mut expected = Duration::zero();
+
+        for week in month.weeks() {
+            let mut row = Vec::new();
+            for date in week.start.iter_days().take(7) {
+                if date.month() != month.month {
+                    row.push(String::new());
+                    continue;
+                }
+
+                // Only past days and today count, the rest of the month is still open
+                if date > today {
+                    row.push(date.day().to_string());
+                    continue;
+                }
+
+                let watson_duration = frames_by_date
+                    .get(&date)
+                    .map(|day_frames| day_frames.total_duration())
+                    .unwrap_or_else(Duration::zero);
+                let absences = absences_by_date.get(&date).cloned().unwrap_or_default();
+                let breakdown = DayTimeBreakdown::new(watson_duration, absences);
+                let target = schedules.daily_target(date);
+
+                worked += breakdown.total_duration();
+                expected += target;
+
+                // Days off without any work or absence stay empty
+                if target.is_zero()
+                    && breakdown.total_duration().is_zero()
+                    && breakdown.absences.is_empty()
+                {
+                    row.push(date.day().to_string());
+                } else {
+                    row.push(format!(
+                        "{}\n{}",
+                        date.day(),
+                        breakdown.to_string_combined_with_emoji(&config.daily_thresholds(target))
+                    ));
+                }
+            }
+            b.push_record(row);
+        }
+
+        b.push_record([format!(
+            "Total: {} | Expected: {} | Delta: {}",
+            worked.to_string_hhmm(),
+            expected.to_string_hhmm(),
+            (worked - expected).to_string_balance_colored()
+        )]);
+
+        let mut table = b.build();
+        table
+            .with(Style::modern_rounded())
+            .with(Alignment::center())
+            .with(AlignmentStrategy::PerLine);
+
+        // Month title and summary span the whole width
+        let last_row = table.count_rows() - 1;
+        table.modify((0, 0), Span::column(0));
+        table.modify((last_row, 0), Span::column(0));
+        table.with(BorderCorrection::span());
+
+        table
+    }
+}
+
+#[derive(Parser)]
+pub struct WorktimeMonthlyCommand {
+    /// Months to show: a month (YYYY-MM), 'last' for the previous month, or a number of months
+    /// up to the current one (default: 1)
+    #[arg(conflicts_with_all = ["from", "date", "week"])]
+    months: Option<MonthSelector>,
+    #[command(flatten)]
+    range: DateRangeArgs,
+}
+
+impl Command for WorktimeMonthlyCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running worktime:monthly command in verbose mode")
+            );
+        }
+
+        let timezone = config.timezone;
+        // Show all months overlapping the selected range, or the selected months
+        let months = match self.range.resolve(timezone).map_err(anyhow::Error::msg)? {
+            Some(range) => range.months_covered(),
+            None => self
+                .months
+                .unwrap_or(MonthSelector::Count(1))
+                .resolve(timezone),
+        };
+
+        let (Some(first_month), Some(last_month)) = (months.first(), months.last()) else {
+            return Ok(());
+        };
+
+        // Fetch all months at once and split them up in memory
+        let range = DateRange::months(first_month, last_month);
+        let frames_by_date = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let query = LogQuery::range(&range, timezone)
+                .with_current()
+                .with_partial_frames();
+            frame_source.log(query)?.by_date(timezone)
+        };
+
+        let store = DataStores::open(config.data_store)?;
+        let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+        let absences_by_date = group_by_date(config.holiday_calendar().apply(
+            store.get_absences(&range)?,
+            &range,
+            &schedules,
+        ));
+        let today = timezone.today();
+
+        // One record per day up to today, the rest of the month is still open
+        if !output.is_text() {
+            let mut records = Vec::new();
+            for date in range.dates().take_while(|date| *date <= today) {
+                let watson_duration = frames_by_date
+                    .get(&date)
+                    .map(|day_frames| day_frames.total_duration())
+                    .unwrap_or_else(Duration::zero);
+                let absences = absences_by_date.get(&date).cloned().unwrap_or_default();
+                let breakdown = DayTimeBreakdown::new(watson_duration, absences);
+                records.push(DayRecord::new(
+                    date,
+                    &breakdown,
+                    schedules.daily_target(date),
+                ));
+            }
+            return Ok(output.print_records(&records)?);
+        }
+
+        for month in &months {
+            let table = MonthlyTableBuilder::build(
+                month,
+                &frames_by_date,
+                today,
+                config,
+                &absences_by_date,
+                &schedules,
+            );
+            println!("{}", table);
+        }
+
+        Ok(())
+    }
+}
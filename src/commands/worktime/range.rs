@@ -0,0 +1,53 @@
+use crate::utils::date::{DateArg, DateRange, Timezone, Week};
+use clap::Args;
+
+/// Date range selection shared by the worktime and export commands
+#[derive(Args)]
+pub struct DateRangeArgs {
+    /// First day of the range (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
+    #[arg(long, conflicts_with_all = ["date", "week"])]
+    from: Option<DateArg>,
+    /// Last day of the range (default: today)
+    #[arg(long, requires = "from")]
+    to: Option<DateArg>,
+    /// A single day (YYYY-MM-DD, 'today', 'yesterday', 'tomorrow')
+    #[arg(long, conflicts_with_all = ["from", "week"])]
+    date: Option<DateArg>,
+    /// An ISO week or a range of weeks (2025-W10 or 2025-W10..2025-W14)
+    #[arg(long, value_parser = parse_week_span, conflicts_with_all = ["from", "date"])]
+    week: Option<(Week, Week)>,
+}
+
+fn parse_week_span(s: &str) -> Result<(Week, Week), String> {
+    match s.split_once("..") {
+        Some((first, last)) => {
+            let (first, last) = (first.parse::<Week>()?, last.parse::<Week>()?);
+            if first.start > last.start {
+                return Err(format!("Week {} is after week {}", first.start, last.start));
+            }
+            Ok((first, last))
+        }
+        None => s.parse::<Week>().map(|week| (week.clone(), week)),
+    }
+}
+
+impl DateRangeArgs {
+    /// Resolve the selected range, or `None` if no range was given
+    pub fn resolve(&self, timezone: Timezone) -> Result<Option<DateRange>, String> {
+        if let Some(date) = self.date {
+            return Ok(Some(DateRange::day(date.resolve(timezone))));
+        }
+
+        if let Some((first, last)) = &self.week {
+            return Ok(Some(DateRange::weeks(first, last)));
+        }
+
+        match self.from {
+            Some(from) => {
+                let to = self.to.map_or(timezone.today(), |to| to.resolve(timezone));
+                DateRange::new(from.resolve(timezone), to).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
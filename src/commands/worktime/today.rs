@@ -1,72 +1,297 @@
 use super::super::Command;
+use super::range::DateRangeArgs;
 use crate::config::Config;
-use crate::utils::date::DayTimeBreakdown;
+use crate::utils::date::{DateRange, DayTimeBreakdown};
 use crate::utils::formatting::{self, DurationFormat, TimeBreakdownFormat};
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::projection::DayProjection;
+use crate::utils::schedule::ScheduleHistory;
 use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
-use crate::wad_data::{AbsenceStorage, JsonDataStore, WadDataStore};
-use crate::watson::{LogQuery, WatsonClient};
+use crate::wad_data::{AbsenceStorage, ContractStorage, DataStores};
+use crate::watson::{FrameSource, FrameSources, LogQuery};
 use anyhow::Result;
-use chrono::Local;
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
 use clap::Parser;
 use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
+
+/// Work time of the selected days, independent of how it is presented
+struct TodayReport {
+    range: DateRange,
+    is_today: bool,
+    /// Time per project, sorted by project name
+    projects: Vec<(String, Duration)>,
+    breakdown: DayTimeBreakdown,
+    target: Duration,
+    /// Projection of the rest of the day, if requested
+    projection: Option<DayProjection>,
+    /// Point in time the target is reached, if a frame is running
+    leave_time: Option<NaiveDateTime>,
+}
+
+/// Summary of the selected days, as emitted in machine-readable output
+#[derive(Serialize)]
+struct TodayRecord {
+    from: NaiveDate,
+    to: NaiveDate,
+    worked_minutes: i64,
+    absence_minutes: i64,
+    total_minutes: i64,
+    target_minutes: i64,
+    remaining_minutes: Option<i64>,
+    owed_break_minutes: Option<i64>,
+    overtime_minutes: Option<i64>,
+    leave_at: Option<NaiveDateTime>,
+}
+
+impl Record for TodayRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "from",
+        "to",
+        "worked_minutes",
+        "absence_minutes",
+        "total_minutes",
+        "target_minutes",
+        "remaining_minutes",
+        "owed_break_minutes",
+        "overtime_minutes",
+        "leave_at",
+    ];
+}
+
+/// Time spent on a project, as emitted in machine-readable output
+#[derive(Serialize)]
+struct ProjectRecord {
+    project: String,
+    minutes: i64,
+}
+
+impl Record for ProjectRecord {
+    const FIELDS: &'static [&'static str] = &["project", "minutes"];
+}
 
 #[derive(Parser)]
 pub struct WorktimeTodayCommand {
-    /// Show breakdown by projects
+    /// Show breakdown by projects (emits one record per project with --output)
     #[arg(long)]
     projects: bool,
+    /// Show the time left to reach today's target and when it will be reached
+    #[arg(long)]
+    remaining: bool,
+    #[command(flatten)]
+    range: DateRangeArgs,
 }
 
-impl Command for WorktimeTodayCommand {
-    fn run(&self, watson_client: &WatsonClient, config: &Config, verbose: bool) -> Result<()> {
-        if verbose {
+impl WorktimeTodayCommand {
+    fn print_projection(
+        projection: &DayProjection,
+        target: Duration,
+        leave_time: Option<NaiveDateTime>,
+    ) {
+        if target.is_zero() {
+            println!("{}", formatting::info_text("No work target for today"));
+            return;
+        }
+
+        if projection.is_target_reached() {
             println!(
-                "{}",
-                formatting::verbose_text("Running worktime:today command in verbose mode")
+                "{} ({} overtime)",
+                formatting::success_text(&format!("Target of {} reached", target.to_string_hhmm())),
+                projection.overtime.to_string_hhmm()
+            );
+            return;
+        }
+
+        println!(
+            "Remaining: {} of {}",
+            projection.remaining.to_string_hhmm().fg::<Yellow>(),
+            target.to_string_hhmm()
+        );
+
+        let owed_break = if projection.owed_break > Duration::zero() {
+            format!(
+                " (incl. {} break still owed)",
+                projection.owed_break.to_string_hhmm()
+            )
+        } else {
+            String::new()
+        };
+
+        if let Some(leave_time) = leave_time {
+            println!(
+                "Leave at: {}{}",
+                leave_time.format("%H:%M").to_string().fg::<Green>(),
+                owed_break
+            );
+        } else {
+            println!(
+                "{}{}",
+                formatting::info_text("No frame running, start tracking to get a leave time"),
+                owed_break
             );
         }
+    }
+
+    /// Gather the work time of the selected days
+    fn collect(&self, frame_source: &FrameSources, config: &Config) -> Result<TodayReport> {
+        // Without a selected range this is today's report
+        let today = config.timezone.today();
+        let range = self
+            .range
+            .resolve(config.timezone)
+            .map_err(anyhow::Error::msg)?
+            .unwrap_or(DateRange::day(today));
+        let is_today = range == DateRange::day(today);
+
+        if self.remaining && !is_today {
+            return Err(anyhow::anyhow!(
+                "--remaining can only be used for today's work time"
+            ));
+        }
 
         let frames = {
             let _spinner = SpinnerGuard::new(SpinnerConfig::default());
-            let query = LogQuery::today().with_current();
-            watson_client.log(query)?
+            let mut query = LogQuery::range(&range, config.timezone).with_partial_frames();
+            if range.contains(today) {
+                query = query.with_current();
+            }
+            frame_source.log(query)?
         };
 
+        let mut projects: Vec<(String, Duration)> = frames
+            .by_project()
+            .into_iter()
+            .map(|(project, project_frames)| (project, project_frames.total_duration()))
+            .collect();
+        projects.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        // Load the absences, including public holidays, and the schedule valid in the range
+        let (absences, schedules) = {
+            let store = DataStores::open(config.data_store)?;
+            let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+            let absences =
+                config
+                    .holiday_calendar()
+                    .apply(store.get_absences(&range)?, &range, &schedules);
+            (absences, schedules)
+        };
+
+        let breakdown = DayTimeBreakdown::new(frames.total_duration(), absences);
+        let target = range
+            .dates()
+            .map(|date| schedules.daily_target(date))
+            .fold(Duration::zero(), |acc, target| acc + target);
+
+        let projection = self.remaining.then(|| {
+            DayProjection::new(
+                &breakdown,
+                target,
+                frames.break_duration(),
+                &config.break_rules(),
+            )
+        });
+        let leave_time = projection
+            .as_ref()
+            .filter(|projection| !projection.is_target_reached() && frames.has_active_frames())
+            .map(|projection| {
+                config
+                    .timezone
+                    .local_datetime_of(&projection.leave_time(Utc::now()))
+            });
+
+        Ok(TodayReport {
+            range,
+            is_today,
+            projects,
+            breakdown,
+            target,
+            projection,
+            leave_time,
+        })
+    }
+
+    fn print_text(&self, report: &TodayReport, config: &Config) {
         // Show project breakdown if requested
         if self.projects {
-            let projects = frames.by_project();
-            for (project_name, project_frames) in projects {
-                let project_duration = project_frames.total_duration();
-                let short_duration = project_duration.to_string_hhmm();
-                let long_duration = project_duration.to_string_long_hhmm();
-
+            for (project_name, project_duration) in &report.projects {
                 println!(
                     "{}: {} ({})",
                     project_name.fg::<Cyan>(),
-                    short_duration.fg::<Blue>(),
-                    long_duration
+                    project_duration.to_string_hhmm().fg::<Blue>(),
+                    project_duration.to_string_long_hhmm()
                 );
             }
             println!(); // Empty line before total
         }
 
-        // Load today's absences
-        let today = Local::now().date_naive();
-        let absences = {
-            let store = JsonDataStore::open()?;
-            store.get_absence(today)?
+        // Display split format
+        let thresholds = config.daily_thresholds(report.target);
+        let split_display = report.breakdown.to_string_split_colored(&thresholds);
+        let long_duration = report.breakdown.total_duration().to_string_long_hhmm();
+
+        let label = if report.is_today {
+            "today".to_string()
+        } else {
+            report.range.to_string()
         };
+        println!("Worktime {}: {} ({})", label, split_display, long_duration);
+
+        if let Some(projection) = &report.projection {
+            Self::print_projection(projection, report.target, report.leave_time);
+        }
+    }
 
-        // Create day breakdown
-        let watson_duration = frames.total_duration();
-        let day_breakdown = DayTimeBreakdown::new(watson_duration, absences);
+    fn print_records(&self, report: &TodayReport, output: OutputFormat) -> Result<()> {
+        if self.projects {
+            let records: Vec<ProjectRecord> = report
+                .projects
+                .iter()
+                .map(|(project, duration)| ProjectRecord {
+                    project: project.clone(),
+                    minutes: duration.num_minutes(),
+                })
+                .collect();
+            return Ok(output.print_records(&records)?);
+        }
 
-        // Display split format
-        let split_display = day_breakdown.to_string_split_colored(config);
-        let total_duration = day_breakdown.total_duration();
-        let long_duration = total_duration.to_string_long_hhmm();
+        let total = report.breakdown.total_duration();
+        let projection = report.projection.as_ref();
+        Ok(output.print_records(&[TodayRecord {
+            from: report.range.from,
+            to: report.range.to,
+            worked_minutes: report.breakdown.watson_duration.num_minutes(),
+            absence_minutes: (total - report.breakdown.watson_duration).num_minutes(),
+            total_minutes: total.num_minutes(),
+            target_minutes: report.target.num_minutes(),
+            remaining_minutes: projection.map(|p| p.remaining.num_minutes()),
+            owed_break_minutes: projection.map(|p| p.owed_break.num_minutes()),
+            overtime_minutes: projection.map(|p| p.overtime.num_minutes()),
+            leave_at: report.leave_time,
+        }])?)
+    }
+}
+
+impl Command for WorktimeTodayCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running worktime:today command in verbose mode")
+            );
+        }
 
-        println!("Worktime today: {} ({})", split_display, long_duration);
-        Ok(())
+        let report = self.collect(frame_source, config)?;
+        if output.is_text() {
+            self.print_text(&report, config);
+            Ok(())
+        } else {
+            self.print_records(&report, output)
+        }
     }
 }
@@ -1,46 +1,310 @@
 use super::super::Command;
+use super::range::DateRangeArgs;
 use crate::config::Config;
-use crate::utils::date::{DayTimeBreakdown, Week, WeeklyWorktime};
+use crate::utils::date::{DateRange, DayTimeBreakdown, Week, WeeklyWorktime};
 use crate::utils::formatting::WeekFormat;
-use crate::utils::formatting::{self, TimeBreakdownFormat};
+use crate::utils::formatting::{self, DurationFormat, TimeBreakdownFormat};
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::report::UNTAGGED;
+use crate::utils::schedule::ScheduleHistory;
 use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
-use crate::wad_data::{AbsenceStorage, JsonDataStore, WadDataStore};
+use crate::wad_data::{AbsenceRecord, AbsenceStorage, ContractStorage, DataStores, group_by_date};
 use crate::watson::frame::Frames;
-use crate::watson::{LogQuery, WatsonClient};
+use crate::watson::{FrameSource, FrameSources, LogQuery};
 use anyhow::Result;
-use chrono::{Datelike, Duration, Weekday};
+use chrono::{Duration, NaiveDate};
 use clap::Parser;
+use owo_colors::{OwoColorize, colors::*};
+use serde::Serialize;
 use std::collections::HashMap;
 use tabled::Table;
 use tabled::builder::Builder;
+use tabled::settings::object::Columns;
 use tabled::settings::themes::BorderCorrection;
 use tabled::settings::{Alignment, Span, Style};
 
+/// Work time of a week's days, independent of how it is presented
+pub struct WeekSummary {
+    pub week: Week,
+    /// Breakdown and target of each day, starting on Monday
+    pub days: Vec<(DayTimeBreakdown, Duration)>,
+    pub target: Duration,
+}
+
+impl WeekSummary {
+    pub fn collect(
+        week: &Week,
+        frames: &Frames,
+        config: &Config,
+        absences_by_date: &HashMap<NaiveDate, Vec<AbsenceRecord>>,
+        schedules: &ScheduleHistory,
+    ) -> Self {
+        let frames_by_date = frames.by_date(config.timezone);
+        let mut days = Vec::new();
+
+        // Calculate breakdown for each day of the week
+        for date in week.start.iter_days().take(7) {
+            let watson_duration = frames_by_date
+                .get(&date)
+                .map(|day_frames| day_frames.total_duration())
+                .unwrap_or_else(Duration::zero);
+
+            let absences = absences_by_date.get(&date).cloned().unwrap_or_default();
+            days.push((
+                DayTimeBreakdown::new(watson_duration, absences),
+                schedules.daily_target(date),
+            ));
+        }
+
+        Self {
+            week: week.clone(),
+            days,
+            target: schedules.weekly_target(week),
+        }
+    }
+
+    /// Sum of the tracked time of all days
+    pub fn worked(&self) -> Duration {
+        self.days
+            .iter()
+            .map(|(breakdown, _)| breakdown.watson_duration)
+            .fold(Duration::zero(), |acc, d| acc + d)
+    }
+
+    /// Sum of the total time of all days, including absences
+    pub fn total(&self) -> Duration {
+        self.days
+            .iter()
+            .map(|(breakdown, _)| breakdown.total_duration())
+            .fold(Duration::zero(), |acc, d| acc + d)
+    }
+}
+
+/// A week's work time, as emitted in machine-readable output
+#[derive(Serialize)]
+struct WeekRecord {
+    week: String,
+    from: NaiveDate,
+    to: NaiveDate,
+    monday_minutes: i64,
+    tuesday_minutes: i64,
+    wednesday_minutes: i64,
+    thursday_minutes: i64,
+    friday_minutes: i64,
+    saturday_minutes: i64,
+    sunday_minutes: i64,
+    worked_minutes: i64,
+    absence_minutes: i64,
+    total_minutes: i64,
+    target_minutes: i64,
+}
+
+impl Record for WeekRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "week",
+        "from",
+        "to",
+        "monday_minutes",
+        "tuesday_minutes",
+        "wednesday_minutes",
+        "thursday_minutes",
+        "friday_minutes",
+        "saturday_minutes",
+        "sunday_minutes",
+        "worked_minutes",
+        "absence_minutes",
+        "total_minutes",
+        "target_minutes",
+    ];
+}
+
+impl From<&WeekSummary> for WeekRecord {
+    fn from(summary: &WeekSummary) -> Self {
+        let day = |index: usize| summary.days[index].0.total_duration().num_minutes();
+        Self {
+            week: summary.week.to_string(),
+            from: summary.week.start,
+            to: summary.week.end,
+            monday_minutes: day(0),
+            tuesday_minutes: day(1),
+            wednesday_minutes: day(2),
+            thursday_minutes: day(3),
+            friday_minutes: day(4),
+            saturday_minutes: day(5),
+            sunday_minutes: day(6),
+            worked_minutes: summary.worked().num_minutes(),
+            absence_minutes: (summary.total() - summary.worked()).num_minutes(),
+            total_minutes: summary.total().num_minutes(),
+            target_minutes: summary.target.num_minutes(),
+        }
+    }
+}
+
+/// What the tracked time of a week is broken down by
+#[derive(Clone, Copy)]
+enum Breakdown {
+    Projects,
+    Tags,
+}
+
+/// Tracked time of a week per project or tag and day
+struct WeekBreakdown {
+    week: Week,
+    /// Time of each project or tag per day, starting on Monday, sorted by name
+    rows: Vec<(String, [Duration; 7])>,
+    /// Time tracked per day, counting frames with several tags only once
+    totals: [Duration; 7],
+}
+
+impl WeekBreakdown {
+    fn collect(week: &Week, frames: &Frames, breakdown: Breakdown, config: &Config) -> Self {
+        let mut groups: Vec<(String, Frames)> = match breakdown {
+            Breakdown::Projects => frames.by_project().into_iter().collect(),
+            Breakdown::Tags => {
+                let mut groups: Vec<_> = frames.by_tag().into_iter().collect();
+                let untagged = frames.untagged();
+                if !untagged.frames.is_empty() {
+                    groups.push((UNTAGGED.to_string(), untagged));
+                }
+                groups
+            }
+        };
+        groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let rows = groups
+            .into_iter()
+            .map(|(name, group)| (name, Self::per_day(week, &group, config)))
+            .collect();
+
+        Self {
+            week: week.clone(),
+            rows,
+            totals: Self::per_day(week, frames, config),
+        }
+    }
+
+    /// Time of the frames on each day of the week, starting on Monday
+    fn per_day(week: &Week, frames: &Frames, config: &Config) -> [Duration; 7] {
+        let by_date = frames.by_date(config.timezone);
+        let mut days = [Duration::zero(); 7];
+        for (day, date) in days.iter_mut().zip(week.start.iter_days()) {
+            if let Some(day_frames) = by_date.get(&date) {
+                *day = day_frames.total_duration();
+            }
+        }
+        days
+    }
+}
+
+fn sum(days: &[Duration; 7]) -> Duration {
+    days.iter().fold(Duration::zero(), |acc, d| acc + *d)
+}
+
+/// Time of a project or tag in a week, as emitted in machine-readable output
+#[derive(Serialize)]
+struct BreakdownRecord {
+    week: String,
+    from: NaiveDate,
+    to: NaiveDate,
+    /// Project or tag, depending on the breakdown requested
+    name: String,
+    monday_minutes: i64,
+    tuesday_minutes: i64,
+    wednesday_minutes: i64,
+    thursday_minutes: i64,
+    friday_minutes: i64,
+    saturday_minutes: i64,
+    sunday_minutes: i64,
+    total_minutes: i64,
+}
+
+impl Record for BreakdownRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "week",
+        "from",
+        "to",
+        "name",
+        "monday_minutes",
+        "tuesday_minutes",
+        "wednesday_minutes",
+        "thursday_minutes",
+        "friday_minutes",
+        "saturday_minutes",
+        "sunday_minutes",
+        "total_minutes",
+    ];
+}
+
+impl BreakdownRecord {
+    fn new(breakdown: &WeekBreakdown, name: &str, days: &[Duration; 7]) -> Self {
+        Self {
+            week: breakdown.week.to_string(),
+            from: breakdown.week.start,
+            to: breakdown.week.end,
+            name: name.to_string(),
+            monday_minutes: days[0].num_minutes(),
+            tuesday_minutes: days[1].num_minutes(),
+            wednesday_minutes: days[2].num_minutes(),
+            thursday_minutes: days[3].num_minutes(),
+            friday_minutes: days[4].num_minutes(),
+            saturday_minutes: days[5].num_minutes(),
+            sunday_minutes: days[6].num_minutes(),
+            total_minutes: sum(days).num_minutes(),
+        }
+    }
+}
+
+/// Render a week's breakdown as a matrix of rows by weekday, with totals
+fn build_breakdown_table(breakdown: &WeekBreakdown, label: &str) -> Table {
+    let cell = |duration: &Duration| {
+        if duration.is_zero() {
+            "-".to_string()
+        } else {
+            duration.to_string_hhmm()
+        }
+    };
+
+    let mut b = Builder::new();
+    b.push_record([
+        label, "Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun", "Total",
+    ]);
+    for (name, days) in &breakdown.rows {
+        let mut row = vec![name.fg::<Cyan>().to_string()];
+        row.extend(days.iter().map(cell));
+        row.push(sum(days).to_string_hhmm().fg::<Blue>().to_string());
+        b.push_record(row);
+    }
+
+    let totals = &breakdown.totals;
+    let mut row = vec![formatting::header_text("Total")];
+    row.extend(
+        totals
+            .iter()
+            .map(|total| formatting::header_text(&cell(total))),
+    );
+    row.push(formatting::header_text(&sum(totals).to_string_hhmm()));
+    b.push_record(row);
+
+    let mut table = b.build();
+    table.with(Style::modern_rounded());
+    table.modify(Columns::new(1..), Alignment::right());
+    table
+}
+
 pub struct WeeklyTableBuilder;
 
 impl WeeklyTableBuilder {
-    pub fn build(
-        week_frames: &[(&Week, Frames)],
-        config: &Config,
-        store: &JsonDataStore,
-        show_absence_details: bool,
-    ) -> Result<Table> {
+    pub fn build(summaries: &[WeekSummary], config: &Config, show_absence_details: bool) -> Table {
         let mut b = Builder::new();
         // Headers
         b.push_record(["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun", "Total"]);
 
-        for (week, frames) in week_frames.iter() {
+        for summary in summaries {
             // Week header
-            b.push_record([&week.to_string_long()]);
+            b.push_record([&summary.week.to_string_long()]);
 
             // Create row for this week
-            b.push_record(Self::create_week_row(
-                week,
-                frames,
-                config,
-                store,
-                show_absence_details,
-            )?);
+            b.push_record(Self::create_week_row(summary, config, show_absence_details));
         }
 
         let mut table = b.build();
@@ -56,101 +320,167 @@ impl WeeklyTableBuilder {
         }
         table.with(BorderCorrection::span());
 
-        Ok(table)
+        table
     }
 
     fn create_week_row(
-        week: &Week,
-        frames: &Frames,
+        summary: &WeekSummary,
         config: &Config,
-        store: &JsonDataStore,
         show_absence_details: bool,
-    ) -> Result<Vec<String>> {
-        let frames_by_date = frames.by_date();
-        let mut daily_breakdowns = HashMap::new();
-
-        // Calculate breakdown for each day of the week
-        for i in 0..7 {
-            let date = week.start + Duration::days(i as i64);
-            let weekday = date.weekday();
-
-            let watson_duration = frames_by_date
-                .get(&date)
-                .map(|day_frames| day_frames.total_duration())
-                .unwrap_or_else(Duration::zero);
-
-            let absences = store.get_absence(date)?;
-            let breakdown = DayTimeBreakdown::new(watson_duration, absences);
-
-            daily_breakdowns.insert(weekday, breakdown);
-        }
+    ) -> Vec<String> {
+        // Choose formatting based on show_absence_details flag, coloring
+        // each day according to its own target
+        let mut row: Vec<String> = summary
+            .days
+            .iter()
+            .map(|(breakdown, target)| {
+                let thresholds = config.daily_thresholds(*target);
+                if show_absence_details {
+                    breakdown.to_string_split_colored(&thresholds)
+                } else {
+                    breakdown.to_string_combined_with_indicator(&thresholds)
+                }
+            })
+            .collect();
 
-        // Calculate weekly total by summing all daily breakdowns
-        let weekly_total: WeeklyWorktime = daily_breakdowns
-            .values()
-            .map(|breakdown| breakdown.total_duration())
-            .fold(Duration::zero(), |acc, d| acc + d)
-            .into();
-
-        // Choose formatting based on show_absence_details flag
-        let format_day = |breakdown: &DayTimeBreakdown| {
-            if show_absence_details {
-                breakdown.to_string_split_colored(config)
-            } else {
-                breakdown.to_string_combined_with_indicator(config)
-            }
-        };
-
-        Ok(vec![
-            format_day(&daily_breakdowns[&Weekday::Mon]),
-            format_day(&daily_breakdowns[&Weekday::Tue]),
-            format_day(&daily_breakdowns[&Weekday::Wed]),
-            format_day(&daily_breakdowns[&Weekday::Thu]),
-            format_day(&daily_breakdowns[&Weekday::Fri]),
-            format_day(&daily_breakdowns[&Weekday::Sat]),
-            format_day(&daily_breakdowns[&Weekday::Sun]),
-            weekly_total.to_string_colored(config),
-        ])
+        let weekly_total: WeeklyWorktime = summary.total().into();
+        row.push(weekly_total.to_string_colored(summary.target));
+        row
     }
 }
 
 #[derive(Parser)]
 pub struct WorktimeWeeklyCommand {
     /// Number of weeks to show (default: 4)
-    #[arg(long, default_value = "4")]
+    #[arg(long, default_value = "4", conflicts_with_all = ["from", "date", "week"])]
     weeks: u32,
     /// Show detailed absence breakdown instead of combined totals
     #[arg(long)]
     absence: bool,
+    /// Break the tracked time down by project and weekday (emits one record per project and week with --output)
+    #[arg(long, conflicts_with_all = ["tags", "absence"])]
+    projects: bool,
+    /// Break the tracked time down by tag and weekday, frames with several tags count for each of them but once in the totals
+    #[arg(long, conflicts_with = "absence")]
+    tags: bool,
+    #[command(flatten)]
+    range: DateRangeArgs,
+}
+
+impl WorktimeWeeklyCommand {
+    fn print_breakdowns(
+        breakdowns: &[WeekBreakdown],
+        label: &str,
+        output: OutputFormat,
+    ) -> Result<()> {
+        if !output.is_text() {
+            let records: Vec<BreakdownRecord> = breakdowns
+                .iter()
+                .flat_map(|breakdown| {
+                    breakdown
+                        .rows
+                        .iter()
+                        .map(|(name, days)| BreakdownRecord::new(breakdown, name, days))
+                })
+                .collect();
+            return Ok(output.print_records(&records)?);
+        }
+
+        for (index, breakdown) in breakdowns.iter().enumerate() {
+            if index > 0 {
+                println!();
+            }
+            println!(
+                "{}",
+                formatting::header_text(&breakdown.week.to_string_long())
+            );
+            if breakdown.rows.is_empty() {
+                println!("{}", formatting::info_text("No time tracked"));
+            } else {
+                println!("{}", build_breakdown_table(breakdown, label));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Command for WorktimeWeeklyCommand {
-    fn run(&self, watson_client: &WatsonClient, config: &Config, verbose: bool) -> Result<()> {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
         if verbose {
-            println!(
+            eprintln!(
                 "{}",
                 formatting::verbose_text("Running worktime:weekly command in verbose mode")
             );
         }
 
-        // Get the last N weeks
-        let weeks = Week::last_n_weeks(self.weeks);
+        // Get the weeks overlapping the selected range, or the last N weeks
+        let weeks = match self
+            .range
+            .resolve(config.timezone)
+            .map_err(anyhow::Error::msg)?
+        {
+            Some(range) => range.weeks_covered(),
+            None => Week::last_n_weeks(self.weeks, config.timezone),
+        };
+
+        let (Some(first_week), Some(last_week)) = (weeks.first(), weeks.last()) else {
+            return Ok(());
+        };
 
-        let week_frames = {
+        // Fetch all weeks at once and split them up in memory
+        let range = DateRange::weeks(first_week, last_week);
+        let mut frames_by_week = {
             let _spinner = SpinnerGuard::new(SpinnerConfig::default());
-            let mut week_frames = vec![];
+            let query = LogQuery::range(&range, config.timezone)
+                .with_current()
+                .with_partial_frames();
+            frame_source.log(query)?.by_week(config.timezone)
+        };
 
-            for week in &weeks {
-                let query = LogQuery::week(week).with_current();
-                let frames = watson_client.log(query)?;
-                week_frames.push((week, frames));
-            }
-            week_frames
+        let breakdown = match (self.projects, self.tags) {
+            (true, _) => Some((Breakdown::Projects, "Project")),
+            (_, true) => Some((Breakdown::Tags, "Tag")),
+            _ => None,
         };
+        if let Some((breakdown, label)) = breakdown {
+            let breakdowns: Vec<WeekBreakdown> = weeks
+                .iter()
+                .map(|week| {
+                    let frames = frames_by_week.remove(week).unwrap_or_default();
+                    WeekBreakdown::collect(week, &frames, breakdown, config)
+                })
+                .collect();
+            return Self::print_breakdowns(&breakdowns, label, output);
+        }
 
         // Open absence store once for the entire operation
-        let store = JsonDataStore::open()?;
-        let table = WeeklyTableBuilder::build(&week_frames, config, &store, self.absence)?;
+        let store = DataStores::open(config.data_store)?;
+        let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+        let absences_by_date = group_by_date(config.holiday_calendar().apply(
+            store.get_absences(&range)?,
+            &range,
+            &schedules,
+        ));
+        let summaries: Vec<WeekSummary> = weeks
+            .iter()
+            .map(|week| {
+                let frames = frames_by_week.remove(week).unwrap_or_default();
+                WeekSummary::collect(week, &frames, config, &absences_by_date, &schedules)
+            })
+            .collect();
+
+        if !output.is_text() {
+            let records: Vec<WeekRecord> = summaries.iter().map(WeekRecord::from).collect();
+            return Ok(output.print_records(&records)?);
+        }
+
+        let table = WeeklyTableBuilder::build(&summaries, config, self.absence);
         println!("{}", table);
 
         Ok(())
@@ -0,0 +1,228 @@
+use super::super::Command;
+use crate::config::Config;
+use crate::utils::date::{DateRange, DayTimeBreakdown, Month, Week};
+use crate::utils::formatting::{self, DurationFormat, HEATMAP_EMPTY_CELL, TimeBreakdownFormat};
+use crate::utils::output::{OutputFormat, Record};
+use crate::utils::schedule::ScheduleHistory;
+use crate::utils::spinner::{SpinnerConfig, SpinnerGuard};
+use crate::wad_data::{AbsenceStorage, ContractStorage, DataStores, group_by_date};
+use crate::watson::{FrameSource, FrameSources, LogQuery};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use clap::Parser;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::{Alignment, Style};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Worked and expected time summed up for a month
+#[derive(Default)]
+struct MonthTotal {
+    worked: Duration,
+    expected: Duration,
+}
+
+/// A month's totals, as emitted in machine-readable output
+#[derive(Serialize)]
+struct MonthRecord {
+    month: String,
+    worked_minutes: i64,
+    expected_minutes: i64,
+    delta_minutes: i64,
+}
+
+impl Record for MonthRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "month",
+        "worked_minutes",
+        "expected_minutes",
+        "delta_minutes",
+    ];
+}
+
+#[derive(Parser)]
+pub struct WorktimeYearlyCommand {
+    /// Calendar year to show (default: current year)
+    year: Option<i32>,
+}
+
+impl WorktimeYearlyCommand {
+    /// Render the heatmap: one row per weekday, one column per week
+    fn render_heatmap(weeks: &[Week], cells: &HashMap<NaiveDate, String>) -> String {
+        let mut lines = Vec::new();
+
+        // Month labels above the first week starting in that month
+        let mut label_line = String::from("    ");
+        let mut pending_label = String::new();
+        for week in weeks {
+            let first_of_month = week
+                .start
+                .iter_days()
+                .take(7)
+                .find(|date| date.day() == 1 && cells.contains_key(date));
+            if let Some(date) = first_of_month {
+                pending_label = date.format("%b").to_string();
+            }
+
+            // Each week column is two characters wide, labels may overflow into the next ones
+            if pending_label.is_empty() {
+                label_line.push_str("  ");
+            } else {
+                let mut chars = pending_label.chars();
+                label_line.extend(chars.by_ref().take(2));
+                pending_label = chars.collect();
+            }
+        }
+        lines.push(label_line.trim_end().to_string());
+
+        for weekday in WEEKDAYS {
+            let mut line = format!("{} ", weekday);
+            for week in weeks {
+                let date = week.start + Duration::days(weekday.num_days_from_monday() as i64);
+                let cell = cells.get(&date).map(String::as_str).unwrap_or(" ");
+                line.push_str(cell);
+                line.push(' ');
+            }
+            lines.push(line.trim_end().to_string());
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl Command for WorktimeYearlyCommand {
+    fn run(
+        &self,
+        frame_source: &FrameSources,
+        config: &Config,
+        output: OutputFormat,
+        verbose: bool,
+    ) -> Result<()> {
+        if verbose {
+            eprintln!(
+                "{}",
+                formatting::verbose_text("Running worktime:yearly command in verbose mode")
+            );
+        }
+
+        let timezone = config.timezone;
+        let today = timezone.today();
+        let year = self.year.unwrap_or(today.year());
+        let (Some(first_day), Some(last_day)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year, 12, 31),
+        ) else {
+            return Err(anyhow::anyhow!("Invalid year: {}", year));
+        };
+        let range = DateRange::new(first_day, last_day).map_err(anyhow::Error::msg)?;
+
+        let frames_by_date = {
+            let _spinner = SpinnerGuard::new(SpinnerConfig::default());
+            let query = LogQuery::range(&range, timezone)
+                .with_current()
+                .with_partial_frames();
+            frame_source.log(query)?.by_date(timezone)
+        };
+
+        let store = DataStores::open(config.data_store)?;
+        let schedules = ScheduleHistory::new(config.schedule(), store.list_contracts()?);
+        let mut absences_by_date = group_by_date(config.holiday_calendar().apply(
+            store.get_absences(&range)?,
+            &range,
+            &schedules,
+        ));
+
+        let mut cells = HashMap::new();
+        let mut month_totals: BTreeMap<Month, MonthTotal> = BTreeMap::new();
+
+        for date in range.dates() {
+            // Future days are part of the grid, but neither worked nor expected yet
+            if date > today {
+                cells.insert(date, HEATMAP_EMPTY_CELL.dimmed().to_string());
+                continue;
+            }
+
+            let watson_duration = frames_by_date
+                .get(&date)
+                .map(|day_frames| day_frames.total_duration())
+                .unwrap_or_else(Duration::zero);
+            let breakdown = DayTimeBreakdown::new(
+                watson_duration,
+                absences_by_date.remove(&date).unwrap_or_default(),
+            );
+            let target = schedules.daily_target(date);
+
+            let total = month_totals.entry(Month::containing(date)).or_default();
+            total.worked += breakdown.total_duration();
+            total.expected += target;
+
+            // Days off without any work are not worth a warning color
+            let cell = if target.is_zero()
+                && breakdown.total_duration().is_zero()
+                && breakdown.absences.is_empty()
+            {
+                HEATMAP_EMPTY_CELL.dimmed().to_string()
+            } else {
+                breakdown.to_heatmap_cell(&config.daily_thresholds(target))
+            };
+            cells.insert(date, cell);
+        }
+
+        if !output.is_text() {
+            let records: Vec<MonthRecord> = month_totals
+                .iter()
+                .map(|(month, total)| MonthRecord {
+                    month: month.to_string(),
+                    worked_minutes: total.worked.num_minutes(),
+                    expected_minutes: total.expected.num_minutes(),
+                    delta_minutes: (total.worked - total.expected).num_minutes(),
+                })
+                .collect();
+            return Ok(output.print_records(&records)?);
+        }
+
+        println!("{}", formatting::header_text(&year.to_string()));
+        println!("{}", Self::render_heatmap(&range.weeks_covered(), &cells));
+        println!();
+
+        let mut b = Builder::new();
+        b.push_record(["Month", "Worked", "Expected", "Delta"]);
+        let mut year_total = MonthTotal::default();
+        for (month, total) in &month_totals {
+            year_total.worked += total.worked;
+            year_total.expected += total.expected;
+            b.push_record([
+                month.first_day().format("%B").to_string(),
+                total.worked.to_string_hhmm(),
+                total.expected.to_string_hhmm(),
+                (total.worked - total.expected).to_string_balance_colored(),
+            ]);
+        }
+        b.push_record([
+            "Total".to_string(),
+            year_total.worked.to_string_hhmm(),
+            year_total.expected.to_string_hhmm(),
+            (year_total.worked - year_total.expected).to_string_balance_colored(),
+        ]);
+
+        let mut table = b.build();
+        table
+            .with(Style::modern_rounded())
+            .modify(Columns::new(1..), Alignment::right());
+        println!("{}", table);
+
+        Ok(())
+    }
+}
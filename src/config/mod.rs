@@ -1,6 +1,14 @@
+use crate::utils::breaks::{BreakRule, BreakRules};
+use crate::utils::compliance::ComplianceRules;
+use crate::utils::date::{Timezone, hours_to_duration};
+use crate::utils::holidays::{HolidayCalendar, HolidayRegion};
+use crate::utils::schedule::{DailyThresholds, WeeklySchedule};
+use crate::utils::vacation::{MonthDay, VacationPolicy};
 use anyhow::Result;
+use chrono::NaiveDate;
 use config::{Config as ConfigBuilder, ConfigError, Environment, File};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -25,22 +33,99 @@ pub enum ConfigFileError {
     JsonError(#[from] serde_json::Error),
 }
 
+/// Where Watson frames are read from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameSourceKind {
+    /// Run `watson log` and parse its JSON output
+    Cli,
+    /// Read Watson's `frames` and `state` files directly
+    Files,
+}
+
+/// Where absences and contract periods are stored
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DataStoreKind {
+    /// One JSON file per day with absences, plus `contracts.json`
+    Json,
+    /// A single SQLite database, `wad.sqlite3`
+    Sqlite,
+}
+
+impl fmt::Display for DataStoreKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataStoreKind::Json => write!(f, "json"),
+            DataStoreKind::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Config {
-    pub workhours_per_week: f64,
+    pub workhours_monday: f64,
+    pub workhours_tuesday: f64,
+    pub workhours_wednesday: f64,
+    pub workhours_thursday: f64,
+    pub workhours_friday: f64,
+    pub workhours_saturday: f64,
+    pub workhours_sunday: f64,
     pub daily_worktime_low: f64,
     pub daily_worktime_medium: f64,
     pub daily_worktime_good: f64,
+    pub frame_source: FrameSourceKind,
+    pub data_store: DataStoreKind,
+    pub timezone: Timezone,
+    pub balance_start: Option<NaiveDate>,
+    pub balance_initial_hours: f64,
+    pub break_after_hours: f64,
+    pub break_minutes: f64,
+    pub long_break_after_hours: f64,
+    pub long_break_minutes: f64,
+    pub min_break_segment_minutes: f64,
+    pub max_daily_hours: f64,
+    pub min_rest_hours: f64,
+    pub vacation_days_per_year: f64,
+    pub vacation_start: Option<NaiveDate>,
+    pub vacation_carry_over_days: f64,
+    pub vacation_carry_over_expiry: Option<MonthDay>,
+    pub holiday_region: Option<HolidayRegion>,
+    pub holiday_half_days: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            workhours_per_week: 40.0,
+            workhours_monday: 8.0,
+            workhours_tuesday: 8.0,
+            workhours_wednesday: 8.0,
+            workhours_thursday: 8.0,
+            workhours_friday: 8.0,
+            workhours_saturday: 0.0,
+            workhours_sunday: 0.0,
             daily_worktime_low: 0.0,
             daily_worktime_medium: 4.0,
             daily_worktime_good: 8.0,
+            frame_source: FrameSourceKind::Cli,
+            data_store: DataStoreKind::Json,
+            timezone: Timezone::Local,
+            balance_start: None,
+            balance_initial_hours: 0.0,
+            break_after_hours: 6.0,
+            break_minutes: 30.0,
+            long_break_after_hours: 9.0,
+            long_break_minutes: 45.0,
+            min_break_segment_minutes: 15.0,
+            max_daily_hours: 10.0,
+            min_rest_hours: 11.0,
+            vacation_days_per_year: 30.0,
+            vacation_start: None,
+            vacation_carry_over_days: 0.0,
+            vacation_carry_over_expiry: None,
+            holiday_region: None,
+            holiday_half_days: true,
         }
     }
 }
@@ -55,9 +140,7 @@ impl Config {
             .build()
             .map_err(ConfigFileError::ConfigFileRead)?;
 
-        let config: Config = settings
-            .try_deserialize()
-            .unwrap_or_else(|_| Config::default());
+        let config = Self::from_settings(settings);
 
         // Always save config to ensure file exists and contains all current fields
         config.save()?;
@@ -65,6 +148,24 @@ impl Config {
         Ok(config)
     }
 
+    /// Deserialize configuration from loaded settings, falling back to defaults
+    fn from_settings(settings: ConfigBuilder) -> Self {
+        let legacy_workhours_per_week = settings.get_float("workhours_per_week").ok();
+
+        let mut config: Config = settings
+            .try_deserialize()
+            .unwrap_or_else(|_| Config::default());
+
+        // `workhours_per_week` has been replaced by a schedule per weekday.
+        // Older config files (or environment variables) still using it are
+        // migrated by spreading the hours across Monday to Friday.
+        if let Some(weekly_hours) = legacy_workhours_per_week {
+            config.set_schedule(WeeklySchedule::spread_over_weekdays(weekly_hours));
+        }
+
+        config
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<(), ConfigFileError> {
         let config_dir = Self::config_dir()?;
@@ -78,6 +179,81 @@ impl Config {
         Ok(())
     }
 
+    /// Get the configured work schedule
+    pub fn schedule(&self) -> WeeklySchedule {
+        WeeklySchedule {
+            monday: self.workhours_monday,
+            tuesday: self.workhours_tuesday,
+            wednesday: self.workhours_wednesday,
+            thursday: self.workhours_thursday,
+            friday: self.workhours_friday,
+            saturday: self.workhours_saturday,
+            sunday: self.workhours_sunday,
+        }
+    }
+
+    /// Replace the configured work schedule
+    pub fn set_schedule(&mut self, schedule: WeeklySchedule) {
+        self.workhours_monday = schedule.monday;
+        self.workhours_tuesday = schedule.tuesday;
+        self.workhours_wednesday = schedule.wednesday;
+        self.workhours_thursday = schedule.thursday;
+        self.workhours_friday = schedule.friday;
+        self.workhours_saturday = schedule.saturday;
+        self.workhours_sunday = schedule.sunday;
+    }
+
+    /// Get the thresholds for coloring the work time of a day with the given target
+    pub fn daily_thresholds(&self, target: chrono::Duration) -> DailyThresholds {
+        DailyThresholds {
+            low: self.daily_worktime_low,
+            medium: self.daily_worktime_medium,
+            good: self.daily_worktime_good,
+        }
+        .scaled_to(target)
+    }
+
+    /// Get the mandatory break rules
+    pub fn break_rules(&self) -> BreakRules {
+        BreakRules::new(vec![
+            BreakRule {
+                worked_over: hours_to_duration(self.break_after_hours),
+                minimum_break: hours_to_duration(self.break_minutes / 60.0),
+            },
+            BreakRule {
+                worked_over: hours_to_duration(self.long_break_after_hours),
+                minimum_break: hours_to_duration(self.long_break_minutes / 60.0),
+            },
+        ])
+    }
+
+    /// Get the working time rules checked by `worktime:compliance`
+    pub fn compliance_rules(&self) -> ComplianceRules {
+        ComplianceRules {
+            breaks: self.break_rules(),
+            min_break_segment: hours_to_duration(self.min_break_segment_minutes / 60.0),
+            max_daily: hours_to_duration(self.max_daily_hours),
+            min_rest: hours_to_duration(self.min_rest_hours),
+        }
+    }
+
+    /// Get the vacation entitlement and carry-over rules
+    pub fn vacation_policy(&self) -> VacationPolicy {
+        VacationPolicy {
+            days_per_year: self.vacation_days_per_year,
+            initial_carry_over: self.vacation_carry_over_days,
+            carry_over_expiry: self.vacation_carry_over_expiry,
+        }
+    }
+
+    /// Get the public holidays applied to targets
+    pub fn holiday_calendar(&self) -> HolidayCalendar {
+        HolidayCalendar {
+            region: self.holiday_region,
+            half_days: self.holiday_half_days,
+        }
+    }
+
     /// Get the platform-specific config directory path
     pub fn config_dir() -> Result<PathBuf, ConfigFileError> {
         dirs::config_dir()
@@ -99,6 +275,7 @@ impl Config {
             serde_json::Value::String(s) => s.clone(),
             serde_json::Value::Number(n) => n.to_string(),
             serde_json::Value::Bool(b) => b.to_string(),
+            serde_json::Value::Null => String::new(),
             _ => v.to_string(),
         })
     }
@@ -127,7 +304,18 @@ impl Config {
                     })?;
                 serde_json::Value::Number(serde_json::Number::from_f64(f).unwrap())
             }
-            Some(serde_json::Value::String(_)) => serde_json::Value::String(value_str.to_string()),
+            Some(serde_json::Value::String(_)) | Some(serde_json::Value::Null) => {
+                // An empty string unsets optional values
+                if value_str.is_empty() {
+                    let mut unset = map.clone();
+                    unset.insert(key.to_string(), serde_json::Value::Null);
+                    if let Ok(config) = serde_json::from_value(serde_json::Value::Object(unset)) {
+                        *self = config;
+                        return Ok(());
+                    }
+                }
+                serde_json::Value::String(value_str.to_string())
+            }
             None => return Err(ConfigFileError::UnknownKey(key.to_string())),
             _ => {
                 return Err(ConfigFileError::InvalidValue {
@@ -156,6 +344,7 @@ impl Config {
                         serde_json::Value::String(s) => s.clone(),
                         serde_json::Value::Number(n) => n.to_string(),
                         serde_json::Value::Bool(b) => b.to_string(),
+                        serde_json::Value::Null => String::new(),
                         _ => v.to_string(),
                     },
                 )
@@ -167,6 +356,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use config::FileFormat;
     use std::collections::HashMap;
 
     #[test]
@@ -175,8 +365,12 @@ mod tests {
 
         // Test getting existing values
         assert_eq!(
-            config.get_value("workhours_per_week"),
-            Some("40.0".to_string())
+            config.get_value("workhours_monday"),
+            Some("8.0".to_string())
+        );
+        assert_eq!(
+            config.get_value("workhours_sunday"),
+            Some("0.0".to_string())
         );
         assert_eq!(
             config.get_value("daily_worktime_low"),
@@ -195,11 +389,12 @@ mod tests {
         assert_eq!(config.get_value("nonexistent"), None);
 
         // Test setting existing values with correct types
-        assert!(config.set_value("workhours_per_week", "37.5").is_ok());
+        assert!(config.set_value("workhours_friday", "4.5").is_ok());
         assert_eq!(
-            config.get_value("workhours_per_week"),
-            Some("37.5".to_string())
+            config.get_value("workhours_friday"),
+            Some("4.5".to_string())
         );
+        assert_eq!(config.schedule().weekly_hours(), 36.5);
 
         assert!(config.set_value("daily_worktime_medium", "5.5").is_ok());
         assert_eq!(
@@ -210,7 +405,7 @@ mod tests {
         // Test setting with wrong types
         assert!(
             config
-                .set_value("workhours_per_week", "not_a_number")
+                .set_value("workhours_friday", "not_a_number")
                 .is_err()
         );
         assert!(
@@ -221,6 +416,68 @@ mod tests {
 
         // Test setting non-existent key
         assert!(config.set_value("nonexistent", "value").is_err());
+
+        // Test setting enum values
+        assert_eq!(config.get_value("frame_source"), Some("cli".to_string()));
+        assert!(config.set_value("frame_source", "files").is_ok());
+        assert_eq!(config.frame_source, FrameSourceKind::Files);
+        assert!(config.set_value("frame_source", "carrier-pigeon").is_err());
+        assert_eq!(config.frame_source, FrameSourceKind::Files);
+        assert_eq!(config.get_value("data_store"), Some("json".to_string()));
+        assert!(config.set_value("data_store", "sqlite").is_ok());
+        assert_eq!(config.data_store, DataStoreKind::Sqlite);
+        assert!(config.set_value("data_store", "postgres").is_err());
+
+        // Test setting the timezone
+        assert_eq!(config.get_value("timezone"), Some("local".to_string()));
+        assert!(config.set_value("timezone", "Europe/Berlin").is_ok());
+        assert_eq!(config.timezone, Timezone::Named(chrono_tz::Europe::Berlin));
+        assert!(config.set_value("timezone", "+05:30").is_ok());
+        assert_eq!(config.get_value("timezone"), Some("+05:30".to_string()));
+        assert!(config.set_value("timezone", "Nowhere/Special").is_err());
+
+        // Test setting and unsetting optional values
+        assert_eq!(config.get_value("balance_start"), Some("".to_string()));
+        assert!(config.set_value("balance_start", "2025-01-01").is_ok());
+        assert_eq!(
+            config.balance_start,
+            Some(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+        );
+        assert!(
+            config
+                .set_value("balance_start", "first of january")
+                .is_err()
+        );
+        assert!(config.set_value("balance_start", "").is_ok());
+        assert_eq!(config.balance_start, None);
+
+        // Test setting and unsetting the carry-over expiry
+        assert!(
+            config
+                .set_value("vacation_carry_over_expiry", "03-31")
+                .is_ok()
+        );
+        assert_eq!(
+            config.get_value("vacation_carry_over_expiry"),
+            Some("03-31".to_string())
+        );
+        assert!(
+            config
+                .set_value("vacation_carry_over_expiry", "31.03.")
+                .is_err()
+        );
+        assert!(config.set_value("vacation_carry_over_expiry", "").is_ok());
+        assert_eq!(config.vacation_carry_over_expiry, None);
+
+        // Test holiday regions are validated against the built-in calendars
+        assert!(config.set_value("holiday_region", "de-by").is_ok());
+        assert_eq!(
+            config.get_value("holiday_region"),
+            Some("DE-BY".to_string())
+        );
+        assert!(config.set_value("holiday_region", "DE-XX").is_err());
+        assert!(config.set_value("holiday_region", "").is_ok());
+        assert_eq!(config.holiday_region, None);
     }
 
     #[test]
@@ -230,17 +487,46 @@ mod tests {
 
         // Should contain all fields
         let keys: Vec<String> = values.iter().map(|(k, _)| k.clone()).collect();
-        assert!(keys.contains(&"workhours_per_week".to_string()));
+        for weekday in [
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ] {
+            assert!(keys.contains(&format!("workhours_{}", weekday)));
+        }
         assert!(keys.contains(&"daily_worktime_low".to_string()));
         assert!(keys.contains(&"daily_worktime_medium".to_string()));
         assert!(keys.contains(&"daily_worktime_good".to_string()));
-        assert_eq!(keys.len(), 4); // Should have exactly 4 fields
+        assert!(keys.contains(&"frame_source".to_string()));
+        assert!(keys.contains(&"data_store".to_string()));
+        assert!(keys.contains(&"timezone".to_string()));
+        assert!(keys.contains(&"balance_start".to_string()));
+        assert!(keys.contains(&"balance_initial_hours".to_string()));
+        assert!(keys.contains(&"break_after_hours".to_string()));
+        assert!(keys.contains(&"break_minutes".to_string()));
+        assert!(keys.contains(&"long_break_after_hours".to_string()));
+        assert!(keys.contains(&"long_break_minutes".to_string()));
+        assert!(keys.contains(&"min_break_segment_minutes".to_string()));
+        assert!(keys.contains(&"max_daily_hours".to_string()));
+        assert!(keys.contains(&"min_rest_hours".to_string()));
+        assert!(keys.contains(&"vacation_days_per_year".to_string()));
+        assert!(keys.contains(&"vacation_start".to_string()));
+        assert!(keys.contains(&"vacation_carry_over_days".to_string()));
+        assert!(keys.contains(&"vacation_carry_over_expiry".to_string()));
+        assert!(keys.contains(&"holiday_region".to_string()));
+        assert!(keys.contains(&"holiday_half_days".to_string()));
+        assert_eq!(keys.len(), 28); // Should have exactly 28 fields
 
         // Check default values
         let values_map: HashMap<String, String> = values.into_iter().collect();
+        assert_eq!(values_map.get("workhours_monday"), Some(&"8.0".to_string()));
         assert_eq!(
-            values_map.get("workhours_per_week"),
-            Some(&"40.0".to_string())
+            values_map.get("workhours_saturday"),
+            Some(&"0.0".to_string())
         );
         assert_eq!(
             values_map.get("daily_worktime_low"),
@@ -259,13 +545,60 @@ mod tests {
     #[test]
     fn test_partial_config_deserialization() {
         // Test that serde fills in missing fields with defaults
-        let partial_toml = "workhours_per_week = 35.0";
+        let partial_toml = "workhours_friday = 4.0";
 
         let config: Config = toml::from_str(partial_toml).unwrap();
-        assert_eq!(config.workhours_per_week, 35.0);
+        assert_eq!(config.workhours_friday, 4.0);
         // Missing fields should use defaults
+        assert_eq!(config.workhours_monday, 8.0);
         assert_eq!(config.daily_worktime_low, 0.0);
         assert_eq!(config.daily_worktime_medium, 4.0);
         assert_eq!(config.daily_worktime_good, 8.0);
+        assert_eq!(config.frame_source, FrameSourceKind::Cli);
+        assert_eq!(config.data_store, DataStoreKind::Json);
+        assert_eq!(config.timezone, Timezone::Local);
+        assert_eq!(config.balance_start, None);
+    }
+
+    #[test]
+    fn test_legacy_workhours_per_week_migration() {
+        let settings = ConfigBuilder::builder()
+            .add_source(File::from_str(
+                "workhours_per_week = 35.0\ndaily_worktime_good = 7.0",
+                FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+
+        let config = Config::from_settings(settings);
+        assert_eq!(
+            config.schedule(),
+            WeeklySchedule::spread_over_weekdays(35.0)
+        );
+        assert_eq!(config.daily_worktime_good, 7.0);
+        assert!(config.get_value("workhours_per_week").is_none());
+    }
+
+    #[test]
+    fn test_daily_thresholds_follow_target() {
+        let config = Config::default();
+
+        let thresholds = config.daily_thresholds(chrono::Duration::hours(4));
+        assert_eq!(thresholds.medium, 2.0);
+        assert_eq!(thresholds.good, 4.0);
+    }
+
+    #[test]
+    fn test_default_break_rules() {
+        let rules = Config::default().break_rules();
+
+        assert_eq!(
+            rules.required_break(chrono::Duration::hours(7)),
+            chrono::Duration::minutes(30)
+        );
+        assert_eq!(
+            rules.required_break(chrono::Duration::hours(10)),
+            chrono::Duration::minutes(45)
+        );
     }
 }
@@ -8,9 +8,10 @@ mod wad_data;
 mod watson;
 
 use commands::{Command, Commands, discovery};
-use config::Config;
+use config::{Config, FrameSourceKind};
 use utils::formatting;
-use watson::WatsonClient;
+use utils::output::OutputFormat;
+use watson::{FrameSources, WatsonClient, WatsonDataFiles};
 
 #[derive(Parser)]
 #[command(name = "wad")]
@@ -20,6 +21,16 @@ struct Cli {
     #[arg(short, long, global = true, help = "Enable verbose output")]
     verbose: bool,
 
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        default_value = "text",
+        help = "Output format, json, csv and tsv emit plain records for scripts"
+    )]
+    output: OutputFormat,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -27,6 +38,36 @@ struct Cli {
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // Open configuration
+    let config = Config::open().unwrap_or_else(|e| {
+        if cli.verbose {
+            eprintln!("{}: {}", formatting::warning_text("Config warning"), e);
+        }
+        Config::default()
+    });
+
+    // The doctor reports a broken frame source itself, so it mustn't exit early
+    let frame_source = match (config.frame_source, &cli.command) {
+        (FrameSourceKind::Cli, Some(Commands::Doctor(_))) => {
+            FrameSources::from(WatsonClient::new())
+        }
+        (FrameSourceKind::Files, Some(Commands::Doctor(_))) => FrameSources::from(
+            WatsonDataFiles::locate().unwrap_or_else(|_| open_watson_data_files(cli.verbose)),
+        ),
+        (FrameSourceKind::Cli, _) => FrameSources::from(open_watson_client(cli.verbose)),
+        (FrameSourceKind::Files, _) => FrameSources::from(open_watson_data_files(cli.verbose)),
+    };
+
+    match cli.command {
+        Some(command) => command.run(&frame_source, &config, cli.output, cli.verbose),
+        None => {
+            discovery::show_command_selection_menu(&frame_source, &config, cli.output, cli.verbose)
+        }
+    }
+}
+
+/// Create a Watson CLI client, exiting if Watson is not available
+fn open_watson_client(verbose: bool) -> WatsonClient {
     // Check Watson availability before executing any commands
     let watson_client = WatsonClient::new();
     if !watson_client.is_usable() {
@@ -43,18 +84,10 @@ fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    // Open configuration
-    let config = Config::open().unwrap_or_else(|e| {
-        if cli.verbose {
-            eprintln!("{}: {}", formatting::warning_text("Config warning"), e);
-        }
-        Config::default()
-    });
-
     // Print Watson info if verbose
-    if cli.verbose {
+    if verbose {
         if let Ok(version) = watson_client.get_version() {
-            println!(
+            eprintln!(
                 "{}: {}.{}.{}",
                 formatting::info_text("Watson version"),
                 version.major,
@@ -64,12 +97,33 @@ fn main() -> Result<()> {
         }
 
         if let Ok(path) = watson_client.get_path() {
-            println!("{}: {}", formatting::info_text("Watson path"), path);
+            eprintln!("{}: {}", formatting::info_text("Watson path"), path);
         }
     }
 
-    match cli.command {
-        Some(command) => command.run(&watson_client, &config, cli.verbose),
-        None => discovery::show_command_selection_menu(&watson_client, &config, cli.verbose),
+    watson_client
+}
+
+/// Open Watson's data directory, exiting if it can't be found
+fn open_watson_data_files(verbose: bool) -> WatsonDataFiles {
+    let data_files = WatsonDataFiles::open().unwrap_or_else(|e| {
+        eprintln!("{}", formatting::error_text(&format!("Error: {}", e)));
+        eprintln!(
+            "{}",
+            formatting::error_text(
+                "Please set WATSON_DIR or switch back to the CLI with `wad config set frame_source cli`."
+            )
+        );
+        std::process::exit(1);
+    });
+
+    if verbose {
+        eprintln!(
+            "{}: {}",
+            formatting::info_text("Watson data directory"),
+            data_files.watson_dir().display()
+        );
     }
+
+    data_files
 }
@@ -0,0 +1,226 @@
+use chrono::{Datelike, NaiveDate};
+use std::fmt;
+
+use crate::utils::date::DateRange;
+use crate::utils::schedule::ScheduleHistory;
+use crate::wad_data::{AbsenceRecord, AbsenceType};
+
+/// Why no absence is added on a day of the range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The schedule has no work on that day
+    DayOff,
+    /// A public holiday already covers the whole day
+    Holiday,
+    /// The day already has another absence
+    ExistingAbsence,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::DayOff => write!(f, "day off"),
+            SkipReason::Holiday => write!(f, "holiday"),
+            SkipReason::ExistingAbsence => write!(f, "already has an absence"),
+        }
+    }
+}
+
+/// What happens on a single day of the range
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlannedDay {
+    /// An absence with the given hours is added
+    Add { date: NaiveDate, hours: f64 },
+    /// The day is left alone
+    Skip { date: NaiveDate, reason: SkipReason },
+}
+
+impl PlannedDay {
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            PlannedDay::Add { date, .. } | PlannedDay::Skip { date, .. } => *date,
+        }
+    }
+}
+
+/// Plan one absence per working day of a range
+///
+/// Working days are the days with a target in the schedule valid on them.
+/// Days already covered by a holiday or another absence are skipped, so
+/// running the same command twice doesn't book a day twice. Without fixed
+/// `hours`, each day gets its own target as absence hours. On half-day
+/// holidays only the rest of the target is booked, at most `hours`.
+pub fn plan_absences(
+    range: &DateRange,
+    hours: Option<f64>,
+    schedules: &ScheduleHistory,
+    existing: impl Fn(NaiveDate) -> Vec<AbsenceRecord>,
+) -> Vec<PlannedDay> {
+    range
+        .dates()
+        .map(|date| {
+            let target_hours = schedules.schedule_on(date).hours(date.weekday());
+            let existing = existing(date);
+            let (holidays, others): (Vec<_>, Vec<_>) = existing
+                .iter()
+                .partition(|record| record.absence_type == AbsenceType::Holiday);
+            let remaining_hours =
+                target_hours - holidays.iter().map(|record| record.hours).sum::<f64>();
+
+            let reason = if !holidays.is_empty() && remaining_hours <= 0.0 {
+                Some(SkipReason::Holiday)
+            } else if !others.is_empty() {
+                Some(SkipReason::ExistingAbsence)
+            } else if target_hours <= 0.0 {
+                Some(SkipReason::DayOff)
+            } else {
+                None
+            };
+
+            match reason {
+                Some(reason) => PlannedDay::Skip { date, reason },
+                None => PlannedDay::Add {
+                    date,
+                    hours: match hours {
+                        Some(hours) if !holidays.is_empty() => hours.min(remaining_hours),
+                        Some(hours) => hours,
+                        None => remaining_hours,
+                    },
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::schedule::WeeklySchedule;
+    use crate::wad_data::ContractPeriod;
+    use test_case::test_case;
+    use ulid::Ulid;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn absence(date: NaiveDate, absence_type: AbsenceType) -> AbsenceRecord {
+        absence_of(date, 8.0, absence_type)
+    }
+
+    fn absence_of(date: NaiveDate, hours: f64, absence_type: AbsenceType) -> AbsenceRecord {
+        AbsenceRecord {
+            id: Ulid::new(),
+            date,
+            hours,
+            absence_type,
+            note: None,
+            ical_uid: None,
+        }
+    }
+
+    fn schedules() -> ScheduleHistory {
+        ScheduleHistory::new("8,8,8,8,4".parse::<WeeklySchedule>().unwrap(), Vec::new())
+    }
+
+    // 2025-07-04 is a Friday, 2025-07-05 and 2025-07-06 are the weekend
+    #[test_case(None, 4.0; "hours from the schedule")]
+    #[test_case(Some(6.0), 6.0; "fixed hours")]
+    fn test_plan_skips_weekend(hours: Option<f64>, friday_hours: f64) {
+        let range = DateRange::new(date("2025-07-03"), date("2025-07-07")).unwrap();
+        let plan = plan_absences(&range, hours, &schedules(), |_| Vec::new());
+
+        assert_eq!(
+            plan,
+            vec![
+                PlannedDay::Add {
+                    date: date("2025-07-03"),
+                    hours: hours.unwrap_or(8.0),
+                },
+                PlannedDay::Add {
+                    date: date("2025-07-04"),
+                    hours: friday_hours,
+                },
+                PlannedDay::Skip {
+                    date: date("2025-07-05"),
+                    reason: SkipReason::DayOff,
+                },
+                PlannedDay::Skip {
+                    date: date("2025-07-06"),
+                    reason: SkipReason::DayOff,
+                },
+                PlannedDay::Add {
+                    date: date("2025-07-07"),
+                    hours: hours.unwrap_or(8.0),
+                },
+            ]
+        );
+    }
+
+    #[test_case(AbsenceType::Holiday, SkipReason::Holiday; "holiday")]
+    #[test_case(AbsenceType::Sick, SkipReason::ExistingAbsence; "existing absence")]
+    fn test_plan_skips_booked_days(absence_type: AbsenceType, reason: SkipReason) {
+        let booked = date("2025-07-02");
+        let range = DateRange::new(date("2025-07-01"), date("2025-07-02")).unwrap();
+        let plan = plan_absences(&range, None, &schedules(), |day| {
+            if day == booked {
+                vec![absence(day, absence_type.clone())]
+            } else {
+                Vec::new()
+            }
+        });
+
+        assert_eq!(
+            plan[1],
+            PlannedDay::Skip {
+                date: booked,
+                reason
+            }
+        );
+        assert!(matches!(plan[0], PlannedDay::Add { .. }));
+    }
+
+    // 2025-12-24 is a Wednesday with a half-day holiday of 4 of its 8 hours
+    #[test_case(None, 4.0; "rest of the target")]
+    #[test_case(Some(6.0), 4.0; "fixed hours capped at the rest")]
+    #[test_case(Some(2.0), 2.0; "fixed hours below the rest")]
+    fn test_plan_books_rest_of_half_day_holiday(hours: Option<f64>, expected: f64) {
+        let christmas_eve = date("2025-12-24");
+        let range = DateRange::new(christmas_eve, christmas_eve).unwrap();
+        let plan = plan_absences(&range, hours, &schedules(), |day| {
+            vec![absence_of(day, 4.0, AbsenceType::Holiday)]
+        });
+
+        assert_eq!(
+            plan,
+            vec![PlannedDay::Add {
+                date: christmas_eve,
+                hours: expected,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_uses_contract_valid_on_each_day() {
+        let contract = ContractPeriod {
+            id: Ulid::new(),
+            valid_from: date("2025-07-02"),
+            schedule: WeeklySchedule::spread_over_weekdays(20.0),
+            note: None,
+        };
+        let schedules =
+            ScheduleHistory::new(WeeklySchedule::spread_over_weekdays(40.0), vec![contract]);
+        let range = DateRange::new(date("2025-07-01"), date("2025-07-02")).unwrap();
+
+        let plan = plan_absences(&range, None, &schedules, |_| Vec::new());
+        assert_eq!(
+            plan.iter()
+                .map(|day| match day {
+                    PlannedDay::Add { hours, .. } => *hours,
+                    PlannedDay::Skip { .. } => 0.0,
+                })
+                .collect::<Vec<_>>(),
+            vec![8.0, 4.0]
+        );
+    }
+}
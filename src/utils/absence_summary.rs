@@ -0,0 +1,170 @@
+use chrono::Datelike;
+use std::collections::BTreeMap;
+
+use crate::utils::schedule::ScheduleHistory;
+use crate::wad_data::{AbsenceRecord, AbsenceType};
+
+/// Hours and working days taken by absences
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AbsenceTotal {
+    pub hours: f64,
+    /// Hours relative to the target of each day, so a half day counts as 0.5
+    pub days: f64,
+}
+
+impl AbsenceTotal {
+    fn add(&mut self, other: AbsenceTotal) {
+        self.hours += other.hours;
+        self.days += other.days;
+    }
+}
+
+/// Absence totals of a calendar year, per absence type
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearSummary {
+    pub year: i32,
+    /// Totals per type, ordered by the type's name
+    pub by_type: Vec<(AbsenceType, AbsenceTotal)>,
+}
+
+impl YearSummary {
+    pub fn total(&self) -> AbsenceTotal {
+        let mut total = AbsenceTotal::default();
+        for (_, type_total) in &self.by_type {
+            total.add(*type_total);
+        }
+        total
+    }
+}
+
+/// Get the share of a working day taken by an absence
+///
+/// The share is the absence's hours divided by that day's target, so a half
+/// day counts as 0.5. Absences on days without a target count as a full day,
+/// as there's nothing to relate them to.
+pub fn absence_days(record: &AbsenceRecord, schedules: &ScheduleHistory) -> f64 {
+    let target_hours = schedules
+        .schedule_on(record.date)
+        .hours(record.date.weekday());
+    if target_hours > 0.0 {
+        record.hours / target_hours
+    } else {
+        1.0
+    }
+}
+
+/// Sum up absences per year and type, counting days like `absence_days`
+pub fn summarize_absences(
+    records: &[AbsenceRecord],
+    schedules: &ScheduleHistory,
+) -> Vec<YearSummary> {
+    let mut years: BTreeMap<i32, Vec<(AbsenceType, AbsenceTotal)>> = BTreeMap::new();
+
+    for record in records {
+        let share = AbsenceTotal {
+            hours: record.hours,
+            days: absence_days(record, schedules),
+        };
+
+        let by_type = years.entry(record.date.year()).or_default();
+        match by_type
+            .iter_mut()
+            .find(|(absence_type, _)| *absence_type == record.absence_type)
+        {
+            Some((_, total)) => total.add(share),
+            None => by_type.push((record.absence_type.clone(), share)),
+        }
+    }
+
+    years
+        .into_iter()
+        .map(|(year, mut by_type)| {
+            by_type.sort_by_key(|(absence_type, _)| absence_type.to_string());
+            YearSummary { year, by_type }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::schedule::WeeklySchedule;
+    use chrono::NaiveDate;
+    use ulid::Ulid;
+
+    fn record(date: &str, hours: f64, absence_type: AbsenceType) -> AbsenceRecord {
+        AbsenceRecord {
+            id: Ulid::new(),
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            hours,
+            absence_type,
+            note: None,
+            ical_uid: None,
+        }
+    }
+
+    fn schedules() -> ScheduleHistory {
+        ScheduleHistory::new("8,8,8,8,4".parse::<WeeklySchedule>().unwrap(), Vec::new())
+    }
+
+    #[test]
+    fn test_summarize_per_year_and_type() {
+        let records = vec![
+            // Tuesday and Friday, a half day on the short Friday is 2 hours
+            record("2024-12-31", 8.0, AbsenceType::Vacation),
+            record("2025-01-03", 2.0, AbsenceType::Sick),
+            record("2025-01-06", 8.0, AbsenceType::Sick),
+            record("2025-01-07", 8.0, AbsenceType::Vacation),
+        ];
+
+        let summaries = summarize_absences(&records, &schedules());
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].year, 2024);
+        assert_eq!(
+            summaries[0].by_type,
+            vec![(
+                AbsenceType::Vacation,
+                AbsenceTotal {
+                    hours: 8.0,
+                    days: 1.0
+                }
+            )]
+        );
+
+        assert_eq!(summaries[1].year, 2025);
+        assert_eq!(
+            summaries[1].by_type,
+            vec![
+                (
+                    AbsenceType::Sick,
+                    AbsenceTotal {
+                        hours: 10.0,
+                        days: 1.5
+                    }
+                ),
+                (
+                    AbsenceType::Vacation,
+                    AbsenceTotal {
+                        hours: 8.0,
+                        days: 1.0
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            summaries[1].total(),
+            AbsenceTotal {
+                hours: 18.0,
+                days: 2.5
+            }
+        );
+    }
+
+    #[test]
+    fn test_summarize_counts_day_off_as_full_day() {
+        // 2025-01-04 is a Saturday
+        let records = vec![record("2025-01-04", 3.0, AbsenceType::Holiday)];
+        let summaries = summarize_absences(&records, &schedules());
+        assert_eq!(summaries[0].total().days, 1.0);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::utils::date::{DayTimeBreakdown, Week};
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// A single day of the balance account: what was worked and what was expected
+#[derive(Debug, Clone)]
+pub struct BalanceDay {
+    pub date: NaiveDate,
+    pub breakdown: DayTimeBreakdown,
+    pub expected: Duration,
+}
+
+impl BalanceDay {
+    pub fn new(date: NaiveDate, breakdown: DayTimeBreakdown, expected: Duration) -> Self {
+        Self {
+            date,
+            breakdown,
+            expected,
+        }
+    }
+
+    /// Difference between worked (including absences) and expected time
+    pub fn delta(&self) -> Duration {
+        self.breakdown.total_duration() - self.expected
+    }
+}
+
+/// Summary of one period (week or month) of the balance account
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceEntry {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub worked: Duration,
+    pub expected: Duration,
+    /// Running balance at the end of this period
+    pub balance: Duration,
+}
+
+impl BalanceEntry {
+    pub fn delta(&self) -> Duration {
+        self.worked - self.expected
+    }
+}
+
+/// Overtime/flextime balance account built from daily breakdowns
+#[derive(Debug, Clone)]
+pub struct BalanceLedger {
+    pub initial_balance: Duration,
+    pub days: Vec<BalanceDay>,
+}
+
+impl BalanceLedger {
+    /// Create a ledger from an initial balance and days sorted by date
+    pub fn new(initial_balance: Duration, days: Vec<BalanceDay>) -> Self {
+        Self {
+            initial_balance,
+            days,
+        }
+    }
+
+    /// Get the balance after the last day of the ledger
+    pub fn balance(&self) -> Duration {
+        self.days
+            .iter()
+            .fold(self.initial_balance, |acc, day| acc + day.delta())
+    }
+
+    /// Summarize the ledger per week (Monday to Sunday)
+    pub fn by_week(&self) -> Vec<BalanceEntry> {
+        self.entries_by(|date| Week::containing(date).start)
+    }
+
+    /// Summarize the ledger per calendar month
+    pub fn by_month(&self) -> Vec<BalanceEntry> {
+        self.entries_by(|date| date.with_day(1).unwrap())
+    }
+
+    /// Group consecutive days sharing the same period key into entries
+    fn entries_by(&self, period_of: impl Fn(NaiveDate) -> NaiveDate) -> Vec<BalanceEntry> {
+        let mut entries: Vec<BalanceEntry> = Vec::new();
+        let mut current_period = None;
+        let mut balance = self.initial_balance;
+
+        for day in &self.days {
+            let period = period_of(day.date);
+            let worked = day.breakdown.total_duration();
+            balance += day.delta();
+
+            match entries.last_mut() {
+                Some(entry) if current_period == Some(period) => {
+                    entry.to = day.date;
+                    entry.worked += worked;
+                    entry.expected += day.expected;
+                    entry.balance = balance;
+                }
+                _ => {
+                    current_period = Some(period);
+                    entries.push(BalanceEntry {
+                        from: day.date,
+                        to: day.date,
+                        worked,
+                        expected: day.expected,
+                        balance,
+                    });
+                }
+            }
+        }
+
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad_data::{AbsenceRecord, AbsenceType};
+    use ulid::Ulid;
+
+    fn create_test_day(date: &str, worked_hours: i64, expected_hours: i64) -> BalanceDay {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        BalanceDay::new(
+            date,
+            DayTimeBreakdown::new(Duration::hours(worked_hours), vec![]),
+            Duration::hours(expected_hours),
+        )
+    }
+
+    #[test]
+    fn test_balance_with_initial_balance() {
+        let ledger = BalanceLedger::new(
+            Duration::hours(5),
+            vec![
+                create_test_day("2025-03-03", 9, 8),
+                create_test_day("2025-03-04", 6, 8),
+                create_test_day("2025-03-08", 2, 0),
+            ],
+        );
+
+        assert_eq!(ledger.balance(), Duration::hours(6));
+    }
+
+    #[test]
+    fn test_absences_count_as_worked_time() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let vacation = AbsenceRecord {
+            id: Ulid::new(),
+            date,
+            hours: 8.0,
+            absence_type: AbsenceType::Vacation,
+            note: None,
+            ical_uid: None,
+        };
+        let day = BalanceDay::new(
+            date,
+            DayTimeBreakdown::new(Duration::zero(), vec![vacation]),
+            Duration::hours(8),
+        );
+
+        assert_eq!(day.delta(), Duration::zero());
+    }
+
+    #[test]
+    fn test_by_week() {
+        let ledger = BalanceLedger::new(
+            Duration::zero(),
+            vec![
+                // Week of 2025-03-03
+                create_test_day("2025-03-06", 10, 8),
+                create_test_day("2025-03-07", 8, 8),
+                // Week of 2025-03-10
+                create_test_day("2025-03-10", 4, 8),
+            ],
+        );
+
+        let weeks = ledger.by_week();
+        assert_eq!(weeks.len(), 2);
+
+        assert_eq!(weeks[0].from, NaiveDate::from_ymd_opt(2025, 3, 6).unwrap());
+        assert_eq!(weeks[0].to, NaiveDate::from_ymd_opt(2025, 3, 7).unwrap());
+        assert_eq!(weeks[0].worked, Duration::hours(18));
+        assert_eq!(weeks[0].expected, Duration::hours(16));
+        assert_eq!(weeks[0].delta(), Duration::hours(2));
+        assert_eq!(weeks[0].balance, Duration::hours(2));
+
+        assert_eq!(weeks[1].delta(), Duration::hours(-4));
+        assert_eq!(weeks[1].balance, Duration::hours(-2));
+    }
+
+    #[test]
+    fn test_by_month() {
+        let ledger = BalanceLedger::new(
+            Duration::hours(-1),
+            vec![
+                create_test_day("2025-02-27", 9, 8),
+                create_test_day("2025-02-28", 9, 8),
+                create_test_day("2025-03-01", 0, 0),
+                create_test_day("2025-03-03", 7, 8),
+            ],
+        );
+
+        let months = ledger.by_month();
+        assert_eq!(months.len(), 2);
+
+        assert_eq!(months[0].delta(), Duration::hours(2));
+        assert_eq!(months[0].balance, Duration::hours(1));
+        assert_eq!(months[1].from, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+        assert_eq!(months[1].delta(), Duration::hours(-1));
+        assert_eq!(months[1].balance, Duration::zero());
+        assert_eq!(months[1].balance, ledger.balance());
+    }
+}
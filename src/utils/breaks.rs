@@ -0,0 +1,79 @@
+use chrono::Duration;
+
+/// A minimum break that is mandatory once more than `worked_over` has been worked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakRule {
+    pub worked_over: Duration,
+    pub minimum_break: Duration,
+}
+
+/// Set of break rules, of which the strictest applicable one is required
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakRules {
+    rules: Vec<BreakRule>,
+}
+
+impl BreakRules {
+    pub fn new(rules: Vec<BreakRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Get the break required for the given work time
+    pub fn required_break(&self, worked: Duration) -> Duration {
+        self.rules
+            .iter()
+            .filter(|rule| worked > rule.worked_over)
+            .map(|rule| rule.minimum_break)
+            .max()
+            .unwrap_or_else(Duration::zero)
+    }
+
+    /// Get the part of the required break that hasn't been taken yet
+    pub fn owed_break(&self, worked: Duration, taken: Duration) -> Duration {
+        (self.required_break(worked) - taken).max(Duration::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn german_rules() -> BreakRules {
+        BreakRules::new(vec![
+            BreakRule {
+                worked_over: Duration::hours(6),
+                minimum_break: Duration::minutes(30),
+            },
+            BreakRule {
+                worked_over: Duration::hours(9),
+                minimum_break: Duration::minutes(45),
+            },
+        ])
+    }
+
+    #[test_case(5 * 60, 0; "short day")]
+    #[test_case(6 * 60, 0; "exactly six hours")]
+    #[test_case(6 * 60 + 1, 30; "just over six hours")]
+    #[test_case(9 * 60, 30; "exactly nine hours")]
+    #[test_case(10 * 60, 45; "long day")]
+    fn test_required_break(worked_minutes: i64, expected_minutes: i64) {
+        assert_eq!(
+            german_rules().required_break(Duration::minutes(worked_minutes)),
+            Duration::minutes(expected_minutes)
+        );
+    }
+
+    #[test_case(8, 0, 30; "no break taken")]
+    #[test_case(8, 20, 10; "partial break taken")]
+    #[test_case(8, 60, 0; "long break taken")]
+    fn test_owed_break(worked_hours: i64, taken_minutes: i64, expected_minutes: i64) {
+        assert_eq!(
+            german_rules().owed_break(
+                Duration::hours(worked_hours),
+                Duration::minutes(taken_minutes)
+            ),
+            Duration::minutes(expected_minutes)
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use crate::utils::breaks::BreakRules;
+use crate::utils::date::Timezone;
+use crate::watson::frame::Frames;
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+/// A breach of the working time rules on a single day
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// Less break was taken than required for the time worked
+    MissingBreak {
+        worked: Duration,
+        required: Duration,
+        taken: Duration,
+    },
+    /// More than the daily maximum was worked
+    MaxDailyExceeded { worked: Duration, maximum: Duration },
+    /// The rest since the previous working day was too short
+    InsufficientRest { rest: Duration, minimum: Duration },
+}
+
+/// All violations found on one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayViolations {
+    pub date: NaiveDate,
+    pub violations: Vec<Violation>,
+}
+
+/// Rules a working day has to comply with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceRules {
+    pub breaks: BreakRules,
+    /// Shortest gap between frames that counts towards the break
+    pub min_break_segment: Duration,
+    pub max_daily: Duration,
+    pub min_rest: Duration,
+}
+
+impl ComplianceRules {
+    /// Check the frames of each day in the given timezone against the rules
+    ///
+    /// Days without frames are skipped. Frames before the first day are only
+    /// used to determine the rest before it, so callers should include the
+    /// day before the period they are interested in.
+    pub fn check(&self, frames: &Frames, timezone: Timezone) -> Vec<DayViolations> {
+        let frames_by_date: BTreeMap<NaiveDate, Frames> =
+            frames.by_date(timezone).into_iter().collect();
+        let mut result = Vec::new();
+
+        for (date, day_frames) in &frames_by_date {
+            if day_frames.frames.is_empty() {
+                continue;
+            }
+
+            let mut violations = self.check_day(day_frames);
+            if let Some(violation) = self.check_rest(frames, *date, timezone) {
+                violations.push(violation);
+            }
+
+            if !violations.is_empty() {
+                result.push(DayViolations {
+                    date: *date,
+                    violations,
+                });
+            }
+        }
+
+        result
+    }
+
+    fn check_day(&self, frames: &Frames) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let worked = frames.total_duration();
+        let taken = frames.break_duration_of_segments(self.min_break_segment);
+
+        let required = self.breaks.required_break(worked);
+        if taken < required {
+            violations.push(Violation::MissingBreak {
+                worked,
+                required,
+                taken,
+            });
+        }
+
+        if worked > self.max_daily {
+            violations.push(Violation::MaxDailyExceeded {
+                worked,
+                maximum: self.max_daily,
+            });
+        }
+
+        violations
+    }
+
+    /// Check the rest before the first frame started on the given date
+    ///
+    /// Uses the frames as tracked rather than split up by day, so work
+    /// continuing past midnight ends the rest when it really ends.
+    fn check_rest(
+        &self,
+        frames: &Frames,
+        date: NaiveDate,
+        timezone: Timezone,
+    ) -> Option<Violation> {
+        let start = frames
+            .frames
+            .iter()
+            .filter(|frame| timezone.date_of(&frame.start) == date)
+            .map(|frame| frame.start)
+            .min()?;
+        let previous_end = frames
+            .frames
+            .iter()
+            .filter(|frame| frame.start < start)
+            .map(|frame| frame.end())
+            .max()?;
+        let rest = start - previous_end;
+
+        // Work continuing across midnight is a single working period, not a
+        // new working day without rest
+        if rest <= Duration::zero() || rest >= self.min_rest {
+            return None;
+        }
+
+        Some(Violation::InsufficientRest {
+            rest,
+            minimum: self.min_rest,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::breaks::BreakRule;
+    use crate::watson::frame::Frame;
+    use chrono::{TimeZone, Utc};
+
+    fn rules() -> ComplianceRules {
+        ComplianceRules {
+            breaks: BreakRules::new(vec![
+                BreakRule {
+                    worked_over: Duration::hours(6),
+                    minimum_break: Duration::minutes(30),
+                },
+                BreakRule {
+                    worked_over: Duration::hours(9),
+                    minimum_break: Duration::minutes(45),
+                },
+            ]),
+            min_break_segment: Duration::minutes(15),
+            max_daily: Duration::hours(10),
+            min_rest: Duration::hours(11),
+        }
+    }
+
+    /// Frames on 2025-03-<day> given as (start hour, start minute, end hour, end minute)
+    fn day(day: u32, spans: &[(u32, u32, u32, u32)]) -> (NaiveDate, Frames) {
+        let frames = spans
+            .iter()
+            .map(|&(start_h, start_m, end_h, end_m)| Frame {
+                id: format!("{}-{}{}", day, start_h, start_m),
+                project: "project".to_string(),
+                start: Utc
+                    .with_ymd_and_hms(2025, 3, day, start_h, start_m, 0)
+                    .unwrap(),
+                stop: Some(Utc.with_ymd_and_hms(2025, 3, day, end_h, end_m, 0).unwrap()),
+                tags: vec![],
+            })
+            .collect::<Vec<_>>();
+
+        (
+            NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            Frames::from(frames),
+        )
+    }
+
+    fn check(days: Vec<(NaiveDate, Frames)>) -> Vec<DayViolations> {
+        let frames: Vec<Frame> = days
+            .into_iter()
+            .flat_map(|(_, frames)| frames.frames)
+            .collect();
+        rules().check(&Frames::from(frames), "+00:00".parse().unwrap())
+    }
+
+    #[test]
+    fn test_compliant_day() {
+        let violations = check(vec![day(3, &[(8, 0, 12, 0), (12, 30, 16, 30)])]);
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_missing_break() {
+        let violations = check(vec![day(3, &[(8, 0, 12, 0), (12, 15, 15, 0)])]);
+
+        assert_eq!(
+            violations,
+            vec![DayViolations {
+                date: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+                violations: vec![Violation::MissingBreak {
+                    worked: Duration::minutes(405),
+                    required: Duration::minutes(30),
+                    taken: Duration::minutes(15),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_short_gaps_do_not_count_as_break() {
+        // Two minutes between frames and a 14 minute pause are too short to be breaks
+        let violations = check(vec![day(
+            3,
+            &[
+                (8, 0, 10, 0),
+                (10, 2, 12, 0),
+                (12, 14, 12, 44),
+                (13, 0, 15, 0),
+            ],
+        )]);
+
+        assert_eq!(
+            violations[0].violations,
+            vec![Violation::MissingBreak {
+                worked: Duration::minutes(388),
+                required: Duration::minutes(30),
+                taken: Duration::minutes(16),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_long_day_needs_long_break_and_exceeds_maximum() {
+        let violations = check(vec![day(3, &[(7, 0, 12, 0), (12, 30, 18, 30)])]);
+
+        assert_eq!(
+            violations[0].violations,
+            vec![
+                Violation::MissingBreak {
+                    worked: Duration::hours(11),
+                    required: Duration::minutes(45),
+                    taken: Duration::minutes(30),
+                },
+                Violation::MaxDailyExceeded {
+                    worked: Duration::hours(11),
+                    maximum: Duration::hours(10),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insufficient_rest() {
+        let violations = check(vec![
+            day(3, &[(16, 0, 22, 0)]),
+            day(4, &[(6, 0, 10, 0)]),
+            // Two days later, so plenty of rest
+            day(6, &[(6, 0, 10, 0)]),
+        ]);
+
+        assert_eq!(
+            violations,
+            vec![DayViolations {
+                date: NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+                violations: vec![Violation::InsufficientRest {
+                    rest: Duration::hours(8),
+                    minimum: Duration::hours(11),
+                }],
+            }]
+        );
+    }
+
+    fn night_shift() -> Frame {
+        Frame {
+            id: "night".to_string(),
+            project: "oncall".to_string(),
+            start: Utc.with_ymd_and_hms(2025, 3, 3, 20, 0, 0).unwrap(),
+            stop: Some(Utc.with_ymd_and_hms(2025, 3, 4, 2, 0, 0).unwrap()),
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_work_across_midnight_is_not_a_rest_violation() {
+        let frames = Frames::from(vec![night_shift()]);
+
+        assert!(rules().check(&frames, "+00:00".parse().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_rest_after_night_shift_ends_when_the_shift_ends() {
+        let (_, morning) = day(4, &[(8, 0, 12, 0)]);
+        let frames = Frames::from(vec![night_shift(), morning.frames[0].clone()]);
+
+        assert_eq!(
+            rules().check(&frames, "+00:00".parse().unwrap()),
+            vec![DayViolations {
+                date: NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+                violations: vec![Violation::InsufficientRest {
+                    rest: Duration::hours(6),
+                    minimum: Duration::hours(11),
+                }],
+            }]
+        );
+    }
+}
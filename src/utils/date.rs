@@ -1,6 +1,17 @@
 use crate::wad_data::AbsenceRecord;
-use chrono::{Datelike, Duration, Local, NaiveDate};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc, Weekday,
+};
 use derive_more::{Deref, From};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Convert fractional hours (as used in config and absence records) into a `Duration`
+pub fn hours_to_duration(hours: f64) -> Duration {
+    Duration::seconds((hours * 3600.0).round() as i64)
+}
 
 /// Type-safe wrapper for daily worktime durations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deref, From)]
@@ -10,8 +21,177 @@ pub struct DailyWorktime(pub Duration);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deref, From)]
 pub struct WeeklyWorktime(pub Duration);
 
+/// The timezone used to assign frames and absences to calendar days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+    /// The system's local timezone
+    Local,
+    /// A fixed offset from UTC, e.g. `+02:00`
+    Fixed(FixedOffset),
+    /// A named IANA timezone, e.g. `Europe/Berlin`
+    Named(chrono_tz::Tz),
+}
+
+impl Timezone {
+    /// Get the wall-clock date and time of the given point in time in this timezone
+    pub fn local_datetime_of(&self, datetime: &DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Timezone::Local => datetime.with_timezone(&Local).naive_local(),
+            Timezone::Fixed(offset) => datetime.with_timezone(offset).naive_local(),
+            Timezone::Named(tz) => datetime.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// Get the calendar date of the given point in time in this timezone
+    pub fn date_of(&self, datetime: &DateTime<Utc>) -> NaiveDate {
+        self.local_datetime_of(datetime).date()
+    }
+
+    /// Get today's date in this timezone
+    pub fn today(&self) -> NaiveDate {
+        self.date_of(&Utc::now())
+    }
+
+    /// Get the point in time at which the given day starts in this timezone
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        match self {
+            Timezone::Local => start_of_day_in(&Local, date),
+            Timezone::Fixed(offset) => start_of_day_in(offset, date),
+            Timezone::Named(tz) => start_of_day_in(tz, date),
+        }
+    }
+}
+
+/// Find the first instant of a day, even if midnight is skipped by a DST change
+fn start_of_day_in<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(midnight + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.eq_ignore_ascii_case("local") {
+            return Ok(Timezone::Local);
+        }
+
+        if let Ok(offset) = s.parse::<FixedOffset>() {
+            return Ok(Timezone::Fixed(offset));
+        }
+
+        s.parse::<chrono_tz::Tz>().map(Timezone::Named).map_err(|_| {
+            format!(
+                "Invalid timezone '{}'. Use 'local', an offset like '+02:00' or a name like 'Europe/Berlin'",
+                s
+            )
+        })
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timezone::Local => write!(f, "local"),
+            Timezone::Fixed(offset) => write!(f, "{}", offset),
+            Timezone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+impl Serialize for Timezone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timezone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A half-open span of time, from `start` (inclusive) to `end` (exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self { start, end }
+    }
+
+    /// Create a window covering all days from `from` to `to` (inclusive) in the given timezone
+    pub fn dates(from: NaiveDate, to: NaiveDate, timezone: Timezone) -> Self {
+        Self::new(
+            timezone.start_of_day(from),
+            timezone.start_of_day(to + Duration::days(1)),
+        )
+    }
+
+    /// Create a window covering a single day in the given timezone
+    pub fn day(date: NaiveDate, timezone: Timezone) -> Self {
+        Self::dates(date, date, timezone)
+    }
+
+    /// Create a window covering a whole week in the given timezone
+    pub fn week(week: &Week, timezone: Timezone) -> Self {
+        Self::dates(week.start, week.end, timezone)
+    }
+}
+
+/// A date given on the command line, which may be relative to today
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateArg {
+    Today,
+    Yesterday,
+    Tomorrow,
+    Date(NaiveDate),
+}
+
+impl DateArg {
+    /// Resolve to a calendar date, using the given timezone for relative dates
+    pub fn resolve(&self, timezone: Timezone) -> NaiveDate {
+        match self {
+            DateArg::Today => timezone.today(),
+            DateArg::Yesterday => timezone.today() - Duration::days(1),
+            DateArg::Tomorrow => timezone.today() + Duration::days(1),
+            DateArg::Date(date) => *date,
+        }
+    }
+}
+
+impl FromStr for DateArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "today" => Ok(DateArg::Today),
+            "yesterday" => Ok(DateArg::Yesterday),
+            "tomorrow" => Ok(DateArg::Tomorrow),
+            _ => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map(DateArg::Date)
+                .map_err(|_| {
+                    "Invalid date format. Use YYYY-MM-DD, 'today', 'yesterday', or 'tomorrow'"
+                        .to_string()
+                }),
+        }
+    }
+}
+
 /// Represents a week with Monday as the first day and Sunday as the last day
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Week {
     pub start: NaiveDate, // Monday
     pub end: NaiveDate,   // Sunday
@@ -27,34 +207,312 @@ impl Week {
         }
     }
 
-    /// Get the current week (Monday to Sunday)
-    pub fn current() -> Self {
-        let today = Local::now().date_naive();
-        let days_from_monday = today.weekday().num_days_from_monday();
-        let monday = today - Duration::days(days_from_monday as i64);
+    /// Get the week (Monday to Sunday) containing the given date
+    pub fn containing(date: NaiveDate) -> Self {
+        let days_from_monday = date.weekday().num_days_from_monday();
+        let monday = date - Duration::days(days_from_monday as i64);
         Self::new(monday)
     }
 
+    /// Get the current week (Monday to Sunday) in the given timezone
+    pub fn current(timezone: Timezone) -> Self {
+        Self::containing(timezone.today())
+    }
+
     /// Get a week offset by the given number of weeks from the current week
     /// offset = 0: current week
     /// offset = 1: last week
     /// offset = 2: two weeks ago
-    pub fn offset(weeks_back: i32) -> Self {
-        let current_week = Self::current();
+    pub fn offset(weeks_back: i32, timezone: Timezone) -> Self {
+        let current_week = Self::current(timezone);
         let target_monday = current_week.start - Duration::weeks(weeks_back as i64);
         Self::new(target_monday)
     }
 
     /// Get the last N weeks
     /// Returns weeks from oldest to newest
-    pub fn last_n_weeks(n: u32) -> Vec<Self> {
+    pub fn last_n_weeks(n: u32, timezone: Timezone) -> Vec<Self> {
         (0..n)
-            .map(|i| Self::offset(i as i32))
+            .map(|i| Self::offset(i as i32, timezone))
             .rev() // Reverse to get oldest to newest
             .collect()
     }
 }
 
+/// An inclusive range of calendar days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl DateRange {
+    /// Create a range from `from` to `to` (inclusive)
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Result<Self, String> {
+        if from > to {
+            return Err(format!(
+                "Start date {} is after end date {}",
+                from.format("%Y-%m-%d"),
+                to.format("%Y-%m-%d")
+            ));
+        }
+        Ok(Self { from, to })
+    }
+
+    /// Create a range covering a single day
+    pub fn day(date: NaiveDate) -> Self {
+        Self {
+            from: date,
+            to: date,
+        }
+    }
+
+    /// Create a range covering all weeks from `first` to `last`
+    pub fn weeks(first: &Week, last: &Week) -> Self {
+        Self {
+            from: first.start,
+            to: last.end,
+        }
+    }
+
+    /// Create a range covering all months from `first` to `last`
+    pub fn months(first: &Month, last: &Month) -> Self {
+        Self {
+            from: first.first_day(),
+            to: last.last_day(),
+        }
+    }
+
+    /// Create a range covering a whole calendar year
+    pub fn year(year: i32) -> Result<Self, String> {
+        match (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year, 12, 31),
+        ) {
+            (Some(from), Some(to)) => Ok(Self { from, to }),
+            _ => Err(format!("Invalid year: {}", year)),
+        }
+    }
+
+    pub fn is_single_day(&self) -> bool {
+        self.from == self.to
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    /// Get all days of this range
+    pub fn dates(&self) -> impl Iterator<Item = NaiveDate> {
+        let to = self.to;
+        self.from.iter_days().take_while(move |date| *date <= to)
+    }
+
+    /// Get all weeks (Monday to Sunday) overlapping this range
+    pub fn weeks_covered(&self) -> Vec<Week> {
+        let last_week = Week::containing(self.to);
+        let mut weeks = vec![Week::containing(self.from)];
+        while weeks[weeks.len() - 1].start < last_week.start {
+            let next_monday = weeks[weeks.len() - 1].start + Duration::weeks(1);
+            weeks.push(Week::new(next_monday));
+        }
+        weeks
+    }
+
+    /// Get all months overlapping this range
+    pub fn months_covered(&self) -> Vec<Month> {
+        let last_month = Month::containing(self.to);
+        let mut months = vec![Month::containing(self.from)];
+        while months[months.len() - 1] < last_month {
+            months.push(months[months.len() - 1].next());
+        }
+        months
+    }
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_single_day() {
+            write!(f, "{}", self.from.format("%Y-%m-%d"))
+        } else {
+            write!(
+                f,
+                "{} to {}",
+                self.from.format("%Y-%m-%d"),
+                self.to.format("%Y-%m-%d")
+            )
+        }
+    }
+}
+
+/// A calendar month
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Month {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl Month {
+    /// Get the month containing the given date
+    pub fn containing(date: NaiveDate) -> Self {
+        Self {
+            year: date.year(),
+            month: date.month(),
+        }
+    }
+
+    /// Get the current month in the given timezone
+    pub fn current(timezone: Timezone) -> Self {
+        Self::containing(timezone.today())
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).unwrap()
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        self.next().first_day() - Duration::days(1)
+    }
+
+    /// Get the weeks (Monday to Sunday) overlapping this month
+    pub fn weeks(&self) -> Vec<Week> {
+        DateRange::months(self, self).weeks_covered()
+    }
+
+    pub fn next(&self) -> Self {
+        if self.month == 12 {
+            Self {
+                year: self.year + 1,
+                month: 1,
+            }
+        } else {
+            Self {
+                year: self.year,
+                month: self.month + 1,
+            }
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        if self.month == 1 {
+            Self {
+                year: self.year - 1,
+                month: 12,
+            }
+        } else {
+            Self {
+                year: self.year,
+                month: self.month - 1,
+            }
+        }
+    }
+
+    /// Get the last N months including the current one
+    /// Returns months from oldest to newest
+    pub fn last_n_months(n: u32, timezone: Timezone) -> Vec<Self> {
+        let mut months = Vec::new();
+        let mut month = Self::current(timezone);
+        for _ in 0..n {
+            months.push(month);
+            month = month.previous();
+        }
+        months.reverse();
+        months
+    }
+}
+
+impl FromStr for Month {
+    type Err = String;
+
+    /// Parse a month given as `YYYY-MM`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveDate::parse_from_str(&format!("{}-01", s), "%Y-%m-%d")
+            .map(Self::containing)
+            .map_err(|_| format!("Invalid month '{}'. Use YYYY-MM", s))
+    }
+}
+
+/// Months selected on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonthSelector {
+    /// A specific month (`2025-03`)
+    Month(Month),
+    /// The month before the current one (`last`)
+    Last,
+    /// The last N months including the current one (`3`)
+    Count(u32),
+}
+
+impl MonthSelector {
+    /// Resolve to the selected months, oldest first
+    pub fn resolve(&self, timezone: Timezone) -> Vec<Month> {
+        match self {
+            MonthSelector::Month(month) => vec![*month],
+            MonthSelector::Last => vec![Month::current(timezone).previous()],
+            MonthSelector::Count(n) => Month::last_n_months(*n, timezone),
+        }
+    }
+}
+
+/// Formats the month as `YYYY-MM`, the inverse of parsing
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for MonthSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("last") {
+            return Ok(MonthSelector::Last);
+        }
+
+        if let Ok(count) = s.parse::<u32>() {
+            if count == 0 {
+                return Err("The number of months must be at least 1".to_string());
+            }
+            return Ok(MonthSelector::Count(count));
+        }
+
+        s.parse::<Month>().map(MonthSelector::Month).map_err(|_| {
+            format!(
+                "Invalid month selection '{}'. Use YYYY-MM, 'last' or a number of months",
+                s
+            )
+        })
+    }
+}
+
+impl FromStr for Week {
+    type Err = String;
+
+    /// Parse an ISO week given as `YYYY-Www` (e.g. `2025-W10`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid week '{}'. Use YYYY-Www (e.g. 2025-W10)", s);
+
+        let (year, week) = s.split_once(['-']).ok_or_else(invalid)?;
+        let week = week
+            .strip_prefix(['W', 'w'])
+            .and_then(|week| week.parse::<u32>().ok())
+            .ok_or_else(invalid)?;
+        let year = year.parse::<i32>().map_err(|_| invalid())?;
+
+        NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .map(Self::new)
+            .ok_or_else(invalid)
+    }
+}
+
+/// Formats the ISO week as `YYYY-Www`, the inverse of parsing
+impl fmt::Display for Week {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iso_week = self.start.iso_week();
+        write!(f, "{}-W{:02}", iso_week.year(), iso_week.week())
+    }
+}
+
 /// Data structure representing a day's time breakdown: work + absences
 #[derive(Debug, Clone)]
 pub struct DayTimeBreakdown {
@@ -88,6 +546,128 @@ impl DayTimeBreakdown {
 mod tests {
     use super::*;
     use chrono::Weekday;
+    use test_case::test_case;
+
+    fn fixed(offset: &str) -> Timezone {
+        Timezone::Fixed(offset.parse().unwrap())
+    }
+
+    #[test_case("local", Timezone::Local; "local")]
+    #[test_case("", Timezone::Local; "empty")]
+    #[test_case("+02:00", fixed("+02:00"); "positive offset")]
+    #[test_case("-05:30", fixed("-05:30"); "negative offset")]
+    #[test_case("Europe/Berlin", Timezone::Named(chrono_tz::Europe::Berlin); "named")]
+    #[test_case("UTC", Timezone::Named(chrono_tz::UTC); "utc")]
+    fn test_timezone_parse(input: &str, expected: Timezone) {
+        assert_eq!(input.parse::<Timezone>().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_timezone_parse_invalid() {
+        assert!("Mars/Olympus_Mons".parse::<Timezone>().is_err());
+    }
+
+    #[test_case("Europe/Berlin"; "named")]
+    #[test_case("+02:00"; "offset")]
+    #[test_case("local"; "local")]
+    fn test_timezone_display_roundtrip(input: &str) {
+        let timezone: Timezone = input.parse().unwrap();
+        assert_eq!(timezone.to_string().parse::<Timezone>().unwrap(), timezone);
+    }
+
+    // 2024-01-15 23:30 UTC
+    #[test_case("+00:00", "2024-01-15"; "utc")]
+    #[test_case("+02:00", "2024-01-16"; "east of utc")]
+    #[test_case("-08:00", "2024-01-15"; "west of utc")]
+    fn test_timezone_date_of_late_evening(offset: &str, expected: &str) {
+        let datetime = Utc.with_ymd_and_hms(2024, 1, 15, 23, 30, 0).unwrap();
+        let expected = NaiveDate::parse_from_str(expected, "%Y-%m-%d").unwrap();
+        assert_eq!(fixed(offset).date_of(&datetime), expected);
+    }
+
+    // 2024-01-16 02:00 UTC
+    #[test_case("+02:00", "2024-01-16"; "east of utc")]
+    #[test_case("-08:00", "2024-01-15"; "west of utc")]
+    fn test_timezone_date_of_early_morning(offset: &str, expected: &str) {
+        let datetime = Utc.with_ymd_and_hms(2024, 1, 16, 2, 0, 0).unwrap();
+        let expected = NaiveDate::parse_from_str(expected, "%Y-%m-%d").unwrap();
+        assert_eq!(fixed(offset).date_of(&datetime), expected);
+    }
+
+    #[test]
+    fn test_timezone_start_of_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 16).unwrap();
+
+        assert_eq!(
+            fixed("+02:00").start_of_day(date),
+            Utc.with_ymd_and_hms(2024, 1, 15, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            fixed("-08:00").start_of_day(date),
+            Utc.with_ymd_and_hms(2024, 1, 16, 8, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timezone_start_of_day_with_dst_gap() {
+        // Midnight doesn't exist in Santiago on the day DST starts
+        let timezone = Timezone::Named(chrono_tz::America::Santiago);
+        let date = NaiveDate::from_ymd_opt(2024, 9, 8).unwrap();
+
+        let start = timezone.start_of_day(date);
+        assert_eq!(timezone.date_of(&start), date);
+    }
+
+    #[test]
+    fn test_time_window_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let window = TimeWindow::day(date, fixed("-03:00"));
+
+        assert_eq!(
+            window.start,
+            Utc.with_ymd_and_hms(2024, 1, 15, 3, 0, 0).unwrap()
+        );
+        assert_eq!(
+            window.end,
+            Utc.with_ymd_and_hms(2024, 1, 16, 3, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_time_window_week() {
+        let week = Week::new(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let window = TimeWindow::week(&week, fixed("+00:00"));
+
+        assert_eq!(
+            window.start,
+            Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            window.end,
+            Utc.with_ymd_and_hms(2024, 1, 22, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_date_arg_resolve() {
+        let timezone = fixed("+02:00");
+        let today = timezone.today();
+
+        assert_eq!("today".parse::<DateArg>().unwrap().resolve(timezone), today);
+        assert_eq!(
+            "Yesterday".parse::<DateArg>().unwrap().resolve(timezone),
+            today - Duration::days(1)
+        );
+        assert_eq!(
+            "tomorrow".parse::<DateArg>().unwrap().resolve(timezone),
+            today + Duration::days(1)
+        );
+        assert_eq!(
+            "2024-02-29".parse::<DateArg>().unwrap().resolve(timezone),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        assert!("29.02.2024".parse::<DateArg>().is_err());
+    }
 
     #[test]
     fn test_week_new() {
@@ -98,9 +678,22 @@ mod tests {
         assert_eq!(week.end, NaiveDate::from_ymd_opt(2023, 7, 23).unwrap()); // Sunday
     }
 
+    #[test]
+    fn test_week_containing() {
+        let monday = NaiveDate::from_ymd_opt(2023, 7, 17).unwrap();
+
+        for offset in 0..7 {
+            let date = monday + Duration::days(offset);
+            assert_eq!(Week::containing(date), Week::new(monday));
+        }
+
+        let next_monday = monday + Duration::days(7);
+        assert_eq!(Week::containing(next_monday), Week::new(next_monday));
+    }
+
     #[test]
     fn test_week_current() {
-        let week = Week::current();
+        let week = Week::current(Timezone::Local);
 
         // Start should be a Monday
         assert_eq!(week.start.weekday(), Weekday::Mon);
@@ -112,8 +705,8 @@ mod tests {
 
     #[test]
     fn test_week_offset() {
-        let current = Week::current();
-        let last_week = Week::offset(1);
+        let current = Week::current(Timezone::Local);
+        let last_week = Week::offset(1, Timezone::Local);
 
         // Last week should be 7 days earlier
         assert_eq!((current.start - last_week.start).num_days(), 7);
@@ -122,7 +715,7 @@ mod tests {
 
     #[test]
     fn test_last_n_weeks() {
-        let weeks = Week::last_n_weeks(4);
+        let weeks = Week::last_n_weeks(4, Timezone::Local);
 
         assert_eq!(weeks.len(), 4);
 
@@ -136,4 +729,129 @@ mod tests {
             assert_eq!((weeks[i].start - weeks[i - 1].start).num_days(), 7);
         }
     }
+
+    fn month(s: &str) -> Month {
+        s.parse().unwrap()
+    }
+
+    #[test_case("2025-03", "2025-03-01", "2025-03-31"; "march")]
+    #[test_case("2024-02", "2024-02-01", "2024-02-29"; "leap february")]
+    #[test_case("2025-12", "2025-12-01", "2025-12-31"; "december")]
+    fn test_month_days(input: &str, first: &str, last: &str) {
+        let month = month(input);
+        assert_eq!(month.first_day().to_string(), first);
+        assert_eq!(month.last_day().to_string(), last);
+    }
+
+    #[test]
+    fn test_month_weeks() {
+        // March 2025 starts on a Saturday and ends on a Monday
+        let weeks = month("2025-03").weeks();
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks[0].start.to_string(), "2025-02-24");
+        assert_eq!(weeks[5].start.to_string(), "2025-03-31");
+    }
+
+    #[test]
+    fn test_month_navigation() {
+        assert_eq!(month("2025-01").previous(), month("2024-12"));
+        assert_eq!(month("2024-12").next(), month("2025-01"));
+    }
+
+    #[test_case("2025-03", MonthSelector::Month(Month { year: 2025, month: 3 }); "month")]
+    #[test_case("last", MonthSelector::Last; "last")]
+    #[test_case("3", MonthSelector::Count(3); "count")]
+    fn test_month_selector_parse(input: &str, expected: MonthSelector) {
+        assert_eq!(input.parse::<MonthSelector>().unwrap(), expected);
+    }
+
+    #[test_case("2025-13"; "invalid month")]
+    #[test_case("march"; "name")]
+    #[test_case("0"; "zero months")]
+    fn test_month_selector_parse_invalid(input: &str) {
+        assert!(input.parse::<MonthSelector>().is_err());
+    }
+
+    #[test]
+    fn test_month_selector_count() {
+        let timezone = fixed("+00:00");
+        let months = MonthSelector::Count(3).resolve(timezone);
+
+        assert_eq!(months.len(), 3);
+        assert_eq!(months[2], Month::current(timezone));
+        assert_eq!(months[0], Month::current(timezone).previous().previous());
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test_case("2025-W10", "2025-03-03"; "week 10")]
+    #[test_case("2025-w01", "2024-12-30"; "first week starting in previous year")]
+    #[test_case("2026-W53", "2026-12-28"; "week 53")]
+    fn test_week_parse(input: &str, monday: &str) {
+        assert_eq!(input.parse::<Week>().unwrap(), Week::new(date(monday)));
+    }
+
+    #[test]
+    fn test_month_display() {
+        let month = Month {
+            year: 2025,
+            month: 3,
+        };
+        assert_eq!(month.to_string(), "2025-03");
+        assert_eq!("2025-03".parse::<Month>().unwrap(), month);
+    }
+
+    #[test_case("2025-03-03", "2025-W10"; "week 10")]
+    #[test_case("2024-12-30", "2025-W01"; "first week starting in previous year")]
+    fn test_week_display(monday: &str, expected: &str) {
+        assert_eq!(Week::new(date(monday)).to_string(), expected);
+    }
+
+    #[test_case("2025-10"; "missing w")]
+    #[test_case("2025-W54"; "week out of range")]
+    #[test_case("2025W10"; "missing dash")]
+    fn test_week_parse_invalid(input: &str) {
+        assert!(input.parse::<Week>().is_err());
+    }
+
+    #[test]
+    fn test_date_range_rejects_reversed_dates() {
+        assert!(DateRange::new(date("2025-03-02"), date("2025-03-01")).is_err());
+        assert!(DateRange::new(date("2025-03-01"), date("2025-03-01")).is_ok());
+    }
+
+    #[test]
+    fn test_date_range_dates() {
+        let range = DateRange::new(date("2025-02-27"), date("2025-03-02")).unwrap();
+        let dates: Vec<_> = range.dates().collect();
+
+        assert_eq!(dates.len(), 4);
+        assert_eq!(dates[3], date("2025-03-02"));
+        assert!(range.contains(date("2025-03-01")));
+        assert!(!range.contains(date("2025-03-03")));
+    }
+
+    #[test]
+    fn test_date_range_weeks_and_months_covered() {
+        let range = DateRange::new(date("2025-02-27"), date("2025-03-10")).unwrap();
+
+        let weeks = range.weeks_covered();
+        assert_eq!(weeks.len(), 3);
+        assert_eq!(weeks[0], Week::containing(date("2025-02-27")));
+        assert_eq!(weeks[2], Week::containing(date("2025-03-10")));
+
+        assert_eq!(
+            range.months_covered(),
+            vec![month("2025-02"), month("2025-03")]
+        );
+    }
+
+    #[test_case("2025-02-14", "2025-02-14", "2025-02-14"; "single day")]
+    #[test_case("2025-02-10", "2025-02-14", "2025-02-10 to 2025-02-14"; "range")]
+    fn test_date_range_display(from: &str, to: &str, expected: &str) {
+        let range = DateRange::new(date(from), date(to)).unwrap();
+        assert_eq!(range.to_string(), expected);
+    }
 }
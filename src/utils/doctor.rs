@@ -0,0 +1,542 @@
+use crate::config::Config;
+use crate::utils::date::{Timezone, hours_to_duration};
+use crate::utils::formatting::DurationFormat;
+use crate::wad_data::{AbsenceRecord, FileIssue};
+use crate::watson::frame::Frames;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use ulid::Ulid;
+
+/// Part of the setup a finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Area {
+    Watson,
+    Config,
+    Data,
+    Frames,
+}
+
+impl fmt::Display for Area {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Area::Watson => write!(f, "Watson"),
+            Area::Config => write!(f, "Config"),
+            Area::Data => write!(f, "Data"),
+            Area::Frames => write!(f, "Frames"),
+        }
+    }
+}
+
+/// How much a finding matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Nothing wrong, just information about the setup
+    Ok,
+    /// Something looks off, but wad works with it
+    Warning,
+    /// Something that breaks commands or makes their results wrong
+    Error,
+}
+
+/// A single result of checking the setup
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub area: Area,
+    pub severity: Severity,
+    pub message: String,
+    /// What to do about the finding, if anything
+    pub suggestion: Option<String>,
+    /// Repair resolving the finding without losing data, applied by `wad doctor --fix`
+    pub repair: Option<FileIssue>,
+}
+
+impl Finding {
+    pub fn ok(area: Area, message: impl Into<String>) -> Self {
+        Self {
+            area,
+            severity: Severity::Ok,
+            message: message.into(),
+            suggestion: None,
+            repair: None,
+        }
+    }
+
+    pub fn warning(area: Area, message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            suggestion: Some(suggestion.into()),
+            ..Self::ok(area, message)
+        }
+    }
+
+    pub fn error(area: Area, message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            ..Self::warning(area, message, suggestion)
+        }
+    }
+
+    fn with_repair(self, issue: FileIssue) -> Self {
+        Self {
+            repair: Some(issue),
+            ..self
+        }
+    }
+
+    /// Check whether the finding is something to act on
+    pub fn is_problem(&self) -> bool {
+        self.severity != Severity::Ok
+    }
+}
+
+/// Check that the configured thresholds and work hours make sense
+pub fn check_config(config: &Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    let (low, medium, good) = (
+        config.daily_worktime_low,
+        config.daily_worktime_medium,
+        config.daily_worktime_good,
+    );
+    if !(low < medium && medium < good) {
+        findings.push(Finding::warning(
+            Area::Config,
+            format!(
+                "The daily work time thresholds aren't ascending (low {}, medium {}, good {}), so days are colored wrongly",
+                low, medium, good
+            ),
+            "Set them so that daily_worktime_low < daily_worktime_medium < daily_worktime_good, e.g. `wad config set daily_worktime_medium 4`",
+        ));
+    }
+
+    let workhours = [
+        ("workhours_monday", config.workhours_monday),
+        ("workhours_tuesday", config.workhours_tuesday),
+        ("workhours_wednesday", config.workhours_wednesday),
+        ("workhours_thursday", config.workhours_thursday),
+        ("workhours_friday", config.workhours_friday),
+        ("workhours_saturday", config.workhours_saturday),
+        ("workhours_sunday", config.workhours_sunday),
+    ];
+    for (key, hours) in workhours {
+        if hours < 0.0 {
+            findings.push(Finding::error(
+                Area::Config,
+                format!("{} is negative ({})", key, hours),
+                format!(
+                    "Set it to the hours you work that day, e.g. `wad config set {} 0`",
+                    key
+                ),
+            ));
+        }
+    }
+
+    let weekly_hours = config.schedule().weekly_hours();
+    if weekly_hours <= 0.0 {
+        findings.push(Finding::error(
+            Area::Config,
+            format!(
+                "The weekly target is {} hours, so all work counts as overtime",
+                weekly_hours
+            ),
+            "Set the hours of your working days, e.g. `wad config set workhours_monday 8`",
+        ));
+    }
+
+    findings
+}
+
+/// Describe the problems found in the files of the JSON store
+///
+/// Everything but unreadable files can be repaired.
+pub fn check_files(issues: &[FileIssue]) -> Vec<Finding> {
+    issues
+        .iter()
+        .map(|issue| {
+            let finding = match issue {
+                FileIssue::Unreadable { path, error } => Finding::error(
+                    Area::Data,
+                    format!("{} can't be read: {}", path.display(), error),
+                    "Correct the file by hand, commands reading it fail until then",
+                ),
+                FileIssue::Misplaced { path, record } => {
+                    let expected = format!("{}.json", record.date.format("%Y-%m-%d"));
+                    let reason = if path.file_name().is_some_and(|name| *name == *expected) {
+                        "the directory of another year"
+                    } else {
+                        "a file not matching its date"
+                    };
+                    Finding::error(
+                        Area::Data,
+                        format!(
+                            "Absence {} of {} is stored in {} ({}), so it's missing on that day",
+                            record.id,
+                            record.date,
+                            path.display(),
+                            reason
+                        ),
+                        format!("Move it to {}", expected),
+                    )
+                }
+                FileIssue::Duplicated {
+                    path,
+                    record,
+                    copies,
+                } => Finding::warning(
+                    Area::Data,
+                    format!(
+                        "Absence {} of {} is stored {} times in {}, so it's counted {} times",
+                        record.id,
+                        record.date,
+                        copies,
+                        path.display(),
+                        copies
+                    ),
+                    "Keep a single copy",
+                ),
+                FileIssue::LeftoverTemporary { path } => Finding::warning(
+                    Area::Data,
+                    format!("{} was left behind by an interrupted write", path.display()),
+                    "Remove it",
+                ),
+                FileIssue::PendingMigration { from, to } => Finding::warning(
+                    Area::Data,
+                    format!(
+                        "The data directory is at version {} and needs migrating to version {}",
+                        from, to
+                    ),
+                    "Migrate it, after taking a backup",
+                ),
+                FileIssue::UnfinishedMove { path } => Finding::warning(
+                    Area::Data,
+                    format!(
+                        "{} holds a move of an absence to another day that was interrupted",
+                        path.display()
+                    ),
+                    "Finish the move",
+                ),
+            };
+
+            if issue.is_repairable() {
+                finding.with_repair(issue.clone())
+            } else {
+                finding
+            }
+        })
+        .collect()
+}
+
+/// Check absences for IDs used by different records and negative hours
+///
+/// Identical copies of a record are left to [`check_files`], as only the
+/// JSON store can end up with those.
+pub fn check_absences(records: &[AbsenceRecord]) -> Vec<Finding> {
+    let mut versions: BTreeMap<Ulid, Vec<&AbsenceRecord>> = BTreeMap::new();
+    for record in records {
+        let known = versions.entry(record.id).or_default();
+        if !known.contains(&record) {
+            known.push(record);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (id, known) in &versions {
+        if known.len() > 1 {
+            let dates: Vec<String> = known.iter().map(|record| record.date.to_string()).collect();
+            findings.push(Finding::error(
+                Area::Data,
+                format!(
+                    "Absence ID {} is used by {} different records ({}), so editing or removing it may hit the wrong one",
+                    id,
+                    known.len(),
+                    dates.join(", ")
+                ),
+                "Remove all but one of them by hand, `wad absence path` shows where they are stored",
+            ));
+        }
+
+        for record in known.iter().filter(|record| record.hours < 0.0) {
+            findings.push(Finding::error(
+                Area::Data,
+                format!(
+                    "Absence {} of {} has negative hours ({})",
+                    record.id, record.date, record.hours
+                ),
+                format!(
+                    "Correct it with `wad absence edit {} --id {}`",
+                    record.date, record.id
+                ),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Check frames for a stop before their start and for running too long
+pub fn check_frames(
+    frames: &Frames,
+    timezone: Timezone,
+    now: DateTime<Utc>,
+    max_active: Duration,
+) -> Vec<Finding> {
+    let local = |datetime: &DateTime<Utc>| {
+        timezone
+            .local_datetime_of(datetime)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+
+    let mut findings = Vec::new();
+    for frame in &frames.frames {
+        if let Some(stop) = frame.stop
+            && stop < frame.start
+        {
+            findings.push(Finding::error(
+                Area::Frames,
+                format!(
+                    "Frame {} of {} stops at {}, before it starts at {}",
+                    frame.id,
+                    frame.project,
+                    local(&stop),
+                    local(&frame.start)
+                ),
+                format!("Correct its times with `watson edit {}`", frame.id),
+            ));
+        }
+
+        let running = now - frame.start;
+        if frame.is_active() && running > max_active {
+            findings.push(Finding::warning(
+                Area::Frames,
+                format!(
+                    "The frame of {} has been running for {} since {}",
+                    frame.project,
+                    running.to_string_hhmm(),
+                    local(&frame.start)
+                ),
+                "Stop it at the time you actually stopped with `watson stop --at HH:MM`, or drop it with `watson cancel`",
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Check for days on which tracked time and absences add up to more than a day
+pub fn check_day_totals(
+    frames: &Frames,
+    absences: &[AbsenceRecord],
+    timezone: Timezone,
+) -> Vec<Finding> {
+    let mut totals: BTreeMap<NaiveDate, (Duration, Duration)> = BTreeMap::new();
+    for (date, day) in frames.by_date(timezone) {
+        totals.entry(date).or_default().0 = day.total_duration();
+    }
+    for record in absences {
+        totals.entry(record.date).or_default().1 += hours_to_duration(record.hours);
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (tracked, absent))| *tracked + *absent > Duration::hours(24))
+        .map(|(date, (tracked, absent))| {
+            Finding::warning(
+                Area::Frames,
+                format!(
+                    "{}: {} tracked and {} of absences add up to more than 24 hours",
+                    date,
+                    tracked.to_string_hhmm(),
+                    absent.to_string_hhmm()
+                ),
+                format!(
+                    "Look for overlapping frames with `watson log --from {} --to {}` and absences entered twice with `wad absence show {}`",
+                    date, date, date
+                ),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::schedule::WeeklySchedule;
+    use crate::wad_data::AbsenceType;
+    use crate::watson::frame::Frame;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+    use test_case::test_case;
+
+    fn record(date: &str, hours: f64) -> AbsenceRecord {
+        AbsenceRecord {
+            id: Ulid::new(),
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            hours,
+            absence_type: AbsenceType::Vacation,
+            note: None,
+            ical_uid: None,
+        }
+    }
+
+    fn utc(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn frame(start: DateTime<Utc>, stop: Option<DateTime<Utc>>) -> Frame {
+        Frame {
+            id: "a1b2c3d".to_string(),
+            project: "wad".to_string(),
+            start,
+            stop,
+            tags: Vec::new(),
+        }
+    }
+
+    fn severities(findings: &[Finding]) -> Vec<Severity> {
+        findings.iter().map(|finding| finding.severity).collect()
+    }
+
+    #[test]
+    fn test_default_config_is_fine() {
+        assert!(check_config(&Config::default()).is_empty());
+    }
+
+    #[test_case(0.0, 8.0, 4.0 ; "medium above good")]
+    #[test_case(4.0, 4.0, 8.0 ; "low equal to medium")]
+    fn test_unordered_thresholds_are_reported(low: f64, medium: f64, good: f64) {
+        let config = Config {
+            daily_worktime_low: low,
+            daily_worktime_medium: medium,
+            daily_worktime_good: good,
+            ..Config::default()
+        };
+
+        assert_eq!(severities(&check_config(&config)), vec![Severity::Warning]);
+    }
+
+    #[test]
+    fn test_missing_weekly_target_is_reported() {
+        let mut config = Config::default();
+        config.set_schedule(WeeklySchedule::spread_over_weekdays(0.0));
+        config.workhours_sunday = -2.0;
+
+        let findings = check_config(&config);
+
+        assert_eq!(
+            severities(&findings),
+            vec![Severity::Error, Severity::Error]
+        );
+        assert!(findings[0].message.starts_with("workhours_sunday"));
+        assert!(findings[1].message.contains("weekly target is -2 hours"));
+    }
+
+    #[test]
+    fn test_file_issues_are_repaired_unless_unreadable() {
+        let path = PathBuf::from("/data/absences/2023/2024-01-15.json");
+        let misplaced = FileIssue::Misplaced {
+            path: path.clone(),
+            record: record("2024-01-15", 8.0),
+        };
+        let unreadable = FileIssue::Unreadable {
+            path,
+            error: "EOF while parsing".to_string(),
+        };
+
+        let findings = check_files(&[misplaced.clone(), unreadable]);
+
+        assert_eq!(
+            severities(&findings),
+            vec![Severity::Error, Severity::Error]
+        );
+        assert!(findings[0].message.contains("directory of another year"));
+        assert_eq!(findings[0].repair, Some(misplaced));
+        assert_eq!(findings[1].repair, None);
+    }
+
+    #[test]
+    fn test_pending_changes_are_applied_by_repair() {
+        let migration = FileIssue::PendingMigration { from: 1, to: 2 };
+        let journal = FileIssue::UnfinishedMove {
+            path: PathBuf::from("/data/journal.json"),
+        };
+
+        let findings = check_files(&[migration.clone(), journal.clone()]);
+
+        assert_eq!(
+            severities(&findings),
+            vec![Severity::Warning, Severity::Warning]
+        );
+        assert!(findings[0].message.contains("version 1"));
+        assert_eq!(findings[0].repair, Some(migration));
+        assert_eq!(findings[1].repair, Some(journal));
+    }
+
+    #[test]
+    fn test_identical_absences_are_left_to_file_check() {
+        let absence = record("2025-03-03", 8.0);
+
+        assert!(check_absences(&[absence.clone(), absence]).is_empty());
+    }
+
+    #[test]
+    fn test_reused_absence_id_is_reported() {
+        let absence = record("2025-03-03", 8.0);
+        let moved = AbsenceRecord {
+            date: NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+            ..absence.clone()
+        };
+
+        let findings = check_absences(&[absence, moved]);
+
+        assert_eq!(severities(&findings), vec![Severity::Error]);
+        assert!(findings[0].message.contains("(2025-03-03, 2025-03-04)"));
+    }
+
+    #[test]
+    fn test_negative_hours_are_reported() {
+        let findings = check_absences(&[record("2025-03-03", -4.0), record("2025-03-03", 4.0)]);
+
+        assert_eq!(severities(&findings), vec![Severity::Error]);
+        assert!(findings[0].message.contains("negative hours (-4)"));
+    }
+
+    #[test_case(utc(3, 9), Some(utc(3, 17)), vec![] ; "finished frame")]
+    #[test_case(utc(3, 17), Some(utc(3, 9)), vec![Severity::Error] ; "stop before start")]
+    #[test_case(utc(4, 8), None, vec![] ; "recently started frame")]
+    #[test_case(utc(3, 17), None, vec![Severity::Warning] ; "frame running for too long")]
+    fn test_check_frames(
+        start: DateTime<Utc>,
+        stop: Option<DateTime<Utc>>,
+        expected: Vec<Severity>,
+    ) {
+        let frames = Frames::new(vec![frame(start, stop)]);
+        let now = utc(4, 10);
+
+        let findings = check_frames(&frames, Timezone::Local, now, Duration::hours(12));
+
+        assert_eq!(severities(&findings), expected);
+    }
+
+    #[test_case(8.0, vec![] ; "fits into the day")]
+    #[test_case(16.5, vec![Severity::Warning] ; "more than a day")]
+    fn test_check_day_totals(absent_hours: f64, expected: Vec<Severity>) {
+        let timezone = Timezone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+        let frames = Frames::new(vec![frame(utc(3, 8), Some(utc(3, 16)))]);
+
+        let findings = check_day_totals(
+            &frames,
+            &[
+                record("2025-03-03", absent_hours),
+                record("2025-03-04", 20.0),
+            ],
+            timezone,
+        );
+
+        assert_eq!(severities(&findings), expected);
+    }
+}
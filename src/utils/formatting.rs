@@ -1,5 +1,7 @@
+use crate::utils::compliance::Violation;
 use crate::utils::date::{DailyWorktime, DayTimeBreakdown, Week, WeeklyWorktime};
-use crate::wad_data::{AbsenceRecord, AbsenceType};
+use crate::utils::schedule::DailyThresholds;
+use crate::wad_data::{AbsenceRecord, AbsenceType, ContractPeriod};
 use chrono::{Datelike, Duration};
 use owo_colors::{OwoColorize, colors::*};
 use std::fmt;
@@ -17,6 +19,15 @@ pub type LowWorkColor = Yellow;
 pub type MediumWorkColor = Cyan;
 pub type HighWorkColor = Green;
 
+// Balance-specific color aliases
+pub type PositiveBalanceColor = Green;
+pub type NegativeBalanceColor = Red;
+
+// Heatmap cell symbols
+pub const HEATMAP_WORK_CELL: &str = "■";
+pub const HEATMAP_ABSENCE_CELL: &str = "◆";
+pub const HEATMAP_EMPTY_CELL: &str = "·";
+
 // Absence-specific color aliases
 pub type AbsenceIdColor = BrightBlack;
 pub type AbsenceHoursColor = Blue;
@@ -56,6 +67,8 @@ pub fn verbose_text(text: &str) -> String {
 pub trait DurationFormat {
     fn to_string_hhmm(&self) -> String;
     fn to_string_long_hhmm(&self) -> String;
+    fn to_string_signed_hhmm(&self) -> String;
+    fn to_string_balance_colored(&self) -> String;
 }
 
 impl DurationFormat for chrono::Duration {
@@ -80,6 +93,25 @@ impl DurationFormat for chrono::Duration {
             ),
         }
     }
+
+    fn to_string_signed_hhmm(&self) -> String {
+        let sign = if *self < chrono::Duration::zero() {
+            "-"
+        } else {
+            "+"
+        };
+        format!("{}{}", sign, self.abs().to_string_hhmm())
+    }
+
+    fn to_string_balance_colored(&self) -> String {
+        let formatted = self.to_string_signed_hhmm();
+
+        if *self < chrono::Duration::zero() {
+            formatted.fg::<NegativeBalanceColor>().to_string()
+        } else {
+            formatted.fg::<PositiveBalanceColor>().to_string()
+        }
+    }
 }
 
 /// Trait for formatting weeks in a human-readable way
@@ -125,16 +157,13 @@ pub trait AbsenceTypeFormat {
 
 impl AbsenceTypeFormat for AbsenceType {
     fn to_string_colored(&self) -> String {
+        let label = self.label();
         match self {
-            AbsenceType::Vacation => "Vacation".fg::<VacationColor>().to_string(),
-            AbsenceType::Sick => "Sick".fg::<SickColor>().to_string(),
-            AbsenceType::OvertimeReduction => "Overtime Reduction"
-                .fg::<OvertimeReductionColor>()
-                .to_string(),
-            AbsenceType::Holiday => "Holiday".fg::<HolidayColor>().to_string(),
-            AbsenceType::Other(custom) => format!("Other: {}", custom)
-                .fg::<OtherAbsenceColor>()
-                .to_string(),
+            AbsenceType::Vacation => label.fg::<VacationColor>().to_string(),
+            AbsenceType::Sick => label.fg::<SickColor>().to_string(),
+            AbsenceType::OvertimeReduction => label.fg::<OvertimeReductionColor>().to_string(),
+            AbsenceType::Holiday => label.fg::<HolidayColor>().to_string(),
+            AbsenceType::Other(_) => label.fg::<OtherAbsenceColor>().to_string(),
         }
     }
 
@@ -149,31 +178,44 @@ impl AbsenceTypeFormat for AbsenceType {
     }
 }
 
+/// Color a day's formatted work time based on the worked hours
+fn colored_by_thresholds(formatted: String, hours: f64, thresholds: &DailyThresholds) -> String {
+    if hours <= thresholds.low {
+        formatted.fg::<NoWorkColor>().to_string()
+    } else if hours < thresholds.medium {
+        formatted.fg::<LowWorkColor>().to_string()
+    } else if hours < thresholds.good {
+        formatted.fg::<MediumWorkColor>().to_string()
+    } else {
+        formatted.fg::<HighWorkColor>().to_string()
+    }
+}
+
+/// Color text with the color of the given absence type
+fn colored_by_absence_type(text: String, absence_type: &AbsenceType) -> String {
+    match absence_type {
+        AbsenceType::Vacation => text.fg::<VacationColor>().to_string(),
+        AbsenceType::Sick => text.fg::<SickColor>().to_string(),
+        AbsenceType::OvertimeReduction => text.fg::<OvertimeReductionColor>().to_string(),
+        AbsenceType::Holiday => text.fg::<HolidayColor>().to_string(),
+        AbsenceType::Other(_) => text.fg::<OtherAbsenceColor>().to_string(),
+    }
+}
+
 /// Format daily worktime with appropriate color coding
 impl DailyWorktime {
-    pub fn to_string_colored(self, config: &crate::config::Config) -> String {
-        let hours = self.num_hours() as f64;
-        let formatted = self.to_string_hhmm();
-
-        if hours <= config.daily_worktime_low {
-            formatted.fg::<NoWorkColor>().to_string()
-        } else if hours < config.daily_worktime_medium {
-            formatted.fg::<LowWorkColor>().to_string()
-        } else if hours < config.daily_worktime_good {
-            formatted.fg::<MediumWorkColor>().to_string()
-        } else {
-            formatted.fg::<HighWorkColor>().to_string()
-        }
+    pub fn to_string_colored(self, thresholds: &DailyThresholds) -> String {
+        let hours = self.num_minutes() as f64 / 60.0;
+        colored_by_thresholds(self.to_string_hhmm(), hours, thresholds)
     }
 }
 
 /// Format weekly worktime with appropriate color coding
 impl WeeklyWorktime {
-    pub fn to_string_colored(self, config: &crate::config::Config) -> String {
-        let hours = self.num_hours() as f64;
+    pub fn to_string_colored(self, target: Duration) -> String {
         let formatted = self.to_string_hhmm();
 
-        if hours < config.workhours_per_week {
+        if self.0 < target {
             formatted.fg::<LowWorkColor>().to_string()
         } else {
             formatted.fg::<HighWorkColor>().to_string()
@@ -183,28 +225,22 @@ impl WeeklyWorktime {
 
 /// Trait for formatting time breakdowns with split display
 pub trait TimeBreakdownFormat {
-    fn to_string_split_colored(&self, config: &crate::config::Config) -> String;
-    fn to_string_combined_with_indicator(&self, config: &crate::config::Config) -> String;
+    fn to_string_split_colored(&self, thresholds: &DailyThresholds) -> String;
+    fn to_string_combined_with_indicator(&self, thresholds: &DailyThresholds) -> String;
+    fn to_string_combined_with_emoji(&self, thresholds: &DailyThresholds) -> String;
+    fn to_heatmap_cell(&self, thresholds: &DailyThresholds) -> String;
 }
 
 impl TimeBreakdownFormat for DayTimeBreakdown {
-    fn to_string_split_colored(&self, config: &crate::config::Config) -> String {
+    fn to_string_split_colored(&self, thresholds: &DailyThresholds) -> String {
         let total: DailyWorktime = self.total_duration().into();
-        let base_watson = self.watson_duration.to_string_hhmm();
-
-        // Color the base duration based on total time using DailyWorktime wrapper
-        let colored_watson = {
-            let hours = total.num_hours() as f64;
-            if hours <= config.daily_worktime_low {
-                base_watson.fg::<NoWorkColor>().to_string()
-            } else if hours < config.daily_worktime_medium {
-                base_watson.fg::<LowWorkColor>().to_string()
-            } else if hours < config.daily_worktime_good {
-                base_watson.fg::<MediumWorkColor>().to_string()
-            } else {
-                base_watson.fg::<HighWorkColor>().to_string()
-            }
-        };
+
+        // Color the base duration based on total time
+        let colored_watson = colored_by_thresholds(
+            self.watson_duration.to_string_hhmm(),
+            total.num_minutes() as f64 / 60.0,
+            thresholds,
+        );
 
         let mut result = colored_watson;
 
@@ -213,33 +249,10 @@ impl TimeBreakdownFormat for DayTimeBreakdown {
                 + Duration::minutes(((absence.hours % 1.0) * 60.0) as i64);
 
             // Color the absence duration with dimmed type color
-            let colored_absence_time = match absence.absence_type {
-                AbsenceType::Vacation => absence_duration
-                    .to_string_hhmm()
-                    .fg::<VacationColor>()
-                    .dimmed()
-                    .to_string(),
-                AbsenceType::Sick => absence_duration
-                    .to_string_hhmm()
-                    .fg::<SickColor>()
-                    .dimmed()
-                    .to_string(),
-                AbsenceType::OvertimeReduction => absence_duration
-                    .to_string_hhmm()
-                    .fg::<OvertimeReductionColor>()
-                    .dimmed()
-                    .to_string(),
-                AbsenceType::Holiday => absence_duration
-                    .to_string_hhmm()
-                    .fg::<HolidayColor>()
+            let colored_absence_time =
+                colored_by_absence_type(absence_duration.to_string_hhmm(), &absence.absence_type)
                     .dimmed()
-                    .to_string(),
-                AbsenceType::Other(_) => absence_duration
-                    .to_string_hhmm()
-                    .fg::<OtherAbsenceColor>()
-                    .dimmed()
-                    .to_string(),
-            };
+                    .to_string();
 
             result.push_str(&format!(
                 "+{}{}",
@@ -251,9 +264,9 @@ impl TimeBreakdownFormat for DayTimeBreakdown {
         result
     }
 
-    fn to_string_combined_with_indicator(&self, config: &crate::config::Config) -> String {
+    fn to_string_combined_with_indicator(&self, thresholds: &DailyThresholds) -> String {
         let total: DailyWorktime = self.total_duration().into();
-        let colored_total = total.to_string_colored(config);
+        let colored_total = total.to_string_colored(thresholds);
 
         // Add + indicator if there are absences
         if self.absences.is_empty() {
@@ -262,6 +275,34 @@ impl TimeBreakdownFormat for DayTimeBreakdown {
             format!("{}+", colored_total)
         }
     }
+
+    fn to_string_combined_with_emoji(&self, thresholds: &DailyThresholds) -> String {
+        let total: DailyWorktime = self.total_duration().into();
+        let emojis: String = self
+            .absences
+            .iter()
+            .map(|absence| absence.absence_type.to_emoji())
+            .collect();
+
+        format!("{}{}", total.to_string_colored(thresholds), emojis)
+    }
+
+    fn to_heatmap_cell(&self, thresholds: &DailyThresholds) -> String {
+        // Days with an absence get a distinct symbol in the color of the absence
+        if let Some(absence) = self.absences.first() {
+            return colored_by_absence_type(
+                HEATMAP_ABSENCE_CELL.to_string(),
+                &absence.absence_type,
+            );
+        }
+
+        let total: DailyWorktime = self.total_duration().into();
+        colored_by_thresholds(
+            HEATMAP_WORK_CELL.to_string(),
+            total.num_minutes() as f64 / 60.0,
+            thresholds,
+        )
+    }
 }
 
 impl fmt::Display for AbsenceRecord {
@@ -281,3 +322,51 @@ impl fmt::Display for AbsenceRecord {
         write!(f, "{} | {} | {} | {}", ulid_str, hours, absence_type, note)
     }
 }
+
+impl fmt::Display for ContractPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ulid_str = self.id.to_string().fg::<AbsenceIdColor>().to_string();
+        let valid_from = format!("from {}", self.valid_from.format("%Y-%m-%d"));
+        let hours = format!("{}h per week", self.schedule.weekly_hours())
+            .fg::<AbsenceHoursColor>()
+            .to_string();
+        let note = self
+            .note
+            .as_deref()
+            .unwrap_or("(no note)")
+            .fg::<AbsenceNoteColor>()
+            .to_string();
+
+        write!(f, "{} | {} | {} | {}", ulid_str, valid_from, hours, note)
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::MissingBreak {
+                worked,
+                required,
+                taken,
+            } => write!(
+                f,
+                "Break too short: {} taken, {} required after {} of work",
+                taken.to_string_hhmm(),
+                required.to_string_hhmm(),
+                worked.to_string_hhmm()
+            ),
+            Violation::MaxDailyExceeded { worked, maximum } => write!(
+                f,
+                "Daily maximum exceeded: {} worked, at most {} allowed",
+                worked.to_string_hhmm(),
+                maximum.to_string_hhmm()
+            ),
+            Violation::InsufficientRest { rest, minimum } => write!(
+                f,
+                "Rest too short: {} since the previous working day, {} required",
+                rest.to_string_hhmm(),
+                minimum.to_string_hhmm()
+            ),
+        }
+    }
+}
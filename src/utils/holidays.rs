@@ -0,0 +1,611 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use ulid::Ulid;
+
+use crate::utils::date::DateRange;
+use crate::utils::schedule::ScheduleHistory;
+use crate::wad_data::{AbsenceRecord, AbsenceType};
+
+/// How the date of a holiday is found in a given year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolidayDate {
+    /// The same day every year
+    Fixed { month: u32, day: u32 },
+    /// Days relative to Easter Sunday, negative ones before it
+    Easter(i64),
+    /// The last given weekday before a fixed day, like the Wednesday before November 23
+    WeekdayBefore {
+        weekday: Weekday,
+        month: u32,
+        day: u32,
+    },
+}
+
+impl HolidayDate {
+    /// Get the date in the given year, if the year has one
+    pub fn in_year(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            HolidayDate::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            HolidayDate::Easter(offset) => {
+                easter_sunday(year).map(|easter| easter + Duration::days(offset))
+            }
+            HolidayDate::WeekdayBefore {
+                weekday,
+                month,
+                day,
+            } => {
+                let mut date = NaiveDate::from_ymd_opt(year, month, day)?.pred_opt()?;
+                while date.weekday() != weekday {
+                    date = date.pred_opt()?;
+                }
+                Some(date)
+            }
+        }
+    }
+}
+
+/// A public holiday of a country, observed in all or some of its regions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HolidayRule {
+    pub name: &'static str,
+    pub date: HolidayDate,
+    /// Share of the working day that is off, 0.5 for half days
+    pub share: f64,
+    /// Region codes observing the holiday, the whole country if empty
+    pub regions: &'static [&'static str],
+    /// First year the holiday is observed, if it hasn't always been
+    pub since: Option<i32>,
+}
+
+impl HolidayRule {
+    fn applies_to(&self, region: Option<&str>, year: i32) -> bool {
+        let in_region =
+            self.regions.is_empty() || region.is_some_and(|region| self.regions.contains(&region));
+        in_region && self.since.is_none_or(|since| year >= since)
+    }
+}
+
+/// The holiday rules of a country and the regions they may be limited to
+#[derive(Debug, PartialEq)]
+pub struct CountryCalendar {
+    /// ISO 3166-1 code, e.g. `DE`
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Subdivision codes (the part after the dash in ISO 3166-2) and names
+    pub regions: &'static [(&'static str, &'static str)],
+    pub rules: &'static [HolidayRule],
+}
+
+const fn fixed(name: &'static str, month: u32, day: u32) -> HolidayRule {
+    HolidayRule {
+        name,
+        date: HolidayDate::Fixed { month, day },
+        share: 1.0,
+        regions: &[],
+        since: None,
+    }
+}
+
+const fn easter(name: &'static str, offset: i64) -> HolidayRule {
+    HolidayRule {
+        name,
+        date: HolidayDate::Easter(offset),
+        share: 1.0,
+        regions: &[],
+        since: None,
+    }
+}
+
+const GERMANY: CountryCalendar = CountryCalendar {
+    code: "DE",
+    name: "Germany",
+    regions: &[
+        ("BB", "Brandenburg"),
+        ("BE", "Berlin"),
+        ("BW", "Baden-Württemberg"),
+        ("BY", "Bavaria"),
+        ("HB", "Bremen"),
+        ("HE", "Hesse"),
+        ("HH", "Hamburg"),
+        ("MV", "Mecklenburg-Western Pomerania"),
+        ("NI", "Lower Saxony"),
+        ("NW", "North Rhine-Westphalia"),
+        ("RP", "Rhineland-Palatinate"),
+        ("SH", "Schleswig-Holstein"),
+        ("SL", "Saarland"),
+        ("SN", "Saxony"),
+        ("ST", "Saxony-Anhalt"),
+        ("TH", "Thuringia"),
+    ],
+    rules: &[
+        fixed("New Year's Day", 1, 1),
+        HolidayRule {
+            regions: &["BW", "BY", "ST"],
+            ..fixed("Epiphany", 1, 6)
+        },
+        HolidayRule {
+            regions: &["BE"],
+            since: Some(2019),
+            ..fixed("International Women's Day", 3, 8)
+        },
+        HolidayRule {
+            regions: &["MV"],
+            since: Some(2023),
+            ..fixed("International Women's Day", 3, 8)
+        },
+        easter("Good Friday", -2),
+        HolidayRule {
+            regions: &["BB"],
+            ..easter("Easter Sunday", 0)
+        },
+        easter("Easter Monday", 1),
+        fixed("Labour Day", 5, 1),
+        easter("Ascension Day", 39),
+        HolidayRule {
+            regions: &["BB"],
+            ..easter("Whit Sunday", 49)
+        },
+        easter("Whit Monday", 50),
+        HolidayRule {
+            regions: &["BW", "BY", "HE", "NW", "RP", "SL"],
+            ..easter("Corpus Christi", 60)
+        },
+        HolidayRule {
+            regions: &["SL"],
+            ..fixed("Assumption Day", 8, 15)
+        },
+        HolidayRule {
+            regions: &["TH"],
+            since: Some(2019),
+            ..fixed("World Children's Day", 9, 20)
+        },
+        fixed("German Unity Day", 10, 3),
+        HolidayRule {
+            regions: &["BB", "MV", "SN", "ST", "TH"],
+            ..fixed("Reformation Day", 10, 31)
+        },
+        HolidayRule {
+            regions: &["HB", "HH", "NI", "SH"],
+            since: Some(2018),
+            ..fixed("Reformation Day", 10, 31)
+        },
+        HolidayRule {
+            regions: &["BW", "BY", "NW", "RP", "SL"],
+            ..fixed("All Saints' Day", 11, 1)
+        },
+        HolidayRule {
+            name: "Day of Repentance and Prayer",
+            date: HolidayDate::WeekdayBefore {
+                weekday: Weekday::Wed,
+                month: 11,
+                day: 23,
+            },
+            share: 1.0,
+            regions: &["SN"],
+            since: None,
+        },
+        HolidayRule {
+            share: 0.5,
+            ..fixed("Christmas Eve", 12, 24)
+        },
+        fixed("Christmas Day", 12, 25),
+        fixed("Boxing Day", 12, 26),
+        HolidayRule {
+            share: 0.5,
+            ..fixed("New Year's Eve", 12, 31)
+        },
+    ],
+};
+
+/// All countries with a built-in holiday calendar
+pub const COUNTRIES: &[&CountryCalendar] = &[&GERMANY];
+
+/// Calculate Easter Sunday in the Gregorian calendar
+///
+/// Uses the anonymous Gregorian algorithm (Meeus/Jones/Butcher), so no
+/// lookup table or network access is needed.
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// A country, optionally narrowed down to one of its regions, e.g. `DE-BY`
+///
+/// Without a region, only the holidays observed in the whole country apply.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HolidayRegion {
+    pub country: &'static CountryCalendar,
+    pub region: Option<&'static str>,
+}
+
+impl HolidayRegion {
+    /// Get the name of the country and region
+    pub fn name(&self) -> String {
+        let region_name = self.region.and_then(|code| {
+            self.country
+                .regions
+                .iter()
+                .find(|(region, _)| *region == code)
+                .map(|(_, name)| *name)
+        });
+        match region_name {
+            Some(region_name) => format!("{}, {}", region_name, self.country.name),
+            None => self.country.name.to_string(),
+        }
+    }
+
+    /// Get the holiday rules observed in this region
+    pub fn rules(&self, year: i32) -> impl Iterator<Item = &'static HolidayRule> {
+        let region = self.region;
+        self.country
+            .rules
+            .iter()
+            .filter(move |rule| rule.applies_to(region, year))
+    }
+}
+
+impl FromStr for HolidayRegion {
+    type Err = String;
+
+    /// Parse an ISO 3166 code like `DE` or `DE-BY`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || {
+            format!(
+                "Unknown holiday region '{}', see `wad holiday regions` for the supported ones",
+                s
+            )
+        };
+
+        let code = s.trim().to_uppercase();
+        let (country_code, region_code) = match code.split_once('-') {
+            Some((country, region)) => (country, Some(region)),
+            None => (code.as_str(), None),
+        };
+
+        let country = COUNTRIES
+            .iter()
+            .find(|country| country.code == country_code)
+            .ok_or_else(unknown)?;
+        let region = match region_code {
+            Some(region_code) => Some(
+                country
+                    .regions
+                    .iter()
+                    .map(|(region, _)| *region)
+                    .find(|region| *region == region_code)
+                    .ok_or_else(unknown)?,
+            ),
+            None => None,
+        };
+
+        Ok(Self { country, region })
+    }
+}
+
+impl fmt::Display for HolidayRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.region {
+            Some(region) => write!(f, "{}-{}", self.country.code, region),
+            None => write!(f, "{}", self.country.code),
+        }
+    }
+}
+
+impl Serialize for HolidayRegion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for HolidayRegion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A public holiday on a specific date
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: &'static str,
+    /// Share of the working day that is off, 0.5 for half days
+    pub share: f64,
+}
+
+/// The public holidays applied to targets, computed rather than stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HolidayCalendar {
+    /// No holidays are computed without a region
+    pub region: Option<HolidayRegion>,
+    /// Whether half days like Christmas Eve count
+    pub half_days: bool,
+}
+
+impl HolidayCalendar {
+    /// Get the holidays within a range, ordered by date
+    ///
+    /// If two holidays fall on the same day (like Ascension Day on May 1),
+    /// the one taking more of the day is kept.
+    pub fn holidays(&self, range: &DateRange) -> Vec<Holiday> {
+        let Some(region) = self.region else {
+            return Vec::new();
+        };
+
+        let mut holidays: Vec<Holiday> = Vec::new();
+        for year in range.from.year()..=range.to.year() {
+            for rule in region.rules(year) {
+                if rule.share < 1.0 && !self.half_days {
+                    continue;
+                }
+                let Some(date) = rule.date.in_year(year).filter(|date| range.contains(*date))
+                else {
+                    continue;
+                };
+
+                match holidays.iter_mut().find(|holiday| holiday.date == date) {
+                    Some(holiday) if holiday.share >= rule.share => {}
+                    Some(holiday) => {
+                        holiday.name = rule.name;
+                        holiday.share = rule.share;
+                    }
+                    None => holidays.push(Holiday {
+                        date,
+                        name: rule.name,
+                        share: rule.share,
+                    }),
+                }
+            }
+        }
+
+        holidays.sort_by_key(|holiday| holiday.date);
+        holidays
+    }
+
+    /// Get holiday absences for the days of a range not having a stored holiday
+    ///
+    /// Each absence covers the holiday's share of that day's target, so
+    /// holidays on days off don't get one. The records are not stored, but
+    /// look exactly like a holiday booked with `wad absence add`.
+    pub fn missing_absences(
+        &self,
+        range: &DateRange,
+        schedules: &ScheduleHistory,
+        stored: &[AbsenceRecord],
+    ) -> Vec<AbsenceRecord> {
+        self.holidays(range)
+            .into_iter()
+            .filter(|holiday| {
+                !stored.iter().any(|record| {
+                    record.date == holiday.date && record.absence_type == AbsenceType::Holiday
+                })
+            })
+            .filter_map(|holiday| {
+                let hours = schedules
+                    .schedule_on(holiday.date)
+                    .hours(holiday.date.weekday())
+                    * holiday.share;
+                (hours > 0.0).then(|| AbsenceRecord {
+                    id: Ulid::new(),
+                    date: holiday.date,
+                    hours,
+                    absence_type: AbsenceType::Holiday,
+                    note: Some(holiday.name.to_string()),
+                    ical_uid: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Add the computed holidays to the stored absences of a range, ordered by date
+    pub fn apply(
+        &self,
+        mut stored: Vec<AbsenceRecord>,
+        range: &DateRange,
+        schedules: &ScheduleHistory,
+    ) -> Vec<AbsenceRecord> {
+        let holidays = self.missing_absences(range, schedules, &stored);
+        stored.extend(holidays);
+        stored.sort_by_key(|record| record.date);
+        stored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::schedule::WeeklySchedule;
+    use test_case::test_case;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn year(year: i32) -> DateRange {
+        DateRange::year(year).unwrap()
+    }
+
+    fn calendar(region: &str) -> HolidayCalendar {
+        HolidayCalendar {
+            region: Some(region.parse().unwrap()),
+            half_days: true,
+        }
+    }
+
+    fn schedules() -> ScheduleHistory {
+        ScheduleHistory::new("8,8,8,8,4".parse::<WeeklySchedule>().unwrap(), Vec::new())
+    }
+
+    fn names(calendar: &HolidayCalendar, range: &DateRange) -> Vec<&'static str> {
+        calendar
+            .holidays(range)
+            .iter()
+            .map(|holiday| holiday.name)
+            .collect()
+    }
+
+    #[test_case(2000, "2000-04-23")]
+    #[test_case(2019, "2019-04-21")]
+    #[test_case(2024, "2024-03-31")]
+    #[test_case(2025, "2025-04-20")]
+    #[test_case(2026, "2026-04-05")]
+    #[test_case(2038, "2038-04-25"; "latest possible")]
+    #[test_case(2285, "2285-03-22"; "earliest possible")]
+    fn test_easter_sunday(year: i32, expected: &str) {
+        assert_eq!(easter_sunday(year), Some(date(expected)));
+    }
+
+    #[test_case(2024, "2024-11-20")]
+    #[test_case(2025, "2025-11-19")]
+    #[test_case(2026, "2026-11-18")]
+    fn test_weekday_before(year: i32, expected: &str) {
+        let repentance_day = HolidayDate::WeekdayBefore {
+            weekday: Weekday::Wed,
+            month: 11,
+            day: 23,
+        };
+        assert_eq!(repentance_day.in_year(year), Some(date(expected)));
+    }
+
+    #[test_case("DE", "DE"; "country")]
+    #[test_case("de-by", "DE-BY"; "lowercase region")]
+    fn test_region_parse(input: &str, expected: &str) {
+        assert_eq!(
+            input.parse::<HolidayRegion>().unwrap().to_string(),
+            expected
+        );
+    }
+
+    #[test_case("FR"; "unknown country")]
+    #[test_case("DE-XX"; "unknown region")]
+    fn test_region_parse_invalid(input: &str) {
+        assert!(input.parse::<HolidayRegion>().is_err());
+    }
+
+    #[test]
+    fn test_region_name() {
+        assert_eq!(
+            "DE-BY".parse::<HolidayRegion>().unwrap().name(),
+            "Bavaria, Germany"
+        );
+    }
+
+    #[test]
+    fn test_nationwide_holidays() {
+        assert_eq!(
+            names(&calendar("DE"), &year(2025)),
+            vec![
+                "New Year's Day",
+                "Good Friday",
+                "Easter Monday",
+                "Labour Day",
+                "Ascension Day",
+                "Whit Monday",
+                "German Unity Day",
+                "Christmas Eve",
+                "Christmas Day",
+                "Boxing Day",
+                "New Year's Eve",
+            ]
+        );
+    }
+
+    #[test_case("DE-BY", "2025-06-19", Some("Corpus Christi"); "corpus christi in bavaria")]
+    #[test_case("DE-BE", "2025-06-19", None; "no corpus christi in berlin")]
+    #[test_case("DE-SN", "2025-11-19", Some("Day of Repentance and Prayer"); "repentance day in saxony")]
+    #[test_case("DE-HH", "2025-10-31", Some("Reformation Day"); "reformation day in hamburg")]
+    #[test_case("DE-HH", "2017-10-31", None; "reformation day in hamburg before 2018")]
+    #[test_case("DE-MV", "2023-03-08", Some("International Women's Day"); "womens day since 2023")]
+    fn test_regional_holiday(region: &str, day: &str, expected: Option<&str>) {
+        let day = date(day);
+        let range = DateRange::day(day);
+        assert_eq!(names(&calendar(region), &range).first().copied(), expected);
+    }
+
+    #[test]
+    fn test_same_day_keeps_one_holiday() {
+        // Ascension Day was on Labour Day in 2008
+        let range = DateRange::day(date("2008-05-01"));
+        assert_eq!(calendar("DE").holidays(&range).len(), 1);
+    }
+
+    #[test]
+    fn test_half_days_can_be_left_out() {
+        let calendar = HolidayCalendar {
+            half_days: false,
+            ..calendar("DE")
+        };
+        let names = names(&calendar, &year(2025));
+        assert!(!names.contains(&"Christmas Eve"));
+        assert!(!names.contains(&"New Year's Eve"));
+    }
+
+    #[test]
+    fn test_no_region_has_no_holidays() {
+        let calendar = HolidayCalendar {
+            region: None,
+            half_days: true,
+        };
+        assert!(calendar.holidays(&year(2025)).is_empty());
+    }
+
+    #[test]
+    fn test_missing_absences_follow_target() {
+        // 2025-12-24 is a Wednesday, 2026-01-01 a Thursday, 2025-12-27 a Saturday
+        let range = DateRange::new(date("2025-12-24"), date("2026-01-01")).unwrap();
+        let absences = calendar("DE").missing_absences(&range, &schedules(), &[]);
+
+        let hours: Vec<(NaiveDate, f64)> = absences
+            .iter()
+            .map(|record| (record.date, record.hours))
+            .collect();
+        assert_eq!(
+            hours,
+            vec![
+                (date("2025-12-24"), 4.0),
+                (date("2025-12-25"), 8.0),
+                (date("2025-12-26"), 4.0),
+                (date("2025-12-31"), 4.0),
+                (date("2026-01-01"), 8.0),
+            ]
+        );
+        assert!(
+            absences
+                .iter()
+                .all(|record| record.absence_type == AbsenceType::Holiday)
+        );
+    }
+
+    #[test]
+    fn test_apply_keeps_stored_holidays() {
+        let stored = AbsenceRecord {
+            id: Ulid::new(),
+            date: date("2025-12-25"),
+            hours: 6.0,
+            absence_type: AbsenceType::Holiday,
+            note: None,
+            ical_uid: None,
+        };
+        let range = DateRange::new(date("2025-12-25"), date("2025-12-26")).unwrap();
+        let absences = calendar("DE").apply(vec![stored.clone()], &range, &schedules());
+
+        assert_eq!(absences.len(), 2);
+        assert_eq!(absences[0], stored);
+        assert_eq!(absences[1].date, date("2025-12-26"));
+    }
+}
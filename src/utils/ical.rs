@@ -0,0 +1,638 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use ical::IcalParser;
+use ical::parser::ParserError;
+use ical::parser::ical::component::IcalEvent;
+use ical::property::Property;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use thiserror::Error;
+
+use crate::utils::date::{DateRange, Timezone};
+
+/// Errors while reading a calendar file as a whole
+#[derive(Error, Debug)]
+pub enum IcsError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid calendar: {0}")]
+    Parse(#[from] ParserError),
+}
+
+/// Why a single event can't be imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    /// Without a UID, a re-import can't recognize the event
+    MissingUid,
+    MissingStart,
+    InvalidTime(String),
+    /// A `TZID` that isn't an IANA timezone name
+    UnknownTimezone(String),
+    InvalidDuration(String),
+    EndBeforeStart,
+    /// A timed event ending when it starts, which would be an absence of no hours
+    NoDuration,
+    /// Only single occurrences are imported, recurrence rules are not expanded
+    Recurring,
+    Cancelled,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventError::MissingUid => write!(f, "no UID"),
+            EventError::MissingStart => write!(f, "no start date"),
+            EventError::InvalidTime(value) => write!(f, "invalid date or time '{}'", value),
+            EventError::UnknownTimezone(tzid) => write!(f, "unknown timezone '{}'", tzid),
+            EventError::InvalidDuration(value) => write!(f, "invalid duration '{}'", value),
+            EventError::EndBeforeStart => write!(f, "ends before it starts"),
+            EventError::NoDuration => write!(f, "ends when it starts"),
+            EventError::Recurring => write!(f, "recurring events are not supported"),
+            EventError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// A calendar event, reduced to what an absence needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: Option<String>,
+    /// Days covered by the event
+    pub dates: DateRange,
+    /// Hours of a timed event within a single day, all-day events have none
+    pub hours: Option<f64>,
+}
+
+/// An event that was skipped while reading, with what's known about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedEvent {
+    pub summary: Option<String>,
+    pub error: EventError,
+}
+
+/// Start or end of an event
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EventTime {
+    Date(NaiveDate),
+    /// Local date and time, with UTC times and times with a `TZID` already converted
+    DateTime(NaiveDateTime),
+}
+
+/// Read all events of an iCalendar (`.ics`) file
+///
+/// All-day events cover every day from their start up to, but not
+/// including, their end date. Timed events within a single day keep their
+/// duration as hours, timed events spanning several days count as all-day
+/// events on each of them. UTC times and times with a `TZID` are converted to
+/// the given timezone, floating times are taken as they are.
+pub fn read_events<R: BufRead>(
+    reader: R,
+    timezone: Timezone,
+) -> Result<Vec<Result<CalendarEvent, RejectedEvent>>, IcsError> {
+    let mut events = Vec::new();
+    for calendar in IcalParser::new(reader) {
+        for event in calendar?.events {
+            let summary = property(&event, "SUMMARY").map(unescape_text);
+            events.push(
+                to_calendar_event(&event, summary.clone(), timezone)
+                    .map_err(|error| RejectedEvent { summary, error }),
+            );
+        }
+    }
+    Ok(events)
+}
+
+fn property<'e>(event: &'e IcalEvent, name: &str) -> Option<&'e str> {
+    find_property(event, name).and_then(|property| property.value.as_deref())
+}
+
+fn find_property<'e>(event: &'e IcalEvent, name: &str) -> Option<&'e Property> {
+    event
+        .properties
+        .iter()
+        .find(|property| property.name == name)
+}
+
+fn to_calendar_event(
+    event: &IcalEvent,
+    summary: Option<String>,
+    timezone: Timezone,
+) -> Result<CalendarEvent, EventError> {
+    let uid = property(event, "UID")
+        .filter(|uid| !uid.trim().is_empty())
+        .ok_or(EventError::MissingUid)?;
+    if find_property(event, "RRULE").is_some() {
+        return Err(EventError::Recurring);
+    }
+    if property(event, "STATUS").is_some_and(|status| status.eq_ignore_ascii_case("CANCELLED")) {
+        return Err(EventError::Cancelled);
+    }
+
+    let start = find_property(event, "DTSTART")
+        .ok_or(EventError::MissingStart)
+        .and_then(|property| parse_time(property, timezone))?;
+    let end = match (find_property(event, "DTEND"), property(event, "DURATION")) {
+        (Some(end), _) => parse_time(end, timezone)?,
+        (None, Some(duration)) => {
+            let duration = parse_duration(duration)
+                .ok_or_else(|| EventError::InvalidDuration(duration.to_string()))?;
+            match start {
+                EventTime::Date(date) => EventTime::Date(date + duration),
+                EventTime::DateTime(datetime) => EventTime::DateTime(datetime + duration),
+            }
+        }
+        // All-day events without an end last one day, timed ones no time at all,
+        // which rejects them below
+        (None, None) => match start {
+            EventTime::Date(date) => EventTime::Date(date + Duration::days(1)),
+            EventTime::DateTime(datetime) => EventTime::DateTime(datetime),
+        },
+    };
+
+    let (from, to, hours) = match (start, end) {
+        (EventTime::Date(from), EventTime::Date(end)) => {
+            // The end date is exclusive, a missing or equal one still means one day
+            (from, (end - Duration::days(1)).max(from), None)
+        }
+        (EventTime::DateTime(from), EventTime::DateTime(end)) => {
+            if end < from {
+                return Err(EventError::EndBeforeStart);
+            }
+            if end == from {
+                return Err(EventError::NoDuration);
+            }
+            // An event ending at midnight doesn't touch the next day
+            let last_day = if end.time() == NaiveTime::MIN && end > from {
+                end.date() - Duration::days(1)
+            } else {
+                end.date()
+            };
+            let hours = (last_day == from.date()).then(|| (end - from).num_minutes() as f64 / 60.0);
+            (from.date(), last_day, hours)
+        }
+        // Mixing dates and times is invalid, so fall back to whole days
+        (start, end) => (
+            event_date(start),
+            event_date(end).max(event_date(start)),
+            None,
+        ),
+    };
+
+    Ok(CalendarEvent {
+        uid: uid.to_string(),
+        summary,
+        dates: DateRange::new(from, to).map_err(|_| EventError::EndBeforeStart)?,
+        hours,
+    })
+}
+
+fn event_date(time: EventTime) -> NaiveDate {
+    match time {
+        EventTime::Date(date) => date,
+        EventTime::DateTime(datetime) => datetime.date(),
+    }
+}
+
+/// Parse a `DTSTART` or `DTEND` property
+fn parse_time(property: &Property, timezone: Timezone) -> Result<EventTime, EventError> {
+    let value = property.value.as_deref().unwrap_or_default().trim();
+    let invalid = || EventError::InvalidTime(value.to_string());
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(EventTime::Date(date));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let datetime =
+            NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        let datetime = DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc);
+        return Ok(EventTime::DateTime(timezone.local_datetime_of(&datetime)));
+    }
+
+    let datetime = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+    let Some(tzid) = parameter(property, "TZID") else {
+        return Ok(EventTime::DateTime(datetime));
+    };
+    let tz = tzid
+        .parse::<Tz>()
+        .map_err(|_| EventError::UnknownTimezone(tzid.to_string()))?;
+    // Times skipped by a DST change don't exist, repeated ones are taken the first time
+    let datetime = tz
+        .from_local_datetime(&datetime)
+        .earliest()
+        .ok_or_else(invalid)?;
+    Ok(EventTime::DateTime(
+        timezone.local_datetime_of(&datetime.with_timezone(&Utc)),
+    ))
+}
+
+fn parameter<'p>(property: &'p Property, name: &str) -> Option<&'p str> {
+    property
+        .params
+        .iter()
+        .flatten()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, values)| values.first())
+        .map(|value| value.trim_matches('"'))
+}
+
+/// Parse a `DURATION` value like `P1D`, `PT8H` or `P1DT12H`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().strip_prefix('+').unwrap_or(value.trim());
+    let (date_part, time_part) = match value.strip_prefix('P')?.split_once('T') {
+        Some((date_part, time_part)) => (date_part, Some(time_part)),
+        None => (value.strip_prefix('P')?, None),
+    };
+
+    let mut duration = Duration::zero();
+    let mut parse_units = |part: &str, units: &[(char, i64)]| -> Option<()> {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let seconds = units.iter().find(|(unit, _)| *unit == c)?.1;
+            duration += Duration::seconds(number.parse::<i64>().ok()? * seconds);
+            number.clear();
+        }
+        number.is_empty().then_some(())
+    };
+
+    parse_units(date_part, &[('W', 7 * 86_400), ('D', 86_400)])?;
+    if let Some(time_part) = time_part {
+        parse_units(time_part, &[('H', 3_600), ('M', 60), ('S', 1)])?;
+    }
+    Some(duration)
+}
+
+/// Undo the escaping of iCalendar text values
+fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// When an exported event takes place
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventSpan {
+    /// Whole days, from the first to the last one
+    AllDay(DateRange),
+    /// From one point in time to another, written in UTC
+    Timed {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+}
+
+/// An event to write into a calendar file
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    /// Has to stay the same across exports, so calendar apps update events instead of duplicating them
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub span: EventSpan,
+}
+
+const PRODUCT_ID: &str = "-//wad//wad//EN";
+
+/// Write events as an iCalendar (RFC 5545) file
+///
+/// `stamp` becomes the `DTSTAMP` of every event, which calendar apps use
+/// to tell a newer export of an event from an older one.
+pub fn write_calendar<W: Write>(
+    mut writer: W,
+    events: &[IcsEvent],
+    stamp: DateTime<Utc>,
+) -> io::Result<()> {
+    let utc = |datetime: DateTime<Utc>| datetime.format("%Y%m%dT%H%M%SZ").to_string();
+    let day = |date: NaiveDate| date.format("%Y%m%d").to_string();
+
+    write_line(&mut writer, "BEGIN:VCALENDAR")?;
+    write_line(&mut writer, "VERSION:2.0")?;
+    write_line(&mut writer, &format!("PRODID:{}", PRODUCT_ID))?;
+    write_line(&mut writer, "CALSCALE:GREGORIAN")?;
+    for event in events {
+        write_line(&mut writer, "BEGIN:VEVENT")?;
+        write_line(&mut writer, &format!("UID:{}", escape_text(&event.uid)))?;
+        write_line(&mut writer, &format!("DTSTAMP:{}", utc(stamp)))?;
+        match event.span {
+            EventSpan::AllDay(dates) => {
+                // The end date is exclusive
+                write_line(
+                    &mut writer,
+                    &format!("DTSTART;VALUE=DATE:{}", day(dates.from)),
+                )?;
+                write_line(
+                    &mut writer,
+                    &format!("DTEND;VALUE=DATE:{}", day(dates.to + Duration::days(1))),
+                )?;
+                // All-day absences shouldn't block the day in free/busy lookups
+                write_line(&mut writer, "TRANSP:TRANSPARENT")?;
+            }
+            EventSpan::Timed { start, end } => {
+                write_line(&mut writer, &format!("DTSTART:{}", utc(start)))?;
+                write_line(&mut writer, &format!("DTEND:{}", utc(end)))?;
+            }
+        }
+        write_line(
+            &mut writer,
+            &format!("SUMMARY:{}", escape_text(&event.summary)),
+        )?;
+        if let Some(description) = &event.description {
+            write_line(
+                &mut writer,
+                &format!("DESCRIPTION:{}", escape_text(description)),
+            )?;
+        }
+        write_line(&mut writer, "END:VEVENT")?;
+    }
+    write_line(&mut writer, "END:VCALENDAR")?;
+    // Buffered writers would otherwise drop errors of the final write
+    writer.flush()
+}
+
+/// Write a content line, folded so no line is longer than 75 octets
+fn write_line<W: Write>(writer: &mut W, line: &str) -> io::Result<()> {
+    let mut octets = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards their length
+        if octets + c.len_utf8() > 75 {
+            writer.write_all(b"\r\n ")?;
+            octets = 1;
+        }
+        write!(writer, "{}", c)?;
+        octets += c.len_utf8();
+    }
+    writer.write_all(b"\r\n")
+}
+
+/// Escape a text value, the reverse of `unescape_text`
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn read(events: &str) -> Vec<Result<CalendarEvent, RejectedEvent>> {
+        let calendar = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n{}END:VCALENDAR\r\n",
+            events
+        );
+        read_events(calendar.as_bytes(), Timezone::Local).unwrap()
+    }
+
+    fn read_one(properties: &str) -> Result<CalendarEvent, RejectedEvent> {
+        read(&format!("BEGIN:VEVENT\r\n{}END:VEVENT\r\n", properties)).remove(0)
+    }
+
+    #[test]
+    fn test_all_day_event() {
+        let event =
+            read_one("UID:a\r\nSUMMARY:Closing day\\, office\r\nDTSTART;VALUE=DATE:20251224\r\nDTEND;VALUE=DATE:20251225\r\n")
+                .unwrap();
+        assert_eq!(event.uid, "a");
+        assert_eq!(event.summary.as_deref(), Some("Closing day, office"));
+        assert_eq!(event.dates, DateRange::day(date("2025-12-24")));
+        assert_eq!(event.hours, None);
+    }
+
+    #[test_case("DTEND;VALUE=DATE:20251231"; "exclusive end date")]
+    #[test_case("DURATION:P7D"; "duration in days")]
+    #[test_case("DURATION:P1W"; "duration in weeks")]
+    fn test_multi_day_event(end: &str) {
+        let event = read_one(&format!(
+            "UID:a\r\nDTSTART;VALUE=DATE:20251224\r\n{}\r\n",
+            end
+        ))
+        .unwrap();
+        assert_eq!(
+            event.dates,
+            DateRange::new(date("2025-12-24"), date("2025-12-30")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_all_day_event_without_end() {
+        let event = read_one("UID:a\r\nDTSTART;VALUE=DATE:20251224\r\n").unwrap();
+        assert_eq!(event.dates, DateRange::day(date("2025-12-24")));
+    }
+
+    #[test_case("DTSTART:20250303T080000\r\nDTEND:20250303T120000", Some(4.0); "floating times")]
+    #[test_case("DTSTART:20250303T080000\r\nDURATION:PT1H30M", Some(1.5); "duration in hours")]
+    #[test_case("DTSTART:20250303T120000\r\nDTEND:20250304T000000", Some(12.0); "ending at midnight")]
+    fn test_timed_event_within_a_day(times: &str, hours: Option<f64>) {
+        let event = read_one(&format!("UID:a\r\n{}\r\n", times)).unwrap();
+        assert_eq!(event.dates, DateRange::day(date("2025-03-03")));
+        assert_eq!(event.hours, hours);
+    }
+
+    #[test]
+    fn test_timed_event_over_several_days() {
+        let event =
+            read_one("UID:a\r\nDTSTART:20250303T090000\r\nDTEND:20250305T170000\r\n").unwrap();
+        assert_eq!(
+            event.dates,
+            DateRange::new(date("2025-03-03"), date("2025-03-05")).unwrap()
+        );
+        assert_eq!(event.hours, None);
+    }
+
+    #[test]
+    fn test_utc_times_are_converted() {
+        let calendar = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nDTSTART:20250302T230000Z\r\nDTEND:20250303T030000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let timezone: Timezone = "Europe/Berlin".parse().unwrap();
+        let event = read_events(calendar.as_bytes(), timezone)
+            .unwrap()
+            .remove(0)
+            .unwrap();
+        assert_eq!(event.dates, DateRange::day(date("2025-03-03")));
+        assert_eq!(event.hours, Some(4.0));
+    }
+
+    #[test]
+    fn test_times_with_tzid_are_converted() {
+        // 03:00 to 07:00 in Tokyo is 19:00 to 23:00 on the day before in Berlin
+        let calendar = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nDTSTART;TZID=Asia/Tokyo:20250303T030000\r\nDTEND;TZID=Asia/Tokyo:20250303T070000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let timezone: Timezone = "Europe/Berlin".parse().unwrap();
+        let event = read_events(calendar.as_bytes(), timezone)
+            .unwrap()
+            .remove(0)
+            .unwrap();
+        assert_eq!(event.dates, DateRange::day(date("2025-03-02")));
+        assert_eq!(event.hours, Some(4.0));
+    }
+
+    #[test_case("DTSTART;VALUE=DATE:20250303", EventError::MissingUid; "missing uid")]
+    #[test_case("UID:a", EventError::MissingStart; "missing start")]
+    #[test_case("UID:a\r\nDTSTART:tomorrow", EventError::InvalidTime("tomorrow".to_string()); "invalid start")]
+    #[test_case("UID:a\r\nDTSTART;TZID=W. Europe Standard Time:20250303T080000", EventError::UnknownTimezone("W. Europe Standard Time".to_string()); "unknown timezone")]
+    #[test_case("UID:a\r\nDTSTART;VALUE=DATE:20250303\r\nRRULE:FREQ=YEARLY", EventError::Recurring; "recurring")]
+    #[test_case("UID:a\r\nDTSTART;VALUE=DATE:20250303\r\nSTATUS:CANCELLED", EventError::Cancelled; "cancelled")]
+    #[test_case("UID:a\r\nDTSTART:20250303T120000\r\nDTEND:20250303T080000", EventError::EndBeforeStart; "end before start")]
+    #[test_case("UID:a\r\nDTSTART;VALUE=DATE:20250303\r\nDURATION:1 day", EventError::InvalidDuration("1 day".to_string()); "invalid duration")]
+    #[test_case("UID:a\r\nDTSTART:20250303T080000", EventError::NoDuration; "timed without end")]
+    #[test_case("UID:a\r\nDTSTART:20250303T080000\r\nDTEND:20250303T080000", EventError::NoDuration; "timed ending at start")]
+    fn test_rejected_event(properties: &str, error: EventError) {
+        let rejected = read_one(&format!("{}\r\nSUMMARY:Offsite\r\n", properties)).unwrap_err();
+        assert_eq!(rejected.error, error);
+        assert_eq!(rejected.summary.as_deref(), Some("Offsite"));
+    }
+
+    #[test]
+    fn test_read_several_events() {
+        let events = read(
+            "BEGIN:VEVENT\r\nUID:a\r\nDTSTART;VALUE=DATE:20250303\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:b\r\nDTSTART;VALUE=DATE:20250304\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(Result::is_ok));
+    }
+
+    #[test]
+    fn test_invalid_calendar() {
+        let result = read_events(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n".as_bytes(),
+            Timezone::Local,
+        );
+        assert!(result.is_err());
+    }
+
+    fn export(events: &[IcsEvent]) -> String {
+        let mut buffer = Vec::new();
+        let stamp = DateTime::parse_from_rfc3339("2025-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        write_calendar(&mut buffer, events, stamp).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let events = vec![
+            IcsEvent {
+                uid: "a@wad".to_string(),
+                summary: "Vacation: beach, sun; sea".to_string(),
+                description: Some("8 hours\nbooked".to_string()),
+                span: EventSpan::AllDay(
+                    DateRange::new(date("2025-07-01"), date("2025-07-02")).unwrap(),
+                ),
+            },
+            IcsEvent {
+                uid: "b@watson".to_string(),
+                summary: "wad +rust".to_string(),
+                description: None,
+                span: EventSpan::Timed {
+                    start: utc("2025-03-03T08:00:00Z"),
+                    end: utc("2025-03-03T10:30:00Z"),
+                },
+            },
+        ];
+
+        let calendar = export(&events);
+        let timezone: Timezone = "UTC".parse().unwrap();
+        let read: Vec<CalendarEvent> = read_events(calendar.as_bytes(), timezone)
+            .unwrap()
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(read[0].uid, "a@wad");
+        assert_eq!(
+            read[0].summary.as_deref(),
+            Some("Vacation: beach, sun; sea")
+        );
+        assert_eq!(
+            read[0].dates,
+            DateRange::new(date("2025-07-01"), date("2025-07-02")).unwrap()
+        );
+        assert_eq!(read[1].dates, DateRange::day(date("2025-03-03")));
+        assert_eq!(read[1].hours, Some(2.5));
+    }
+
+    #[test]
+    fn test_write_required_properties() {
+        let calendar = export(&[]);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+
+        let calendar = export(&[IcsEvent {
+            uid: "a".to_string(),
+            summary: "x".to_string(),
+            description: None,
+            span: EventSpan::AllDay(DateRange::day(date("2025-07-01"))),
+        }]);
+        assert!(calendar.contains("DTSTAMP:20250301T120000Z\r\n"));
+        assert!(calendar.contains("DTEND;VALUE=DATE:20250702\r\n"));
+    }
+
+    #[test]
+    fn test_write_reports_flush_errors() {
+        /// Accepts every write, but fails once the data has to reach the disk
+        struct FullDisk;
+
+        impl Write for FullDisk {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Err(io::Error::other("disk full"))
+            }
+        }
+
+        assert!(write_calendar(FullDisk, &[], Utc::now()).is_err());
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let summary = vec!["Überstunden"; 20].join(" ");
+        let calendar = export(&[IcsEvent {
+            uid: "a".to_string(),
+            summary: summary.clone(),
+            description: None,
+            span: EventSpan::AllDay(DateRange::day(date("2025-07-01"))),
+        }]);
+
+        assert!(calendar.split("\r\n").all(|line| line.len() <= 75));
+        let event = read_events(calendar.as_bytes(), Timezone::Local)
+            .unwrap()
+            .remove(0)
+            .unwrap();
+        assert_eq!(event.summary, Some(summary));
+    }
+}
@@ -1,5 +1,18 @@
+pub mod absence_plan;
+pub mod absence_summary;
+pub mod balance;
+pub mod breaks;
+pub mod compliance;
 pub mod date;
+pub mod doctor;
 pub mod editor;
 pub mod formatting;
+pub mod holidays;
+pub mod ical;
+pub mod output;
+pub mod projection;
+pub mod report;
+pub mod schedule;
 pub mod selection;
 pub mod spinner;
+pub mod vacation;
@@ -0,0 +1,263 @@
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+use thiserror::Error;
+
+use crate::utils::date::DayTimeBreakdown;
+use crate::wad_data::AbsenceRecord;
+
+/// Errors while writing machine-readable output
+#[derive(Error, Debug)]
+pub enum OutputError {
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Text output can't be written as records")]
+    TextFormat,
+}
+
+/// A flat record of machine-readable output
+pub trait Record: Serialize {
+    /// Names of the serialized fields, in order
+    ///
+    /// Field names are part of the output's interface, so renaming them
+    /// breaks scripts consuming it.
+    const FIELDS: &'static [&'static str];
+}
+
+/// How a command presents its results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored text and tables for humans
+    #[default]
+    Text,
+    /// A JSON array of records
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Tab-separated values with a header row
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Check whether the output is meant for humans rather than scripts
+    pub fn is_text(self) -> bool {
+        self == OutputFormat::Text
+    }
+
+    /// Write records in this format
+    ///
+    /// Records have to be flat, as CSV and TSV have no way to represent
+    /// nested values. The header row is taken from the record type, so it is
+    /// written even without any records.
+    pub fn write_records<T: Record, W: Write>(
+        self,
+        mut writer: W,
+        records: &[T],
+    ) -> Result<(), OutputError> {
+        let delimiter = match self {
+            OutputFormat::Text => return Err(OutputError::TextFormat),
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, records)?;
+                writeln!(writer)?;
+                return Ok(());
+            }
+            OutputFormat::Csv => b',',
+            OutputFormat::Tsv => b'\t',
+        };
+
+        let mut csv_writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .from_writer(writer);
+        csv_writer.write_record(T::FIELDS)?;
+        for record in records {
+            csv_writer.serialize(record)?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    /// Print records in this format to stdout
+    pub fn print_records<T: Record>(self, records: &[T]) -> Result<(), OutputError> {
+        self.write_records(io::stdout().lock(), records)
+    }
+}
+
+/// Time of a single day, as emitted by the worktime commands
+#[derive(Debug, Serialize)]
+pub struct DayRecord {
+    pub date: NaiveDate,
+    pub worked_minutes: i64,
+    pub absence_minutes: i64,
+    pub total_minutes: i64,
+    pub target_minutes: i64,
+}
+
+impl Record for DayRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "date",
+        "worked_minutes",
+        "absence_minutes",
+        "total_minutes",
+        "target_minutes",
+    ];
+}
+
+impl DayRecord {
+    pub fn new(date: NaiveDate, breakdown: &DayTimeBreakdown, target: chrono::Duration) -> Self {
+        let total = breakdown.total_duration();
+        Self {
+            date,
+            worked_minutes: breakdown.watson_duration.num_minutes(),
+            absence_minutes: (total - breakdown.watson_duration).num_minutes(),
+            total_minutes: total.num_minutes(),
+            target_minutes: target.num_minutes(),
+        }
+    }
+}
+
+/// A stored absence, with its type spelled like on the command line
+#[derive(Debug, Serialize)]
+pub struct AbsenceRow {
+    pub id: String,
+    pub date: NaiveDate,
+    pub hours: f64,
+    pub absence_type: String,
+    pub note: Option<String>,
+}
+
+impl Record for AbsenceRow {
+    const FIELDS: &'static [&'static str] = &["id", "date", "hours", "absence_type", "note"];
+}
+
+impl From<&AbsenceRecord> for AbsenceRow {
+    fn from(record: &AbsenceRecord) -> Self {
+        Self {
+            id: record.id.to_string(),
+            date: record.date,
+            hours: record.hours,
+            absence_type: record.absence_type.to_string(),
+            note: record.note.clone(),
+        }
+    }
+}
+
+/// A file system location, as printed by the `path` subcommands
+#[derive(Debug, Serialize)]
+pub struct PathRecord {
+    pub path: String,
+}
+
+impl Record for PathRecord {
+    const FIELDS: &'static [&'static str] = &["path"];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad_data::AbsenceType;
+    use test_case::test_case;
+    use ulid::Ulid;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: &'static str,
+        minutes: i64,
+        note: Option<&'static str>,
+    }
+
+    impl Record for Row {
+        const FIELDS: &'static [&'static str] = &["name", "minutes", "note"];
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                name: "a",
+                minutes: 90,
+                note: None,
+            },
+            Row {
+                name: "b, c",
+                minutes: -15,
+                note: Some("x"),
+            },
+        ]
+    }
+
+    fn render(format: OutputFormat, rows: &[Row]) -> String {
+        let mut buffer = Vec::new();
+        format.write_records(&mut buffer, rows).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test_case(OutputFormat::Csv, "name,minutes,note\na,90,\n\"b, c\",-15,x\n"; "csv")]
+    #[test_case(OutputFormat::Tsv, "name\tminutes\tnote\na\t90\t\nb, c\t-15\tx\n"; "tsv")]
+    fn test_write_delimited(format: OutputFormat, expected: &str) {
+        assert_eq!(render(format, &rows()), expected);
+    }
+
+    #[test_case(OutputFormat::Csv, "name,minutes,note\n"; "csv")]
+    #[test_case(OutputFormat::Tsv, "name\tminutes\tnote\n"; "tsv")]
+    #[test_case(OutputFormat::Json, "[]\n"; "json")]
+    fn test_write_without_records_keeps_header(format: OutputFormat, expected: &str) {
+        assert_eq!(render(format, &[]), expected);
+    }
+
+    #[test]
+    fn test_write_json() {
+        let value: serde_json::Value =
+            serde_json::from_str(&render(OutputFormat::Json, &rows())).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                {"name": "a", "minutes": 90, "note": null},
+                {"name": "b, c", "minutes": -15, "note": "x"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_write_text_is_rejected() {
+        let result = OutputFormat::Text.write_records(Vec::new(), &rows());
+        assert!(matches!(result, Err(OutputError::TextFormat)));
+    }
+
+    #[test]
+    fn test_day_record() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let absence = AbsenceRecord {
+            id: Ulid::new(),
+            date,
+            hours: 4.0,
+            absence_type: AbsenceType::Vacation,
+            note: None,
+            ical_uid: None,
+        };
+        let breakdown = DayTimeBreakdown::new(chrono::Duration::minutes(200), vec![absence]);
+
+        let record = DayRecord::new(date, &breakdown, chrono::Duration::hours(8));
+        assert_eq!(record.worked_minutes, 200);
+        assert_eq!(record.absence_minutes, 240);
+        assert_eq!(record.total_minutes, 440);
+        assert_eq!(record.target_minutes, 480);
+    }
+
+    #[test]
+    fn test_absence_row_uses_command_line_type() {
+        let record = AbsenceRecord {
+            id: Ulid::new(),
+            date: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+            hours: 8.0,
+            absence_type: AbsenceType::Other("Moving".to_string()),
+            note: None,
+            ical_uid: None,
+        };
+        assert_eq!(AbsenceRow::from(&record).absence_type, "other:Moving");
+    }
+}
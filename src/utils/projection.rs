@@ -0,0 +1,133 @@
+use crate::utils::breaks::BreakRules;
+use crate::utils::date::DayTimeBreakdown;
+use chrono::{DateTime, Duration, Utc};
+
+/// Projection of how much is left to do today to reach the daily target
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayProjection {
+    /// Work time still missing to reach the target
+    pub remaining: Duration,
+    /// Mandatory break that still has to be taken before reaching the target
+    pub owed_break: Duration,
+    /// Work time beyond the target
+    pub overtime: Duration,
+}
+
+impl DayProjection {
+    /// Project the rest of the day from what has been done so far
+    ///
+    /// Absences count towards the target, but only tracked time counts
+    /// towards the break rules.
+    pub fn new(
+        breakdown: &DayTimeBreakdown,
+        target: Duration,
+        break_taken: Duration,
+        break_rules: &BreakRules,
+    ) -> Self {
+        let done = breakdown.total_duration();
+        let remaining = (target - done).max(Duration::zero());
+        let worked_at_target = breakdown.watson_duration + remaining;
+
+        Self {
+            remaining,
+            owed_break: if remaining > Duration::zero() {
+                break_rules.owed_break(worked_at_target, break_taken)
+            } else {
+                Duration::zero()
+            },
+            overtime: (done - target).max(Duration::zero()),
+        }
+    }
+
+    pub fn is_target_reached(&self) -> bool {
+        self.remaining <= Duration::zero()
+    }
+
+    /// Get the point in time the target is reached when working on from `now`
+    pub fn leave_time(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + self.remaining + self.owed_break
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::breaks::BreakRule;
+    use crate::wad_data::{AbsenceRecord, AbsenceType};
+    use chrono::{NaiveDate, TimeZone};
+    use ulid::Ulid;
+
+    fn rules() -> BreakRules {
+        BreakRules::new(vec![BreakRule {
+            worked_over: Duration::hours(6),
+            minimum_break: Duration::minutes(30),
+        }])
+    }
+
+    fn worked(minutes: i64) -> DayTimeBreakdown {
+        DayTimeBreakdown::new(Duration::minutes(minutes), vec![])
+    }
+
+    #[test]
+    fn test_remaining_with_owed_break() {
+        let projection = DayProjection::new(
+            &worked(5 * 60),
+            Duration::hours(8),
+            Duration::minutes(10),
+            &rules(),
+        );
+
+        assert_eq!(projection.remaining, Duration::hours(3));
+        assert_eq!(projection.owed_break, Duration::minutes(20));
+        assert!(!projection.is_target_reached());
+
+        let now = Utc.with_ymd_and_hms(2025, 3, 3, 13, 0, 0).unwrap();
+        assert_eq!(
+            projection.leave_time(now),
+            Utc.with_ymd_and_hms(2025, 3, 3, 16, 20, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_short_target_owes_no_break() {
+        let projection =
+            DayProjection::new(&worked(60), Duration::hours(4), Duration::zero(), &rules());
+
+        assert_eq!(projection.remaining, Duration::hours(3));
+        assert_eq!(projection.owed_break, Duration::zero());
+    }
+
+    #[test]
+    fn test_absences_count_towards_target_but_not_break() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let half_day_off = AbsenceRecord {
+            id: Ulid::new(),
+            date,
+            hours: 4.0,
+            absence_type: AbsenceType::Vacation,
+            note: None,
+            ical_uid: None,
+        };
+        let breakdown = DayTimeBreakdown::new(Duration::hours(2), vec![half_day_off]);
+
+        let projection =
+            DayProjection::new(&breakdown, Duration::hours(8), Duration::zero(), &rules());
+
+        assert_eq!(projection.remaining, Duration::hours(2));
+        assert_eq!(projection.owed_break, Duration::zero());
+    }
+
+    #[test]
+    fn test_target_reached() {
+        let projection = DayProjection::new(
+            &worked(8 * 60 + 30),
+            Duration::hours(8),
+            Duration::zero(),
+            &rules(),
+        );
+
+        assert!(projection.is_target_reached());
+        assert_eq!(projection.owed_break, Duration::zero());
+        assert_eq!(projection.overtime, Duration::minutes(30));
+    }
+}
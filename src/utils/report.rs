@@ -0,0 +1,170 @@
+use crate::watson::frame::Frames;
+use chrono::Duration;
+
+/// Label of the group holding frames without any tag
+pub const UNTAGGED: &str = "(untagged)";
+
+/// Which dimension a report is grouped by first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportGrouping {
+    /// Projects, each split up by tag
+    ProjectThenTag,
+    /// Tags, each split up by project, e.g. for totals per ticket
+    TagThenProject,
+}
+
+/// A project or tag in a report, with the groups below it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportNode {
+    pub name: String,
+    pub duration: Duration,
+    pub children: Vec<ReportNode>,
+}
+
+impl ReportNode {
+    fn leaf(name: impl Into<String>, frames: &Frames) -> Self {
+        Self {
+            name: name.into(),
+            duration: frames.total_duration(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Share of the given total, in percent
+    pub fn percent_of(&self, total: Duration) -> f64 {
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.duration.num_seconds() as f64 / total.num_seconds() as f64 * 100.0
+    }
+}
+
+/// Build the tree of tracked time, sorted by name on each level
+///
+/// Frames with several tags are counted for each tag, so the tags of a
+/// project can add up to more than the project. Untagged frames are
+/// grouped under [`UNTAGGED`].
+pub fn build_tree(frames: &Frames, grouping: ReportGrouping) -> Vec<ReportNode> {
+    let by_project_and_tag = frames.by_project_and_tag();
+
+    let mut nodes: Vec<ReportNode> = match grouping {
+        ReportGrouping::ProjectThenTag => frames
+            .by_project()
+            .into_iter()
+            .map(|(project, project_frames)| {
+                let mut children: Vec<ReportNode> = by_project_and_tag
+                    .iter()
+                    .filter(|((other, _), _)| *other == project)
+                    .map(|((_, tag), frames)| ReportNode::leaf(tag, frames))
+                    .collect();
+                let untagged = project_frames.untagged();
+                if !untagged.frames.is_empty() {
+                    children.push(ReportNode::leaf(UNTAGGED, &untagged));
+                }
+                ReportNode {
+                    children,
+                    ..ReportNode::leaf(project, &project_frames)
+                }
+            })
+            .collect(),
+        ReportGrouping::TagThenProject => {
+            let mut nodes: Vec<ReportNode> = frames
+                .by_tag()
+                .into_iter()
+                .map(|(tag, tag_frames)| ReportNode {
+                    children: by_project_and_tag
+                        .iter()
+                        .filter(|((_, other), _)| *other == tag)
+                        .map(|((project, _), frames)| ReportNode::leaf(project, frames))
+                        .collect(),
+                    ..ReportNode::leaf(tag, &tag_frames)
+                })
+                .collect();
+
+            let untagged = frames.untagged();
+            if !untagged.frames.is_empty() {
+                nodes.push(ReportNode {
+                    children: untagged
+                        .by_project()
+                        .iter()
+                        .map(|(project, frames)| ReportNode::leaf(project, frames))
+                        .collect(),
+                    ..ReportNode::leaf(UNTAGGED, &untagged)
+                });
+            }
+            nodes
+        }
+    };
+
+    sort_by_name(&mut nodes);
+    nodes
+}
+
+/// Sort nodes by name, keeping the untagged group last
+fn sort_by_name(nodes: &mut [ReportNode]) {
+    nodes.sort_by(|a, b| (a.name == UNTAGGED, &a.name).cmp(&(b.name == UNTAGGED, &b.name)));
+    for node in nodes {
+        sort_by_name(&mut node.children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watson::frame::Frame;
+    use chrono::{TimeZone, Utc};
+    use test_case::test_case;
+
+    fn frames() -> Frames {
+        let start = Utc.with_ymd_and_hms(2025, 3, 3, 8, 0, 0).unwrap();
+        let frame = |project: &str, hours, tags: &[&str]| Frame {
+            id: format!("{}-{}", project, hours),
+            project: project.to_string(),
+            start,
+            stop: Some(start + Duration::hours(hours)),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        };
+        Frames::from(vec![
+            frame("wad", 1, &["ABC-1"]),
+            frame("wad", 2, &["ABC-2"]),
+            frame("ops", 3, &["ABC-1"]),
+            frame("ops", 4, &[]),
+        ])
+    }
+
+    /// Flatten a tree into lines of names and hours, indented by level
+    fn lines(nodes: &[ReportNode]) -> Vec<String> {
+        nodes
+            .iter()
+            .flat_map(|node| {
+                std::iter::once(format!("{} {}", node.name, node.duration.num_hours())).chain(
+                    node.children
+                        .iter()
+                        .map(|child| format!("  {} {}", child.name, child.duration.num_hours())),
+                )
+            })
+            .collect()
+    }
+
+    #[test_case(ReportGrouping::ProjectThenTag, &[
+        "ops 7", "  ABC-1 3", "  (untagged) 4",
+        "wad 3", "  ABC-1 1", "  ABC-2 2",
+    ] ; "project then tag")]
+    #[test_case(ReportGrouping::TagThenProject, &[
+        "ABC-1 4", "  ops 3", "  wad 1",
+        "ABC-2 2", "  wad 2",
+        "(untagged) 4", "  ops 4",
+    ] ; "tag then project")]
+    fn test_build_tree(grouping: ReportGrouping, expected: &[&str]) {
+        assert_eq!(lines(&build_tree(&frames(), grouping)), expected);
+    }
+
+    #[test]
+    fn test_percent_of_total() {
+        let frames = frames();
+        let tree = build_tree(&frames, ReportGrouping::ProjectThenTag);
+
+        assert_eq!(tree[0].percent_of(frames.total_duration()), 70.0);
+        assert_eq!(tree[0].percent_of(Duration::zero()), 0.0);
+    }
+}
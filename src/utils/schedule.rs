@@ -0,0 +1,293 @@
+use crate::utils::date::{Week, hours_to_duration};
+use crate::wad_data::ContractPeriod;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Target work hours for each day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeeklySchedule {
+    pub monday: f64,
+    pub tuesday: f64,
+    pub wednesday: f64,
+    pub thursday: f64,
+    pub friday: f64,
+    pub saturday: f64,
+    pub sunday: f64,
+}
+
+impl WeeklySchedule {
+    /// Spread weekly hours evenly across Monday to Friday
+    pub fn spread_over_weekdays(weekly_hours: f64) -> Self {
+        let daily_hours = weekly_hours / 5.0;
+        Self {
+            monday: daily_hours,
+            tuesday: daily_hours,
+            wednesday: daily_hours,
+            thursday: daily_hours,
+            friday: daily_hours,
+            saturday: 0.0,
+            sunday: 0.0,
+        }
+    }
+
+    /// Get the target hours for the given weekday
+    pub fn hours(&self, weekday: Weekday) -> f64 {
+        match weekday {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+
+    /// Get the target work time for the given weekday
+    pub fn daily_target(&self, weekday: Weekday) -> Duration {
+        hours_to_duration(self.hours(weekday))
+    }
+
+    /// Get the total target hours of a whole week
+    pub fn weekly_hours(&self) -> f64 {
+        self.monday
+            + self.tuesday
+            + self.wednesday
+            + self.thursday
+            + self.friday
+            + self.saturday
+            + self.sunday
+    }
+}
+
+/// Parse a number of hours, which has to be finite and not negative
+pub fn parse_hours(s: &str) -> Result<f64, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|hours| hours.is_finite() && *hours >= 0.0)
+        .ok_or_else(|| format!("Invalid hours '{s}'"))
+}
+
+impl FromStr for WeeklySchedule {
+    type Err = String;
+
+    /// Parse comma separated hours starting on Monday (e.g. `8,8,8,8,4`)
+    ///
+    /// Days left out at the end of the week are days off.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hours = s
+            .split(',')
+            .map(|part| parse_hours(part.trim()).map_err(|e| format!("{e} in schedule")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if hours.len() > 7 {
+            return Err("A schedule has at most 7 days (Monday to Sunday)".to_string());
+        }
+
+        let day = |index: usize| hours.get(index).copied().unwrap_or(0.0);
+        Ok(Self {
+            monday: day(0),
+            tuesday: day(1),
+            wednesday: day(2),
+            thursday: day(3),
+            friday: day(4),
+            saturday: day(5),
+            sunday: day(6),
+        })
+    }
+}
+
+/// Work schedules over time: contract periods falling back to the configured
+/// schedule for dates before the first period
+#[derive(Debug, Clone)]
+pub struct ScheduleHistory {
+    default: WeeklySchedule,
+    contracts: Vec<ContractPeriod>,
+}
+
+impl ScheduleHistory {
+    pub fn new(default: WeeklySchedule, mut contracts: Vec<ContractPeriod>) -> Self {
+        contracts.sort_by_key(|contract| contract.valid_from);
+        Self { default, contracts }
+    }
+
+    /// Get the contract period valid on the given date, if any
+    pub fn contract_on(&self, date: NaiveDate) -> Option<&ContractPeriod> {
+        self.contracts
+            .iter()
+            .rev()
+            .find(|contract| contract.valid_from <= date)
+    }
+
+    /// Get the schedule valid on the given date
+    pub fn schedule_on(&self, date: NaiveDate) -> WeeklySchedule {
+        self.contract_on(date)
+            .map(|contract| contract.schedule)
+            .unwrap_or(self.default)
+    }
+
+    /// Get the expected work time for the given date
+    pub fn daily_target(&self, date: NaiveDate) -> Duration {
+        self.schedule_on(date).daily_target(date.weekday())
+    }
+
+    /// Get the expected work time of a week, honoring a contract change within it
+    pub fn weekly_target(&self, week: &Week) -> Duration {
+        week.start
+            .iter_days()
+            .take(7)
+            .map(|date| self.daily_target(date))
+            .fold(Duration::zero(), |acc, target| acc + target)
+    }
+}
+
+/// Hour thresholds used to color a day's work time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyThresholds {
+    pub low: f64,
+    pub medium: f64,
+    pub good: f64,
+}
+
+impl DailyThresholds {
+    /// Scale thresholds describing a full day of `good` hours to a day's target
+    ///
+    /// A 4 hour day is colored like half of a full day. Days without a target
+    /// count as good as soon as any work has been done.
+    pub fn scaled_to(&self, target: Duration) -> Self {
+        if self.good <= 0.0 {
+            return *self;
+        }
+
+        let factor = (target.num_minutes() as f64 / 60.0) / self.good;
+        Self {
+            low: self.low * factor,
+            medium: self.medium * factor,
+            good: self.good * factor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use ulid::Ulid;
+
+    fn part_time_schedule() -> WeeklySchedule {
+        WeeklySchedule {
+            monday: 8.0,
+            tuesday: 8.0,
+            wednesday: 8.0,
+            thursday: 8.0,
+            friday: 4.0,
+            saturday: 0.0,
+            sunday: 0.0,
+        }
+    }
+
+    #[test_case(Weekday::Mon, 8.0; "monday")]
+    #[test_case(Weekday::Fri, 4.0; "friday")]
+    #[test_case(Weekday::Sun, 0.0; "sunday")]
+    fn test_daily_target(weekday: Weekday, expected_hours: f64) {
+        let schedule = part_time_schedule();
+        assert_eq!(
+            schedule.daily_target(weekday),
+            hours_to_duration(expected_hours)
+        );
+    }
+
+    #[test]
+    fn test_weekly_hours_are_derived_from_schedule() {
+        assert_eq!(part_time_schedule().weekly_hours(), 36.0);
+    }
+
+    #[test]
+    fn test_spread_over_weekdays() {
+        let schedule = WeeklySchedule::spread_over_weekdays(37.5);
+
+        assert_eq!(schedule.daily_target(Weekday::Mon), Duration::minutes(450));
+        assert_eq!(schedule.daily_target(Weekday::Fri), Duration::minutes(450));
+        assert_eq!(schedule.daily_target(Weekday::Sat), Duration::zero());
+        assert_eq!(schedule.weekly_hours(), 37.5);
+    }
+
+    #[test_case(8, 0.0, 4.0, 8.0; "full day")]
+    #[test_case(4, 0.0, 2.0, 4.0; "half day")]
+    #[test_case(0, 0.0, 0.0, 0.0; "day off")]
+    fn test_thresholds_scaled_to_target(target_hours: i64, low: f64, medium: f64, good: f64) {
+        let thresholds = DailyThresholds {
+            low: 0.0,
+            medium: 4.0,
+            good: 8.0,
+        };
+
+        assert_eq!(
+            thresholds.scaled_to(Duration::hours(target_hours)),
+            DailyThresholds { low, medium, good }
+        );
+    }
+
+    #[test_case("8,8,8,8,4", part_time_schedule(); "weekdays only")]
+    #[test_case("8, 8, 8, 8, 4, 0, 0", part_time_schedule(); "full week with spaces")]
+    fn test_parse_schedule(input: &str, expected: WeeklySchedule) {
+        assert_eq!(input.parse::<WeeklySchedule>().unwrap(), expected);
+    }
+
+    #[test_case("8,8,x"; "not a number")]
+    #[test_case("8,-1"; "negative")]
+    #[test_case("8,inf"; "infinite")]
+    #[test_case("8,NaN"; "not a number value")]
+    #[test_case("1,1,1,1,1,1,1,1"; "too many days")]
+    fn test_parse_invalid_schedule(input: &str) {
+        assert!(input.parse::<WeeklySchedule>().is_err());
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn history() -> ScheduleHistory {
+        let contract = |valid_from: &str, schedule: WeeklySchedule| ContractPeriod {
+            id: Ulid::new(),
+            valid_from: date(valid_from),
+            schedule,
+            note: None,
+        };
+
+        ScheduleHistory::new(
+            WeeklySchedule::spread_over_weekdays(20.0),
+            vec![
+                // 2025-03-06 is a Thursday
+                contract("2025-03-06", WeeklySchedule::spread_over_weekdays(30.0)),
+                contract("2024-01-01", WeeklySchedule::spread_over_weekdays(40.0)),
+            ],
+        )
+    }
+
+    #[test_case("2023-12-29", 4; "before first contract uses default")]
+    #[test_case("2024-01-01", 8; "first day of first contract")]
+    #[test_case("2025-03-07", 6; "second contract")]
+    #[test_case("2025-03-08", 0; "weekend")]
+    fn test_history_daily_target(on: &str, expected_hours: i64) {
+        assert_eq!(
+            history().daily_target(date(on)),
+            Duration::hours(expected_hours)
+        );
+    }
+
+    #[test]
+    fn test_history_weekly_target_spans_contract_change() {
+        let history = history();
+
+        // Mon-Wed under the 40h contract, Thu-Fri under the 30h one
+        let week = Week::containing(date("2025-03-03"));
+        assert_eq!(history.weekly_target(&week), Duration::hours(36));
+
+        let next_week = Week::containing(date("2025-03-10"));
+        assert_eq!(history.weekly_target(&next_week), Duration::hours(30));
+        let first_week = Week::containing(date("2024-01-01"));
+        assert_eq!(history.weekly_target(&first_week), Duration::hours(40));
+    }
+}
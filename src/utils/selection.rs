@@ -53,3 +53,10 @@ impl<T> SelectionMenu<T> {
         Ok(selection.value)
     }
 }
+
+/// Ask a yes/no question, defaulting to no
+pub fn confirm(prompt: impl AsRef<str>) -> Result<bool, inquire::InquireError> {
+    inquire::Confirm::new(prompt.as_ref())
+        .with_default(false)
+        .prompt()
+}
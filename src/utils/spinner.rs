@@ -22,6 +22,7 @@ impl Default for SpinnerConfig {
     }
 }
 
+#[allow(dead_code)]
 impl SpinnerConfig {
     pub fn with_message(mut self, message: impl Into<String>) -> Self {
         self.message = message.into();
@@ -40,11 +41,13 @@ impl SpinnerConfig {
 }
 
 pub struct SpinnerGuard {
+    #[allow(dead_code)]
     spinner: Arc<Mutex<Option<Spinner>>>,
     sender: Option<mpsc::Sender<SpinnerMessage>>,
     thread_handle: Option<thread::JoinHandle<()>>,
 }
 
+#[allow(dead_code)]
 enum SpinnerMessage {
     Stop,
     StopWithMessage(String),
@@ -92,6 +95,7 @@ impl SpinnerGuard {
         }
     }
 
+    #[allow(dead_code)]
     pub fn finish(mut self, message: Option<String>) {
         self.send_message(match message {
             Some(msg) => SpinnerMessage::StopWithMessage(msg),
@@ -104,6 +108,7 @@ impl SpinnerGuard {
         }
     }
 
+    #[allow(dead_code)]
     pub fn finish_with_symbol(mut self, symbol: String, message: Option<String>) {
         self.send_message(SpinnerMessage::StopWithSymbol(symbol, message));
 
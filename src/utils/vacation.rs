@@ -0,0 +1,250 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A day of the year without a year, like the March 31 carry-over deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonthDay {
+    pub month: u32,
+    pub day: u32,
+}
+
+impl MonthDay {
+    /// Get this day in the given year, using February 28 for February 29 in common years
+    pub fn in_year(&self, year: i32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, self.month, self.day)
+            .or_else(|| NaiveDate::from_ymd_opt(year, self.month, self.day - 1))
+            .expect("month and day are validated when parsing")
+    }
+}
+
+impl FromStr for MonthDay {
+    type Err = String;
+
+    /// Parse a day given as `MM-DD` (e.g. `03-31`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid day '{}'. Use MM-DD (e.g. 03-31)", s);
+
+        let (month, day) = s.split_once('-').ok_or_else(invalid)?;
+        let month = month.parse::<u32>().map_err(|_| invalid())?;
+        let day = day.parse::<u32>().map_err(|_| invalid())?;
+
+        // Validate against a leap year, so February 29 is accepted
+        NaiveDate::from_ymd_opt(2000, month, day)
+            .map(|_| Self { month, day })
+            .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for MonthDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.month, self.day)
+    }
+}
+
+impl Serialize for MonthDay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for MonthDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// How many vacation days there are and how long unused ones are kept
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VacationPolicy {
+    pub days_per_year: f64,
+    /// Days carried over into the first tracked year
+    pub initial_carry_over: f64,
+    /// Day carried over days expire in the following year, if at all
+    pub carry_over_expiry: Option<MonthDay>,
+}
+
+/// Vacation account of a single calendar year, all values in days
+#[derive(Debug, Clone, PartialEq)]
+pub struct VacationYear {
+    pub year: i32,
+    pub entitlement: f64,
+    pub carry_over: f64,
+    /// Vacation up to and including today
+    pub taken: f64,
+    /// Vacation booked after today
+    pub planned: f64,
+    /// Carry-over lost because it wasn't used before the expiry date
+    pub expired: f64,
+    /// Carry-over that will be lost unless more vacation is booked before the expiry date
+    pub expiring: f64,
+    pub carry_over_expires: Option<NaiveDate>,
+}
+
+impl VacationYear {
+    pub fn remaining(&self) -> f64 {
+        self.entitlement + self.carry_over - self.taken - self.planned - self.expired
+    }
+}
+
+impl VacationPolicy {
+    /// Calculate the vacation accounts from the first to the last year
+    ///
+    /// `vacation` holds the vacation days booked per date, with half days
+    /// already counted proportionally. Vacation in a year uses up the
+    /// carry-over first, so only carry-over exceeding the vacation booked
+    /// until the expiry date is lost. Remaining days are carried over into
+    /// the following year, overdrawn days are not.
+    pub fn years(
+        &self,
+        first_year: i32,
+        last_year: i32,
+        vacation: &[(NaiveDate, f64)],
+        today: NaiveDate,
+    ) -> Vec<VacationYear> {
+        let mut years: Vec<VacationYear> = Vec::new();
+
+        for year in first_year..=last_year {
+            let carry_over = match years.last() {
+                Some(previous) => previous.remaining().max(0.0),
+                None => self.initial_carry_over,
+            };
+
+            let in_year = vacation.iter().filter(|(date, _)| date.year() == year);
+            let taken = sum_days(in_year.clone().filter(|(date, _)| *date <= today));
+            let planned = sum_days(in_year.clone().filter(|(date, _)| *date > today));
+
+            let carry_over_expires = self.carry_over_expiry.map(|expiry| expiry.in_year(year));
+            let (expired, expiring) = match carry_over_expires {
+                Some(expires) => {
+                    let used_before_expiry = sum_days(in_year.filter(|(date, _)| *date <= expires));
+                    let unused = (carry_over - used_before_expiry).max(0.0);
+                    if expires < today {
+                        (unused, 0.0)
+                    } else {
+                        (0.0, unused)
+                    }
+                }
+                None => (0.0, 0.0),
+            };
+
+            years.push(VacationYear {
+                year,
+                entitlement: self.days_per_year,
+                carry_over,
+                taken,
+                planned,
+                expired,
+                expiring,
+                carry_over_expires,
+            });
+        }
+
+        years
+    }
+}
+
+/// Add up vacation days, starting from 0.0 so an empty year isn't shown as -0
+fn sum_days<'a>(vacation: impl Iterator<Item = &'a (NaiveDate, f64)>) -> f64 {
+    vacation.fold(0.0, |sum, (_, days)| sum + days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn policy() -> VacationPolicy {
+        VacationPolicy {
+            days_per_year: 30.0,
+            initial_carry_over: 5.0,
+            carry_over_expiry: Some("03-31".parse().unwrap()),
+        }
+    }
+
+    #[test_case("03-31", 3, 31; "end of march")]
+    #[test_case("02-29", 2, 29; "leap day")]
+    fn test_month_day_parse(input: &str, month: u32, day: u32) {
+        let parsed = input.parse::<MonthDay>().unwrap();
+        assert_eq!(parsed, MonthDay { month, day });
+        assert_eq!(parsed.to_string(), input);
+    }
+
+    #[test_case("3/31"; "wrong separator")]
+    #[test_case("02-30"; "no such day")]
+    #[test_case("13-01"; "no such month")]
+    fn test_month_day_parse_invalid(input: &str) {
+        assert!(input.parse::<MonthDay>().is_err());
+    }
+
+    #[test]
+    fn test_month_day_leap_day_in_common_year() {
+        let leap_day = MonthDay { month: 2, day: 29 };
+        assert_eq!(leap_day.in_year(2025), date("2025-02-28"));
+        assert_eq!(leap_day.in_year(2024), date("2024-02-29"));
+    }
+
+    #[test]
+    fn test_taken_and_planned() {
+        let vacation = vec![
+            (date("2025-02-03"), 1.0),
+            (date("2025-02-04"), 0.5),
+            (date("2025-08-04"), 1.0),
+        ];
+        let years = policy().years(2025, 2025, &vacation, date("2025-06-01"));
+
+        assert_eq!(years[0].taken, 1.5);
+        assert_eq!(years[0].planned, 1.0);
+        // 3.5 of the 5 carried over days are lost after March
+        assert_eq!(years[0].expired, 3.5);
+        assert_eq!(years[0].remaining(), 30.0 + 5.0 - 1.5 - 1.0 - 3.5);
+    }
+
+    #[test]
+    fn test_carry_over_expiring_before_deadline() {
+        let vacation = vec![(date("2025-03-10"), 2.0), (date("2025-04-07"), 1.0)];
+        let years = policy().years(2025, 2025, &vacation, date("2025-01-15"));
+
+        assert_eq!(years[0].expired, 0.0);
+        assert_eq!(years[0].expiring, 3.0);
+        assert_eq!(years[0].carry_over_expires, Some(date("2025-03-31")));
+    }
+
+    #[test]
+    fn test_remaining_days_carry_over() {
+        let vacation = vec![(date("2024-03-01"), 10.0), (date("2025-02-03"), 1.0)];
+        let years = policy().years(2024, 2025, &vacation, date("2025-06-01"));
+
+        assert_eq!(years[0].remaining(), 25.0);
+        assert_eq!(years[1].carry_over, 25.0);
+        assert_eq!(years[1].expired, 24.0);
+        assert_eq!(years[1].remaining(), 30.0);
+    }
+
+    #[test]
+    fn test_overdrawn_days_are_not_carried_over() {
+        let vacation = vec![(date("2024-07-01"), 40.0)];
+        let years = policy().years(2024, 2025, &vacation, date("2025-06-01"));
+
+        assert_eq!(years[0].remaining(), -10.0);
+        assert_eq!(years[1].carry_over, 0.0);
+    }
+
+    #[test]
+    fn test_carry_over_without_expiry() {
+        let policy = VacationPolicy {
+            carry_over_expiry: None,
+            ..policy()
+        };
+        let years = policy.years(2025, 2025, &[], date("2025-12-31"));
+
+        assert_eq!(years[0].expired, 0.0);
+        assert_eq!(years[0].remaining(), 35.0);
+    }
+}
@@ -1,7 +1,11 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use ulid::Ulid;
 
+use crate::utils::date::DateRange;
 use crate::utils::editor::EditableDocument;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -11,6 +15,9 @@ pub struct AbsenceRecord {
     pub hours: f64,
     pub absence_type: AbsenceType,
     pub note: Option<String>,
+    /// UID of the calendar event the absence was imported from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ical_uid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -22,11 +29,70 @@ pub enum AbsenceType {
     Other(String),
 }
 
+impl AbsenceType {
+    /// Get the name shown to humans, e.g. `Overtime Reduction`
+    pub fn label(&self) -> String {
+        match self {
+            AbsenceType::Vacation => "Vacation".to_string(),
+            AbsenceType::Sick => "Sick".to_string(),
+            AbsenceType::OvertimeReduction => "Overtime Reduction".to_string(),
+            AbsenceType::Holiday => "Holiday".to_string(),
+            AbsenceType::Other(custom) => format!("Other: {}", custom),
+        }
+    }
+}
+
+/// Spelled like on the command line, e.g. `vacation` or `other:custom`
+impl fmt::Display for AbsenceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbsenceType::Vacation => write!(f, "vacation"),
+            AbsenceType::Sick => write!(f, "sick"),
+            AbsenceType::OvertimeReduction => write!(f, "overtime-reduction"),
+            AbsenceType::Holiday => write!(f, "holiday"),
+            AbsenceType::Other(custom) => write!(f, "other:{}", custom),
+        }
+    }
+}
+
+impl FromStr for AbsenceType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "vacation" => Ok(AbsenceType::Vacation),
+            "sick" => Ok(AbsenceType::Sick),
+            "overtime-reduction" => Ok(AbsenceType::OvertimeReduction),
+            "holiday" => Ok(AbsenceType::Holiday),
+            _ => {
+                if let Some(custom) = s.strip_prefix("other:") {
+                    Ok(AbsenceType::Other(custom.to_string()))
+                } else {
+                    Err("Invalid absence type. Use: vacation, sick, overtime-reduction, holiday, or other:custom".to_string())
+                }
+            }
+        }
+    }
+}
+
+/// Group absences by their date, keeping their order within a day
+pub fn group_by_date(records: Vec<AbsenceRecord>) -> HashMap<NaiveDate, Vec<AbsenceRecord>> {
+    let mut by_date: HashMap<NaiveDate, Vec<AbsenceRecord>> = HashMap::new();
+    for record in records {
+        by_date.entry(record.date).or_default().push(record);
+    }
+    by_date
+}
+
 pub trait AbsenceStorage {
     type Error;
 
     fn add_absence(&self, record: AbsenceRecord) -> Result<(), Self::Error>;
     fn get_absence(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, Self::Error>;
+    /// Get all absences within a range, ordered by date
+    fn get_absences(&self, range: &DateRange) -> Result<Vec<AbsenceRecord>, Self::Error>;
+    /// Get every stored absence, ordered by date
+    fn all_absences(&self) -> Result<Vec<AbsenceRecord>, Self::Error>;
     fn remove_absence(&self, date: NaiveDate, id: Ulid) -> Result<bool, Self::Error>;
     fn update_absence(
         &self,
@@ -75,6 +141,7 @@ mod tests {
             hours,
             absence_type,
             note,
+            ical_uid: None,
         };
 
         let json = serde_json::to_string_pretty(&record).unwrap();
@@ -96,4 +163,19 @@ mod tests {
             ),
         }
     }
+
+    #[test_case(AbsenceType::Vacation, "vacation"; "vacation")]
+    #[test_case(AbsenceType::Sick, "sick"; "sick")]
+    #[test_case(AbsenceType::OvertimeReduction, "overtime-reduction"; "overtime reduction")]
+    #[test_case(AbsenceType::Holiday, "holiday"; "holiday")]
+    #[test_case(AbsenceType::Other("Moving".to_string()), "other:Moving"; "other")]
+    fn test_absence_type_round_trip(absence_type: AbsenceType, spelled: &str) {
+        assert_eq!(absence_type.to_string(), spelled);
+        assert_eq!(spelled.parse::<AbsenceType>().unwrap(), absence_type);
+    }
+
+    #[test]
+    fn test_absence_type_invalid() {
+        assert!("party".parse::<AbsenceType>().is_err());
+    }
 }
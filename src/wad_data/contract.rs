@@ -0,0 +1,43 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::utils::schedule::WeeklySchedule;
+
+/// A contract period: the work schedule valid from a given date until the
+/// next period starts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContractPeriod {
+    pub id: Ulid,
+    pub valid_from: NaiveDate,
+    pub schedule: WeeklySchedule,
+    pub note: Option<String>,
+}
+
+pub trait ContractStorage {
+    type Error;
+
+    fn add_contract(&self, contract: ContractPeriod) -> Result<(), Self::Error>;
+    /// Get all contract periods sorted by the date they become valid
+    fn list_contracts(&self) -> Result<Vec<ContractPeriod>, Self::Error>;
+    fn remove_contract(&self, id: Ulid) -> Result<bool, Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_period_serialization() {
+        let contract = ContractPeriod {
+            id: Ulid::new(),
+            valid_from: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            schedule: WeeklySchedule::spread_over_weekdays(32.0),
+            note: Some("Part-time".to_string()),
+        };
+
+        let json = serde_json::to_string_pretty(&contract).unwrap();
+        let deserialized: ContractPeriod = serde_json::from_str(&json).unwrap();
+        assert_eq!(contract, deserialized);
+    }
+}
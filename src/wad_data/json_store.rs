@@ -1,11 +1,18 @@
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tempfile::Builder;
 use thiserror::Error;
 use ulid::Ulid;
 
-use super::{AbsenceRecord, AbsenceStorage, WadDataStore};
+use super::schema::{self, SchemaError};
+use super::{
+    AbsenceRecord, AbsenceStorage, ContractPeriod, ContractStorage, WadDataStore, default_data_dir,
+};
+use crate::utils::date::DateRange;
 
 #[derive(Error, Debug)]
 pub enum JsonDataStoreError {
@@ -17,17 +24,200 @@ pub enum JsonDataStoreError {
     Serialization(#[from] serde_json::Error),
     #[error("Absence record with ID {id} not found on {date}")]
     RecordNotFound { id: Ulid, date: NaiveDate },
+    #[error("A contract period starting on {0} already exists")]
+    DuplicateContract(NaiveDate),
+    #[error("A different absence record with ID {id} already exists on {date}")]
+    ConflictingRecord { id: Ulid, date: NaiveDate },
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+}
+
+/// An absence being moved to another day
+///
+/// Written before the move starts and removed once it's done, so a move
+/// interrupted in between is finished the next time the store is locked.
+#[derive(Debug, Serialize, Deserialize)]
+struct MoveJournal {
+    from: NaiveDate,
+    record: AbsenceRecord,
+}
+
+/// Held while changing the data directory, released when dropped
+struct DataDirLock {
+    _file: File,
 }
 
 pub struct JsonDataStore {
     data_dir: PathBuf,
 }
 
+/// Problems with the files of the store, as found by [`JsonDataStore::check_files`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileIssue {
+    /// A file that can't be read, so its day (or the contract periods) can't be loaded
+    Unreadable { path: PathBuf, error: String },
+    /// A record in a file that isn't the one for its date, so it's missing on that day
+    Misplaced {
+        path: PathBuf,
+        record: AbsenceRecord,
+    },
+    /// A record stored several times in the same file
+    Duplicated {
+        path: PathBuf,
+        record: AbsenceRecord,
+        copies: usize,
+    },
+    /// A temporary file left behind by an interrupted write
+    LeftoverTemporary { path: PathBuf },
+    /// A data directory in an older layout, migrated whenever the store is opened
+    PendingMigration { from: u32, to: u32 },
+    /// The journal of a move between days that was interrupted, finished
+    /// whenever the store is locked
+    UnfinishedMove { path: PathBuf },
+}
+
+impl FileIssue {
+    /// Check whether [`JsonDataStore::repair`] can resolve the issue without losing data
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, FileIssue::Unreadable { .. })
+    }
+}
+
+/// The outcome of [`JsonDataStore::check_files`]
+#[derive(Debug, Default)]
+pub struct FileCheck {
+    /// Every absence that could be read, wherever it's stored
+    pub records: Vec<AbsenceRecord>,
+    pub issues: Vec<FileIssue>,
+}
+
+const TEMPORARY_FILE_PREFIX: &str = ".tmp";
+
+/// Replace a file with new content without ever leaving it half written
+///
+/// The content goes to a temporary file in the same directory first, which
+/// is then renamed over the target. Temporary files don't end in `.json`, so
+/// leftovers of a crash are ignored when reading.
+pub(super) fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut file = Builder::new()
+        .prefix(TEMPORARY_FILE_PREFIX)
+        .tempfile_in(dir)?;
+    file.write_all(content.as_bytes())?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
 impl JsonDataStore {
+    /// Open the store kept in the given directory, creating it if missing
+    ///
+    /// An outdated layout is migrated and a move between days left
+    /// unfinished by a crash is completed here.
+    pub fn open_in(data_dir: &Path) -> Result<Self, JsonDataStoreError> {
+        let store = Self::unlocked_in(data_dir)?;
+        store.migrate()?;
+        Ok(store)
+    }
+
+    /// Open the store kept in the given directory without changing its data
+    ///
+    /// Unlike [`Self::open_in`], an outdated layout and an unfinished move are
+    /// left as they are, so [`Self::check_files`] can report them.
+    pub fn inspect_in(data_dir: &Path) -> Result<Self, JsonDataStoreError> {
+        Self::unlocked_in(data_dir)
+    }
+
+    fn unlocked_in(data_dir: &Path) -> Result<Self, JsonDataStoreError> {
+        fs::create_dir_all(data_dir)?;
+
+        Ok(Self {
+            data_dir: data_dir.to_path_buf(),
+        })
+    }
+
+    /// Migrate the data directory to the current layout, like opening it does
+    ///
+    /// Returns the path of the backup taken if anything was migrated.
+    pub fn migrate_in(data_dir: &Path) -> Result<Option<PathBuf>, JsonDataStoreError> {
+        Self::unlocked_in(data_dir)?.migrate()
+    }
+
+    fn migrate(&self) -> Result<Option<PathBuf>, JsonDataStoreError> {
+        // The journal is written in the current layout, so it's applied after migrating
+        let _lock = self.lock_without_recovery()?;
+        let backup = schema::upgrade(&self.data_dir)?;
+        self.recover()?;
+        Ok(backup)
+    }
+
     pub fn absences_dir(&self) -> PathBuf {
         self.data_dir.join("absences")
     }
 
+    pub fn contracts_file_path(&self) -> PathBuf {
+        self.data_dir.join("contracts.json")
+    }
+
+    fn lock_file_path(&self) -> PathBuf {
+        self.data_dir.join(".lock")
+    }
+
+    fn journal_file_path(&self) -> PathBuf {
+        self.data_dir.join("journal.json")
+    }
+
+    /// Take the advisory lock on the data directory, waiting for other `wad`
+    /// processes to finish their changes
+    ///
+    /// Every change holds the lock from reading a file until writing it back,
+    /// so concurrent changes to the same day can't overwrite each other.
+    fn lock(&self) -> Result<DataDirLock, JsonDataStoreError> {
+        let lock = self.lock_without_recovery()?;
+        self.recover()?;
+        Ok(lock)
+    }
+
+    fn lock_without_recovery(&self) -> Result<DataDirLock, JsonDataStoreError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.lock_file_path())?;
+        file.lock()?;
+        Ok(DataDirLock { _file: file })
+    }
+
+    /// Finish a move between days that was interrupted
+    fn recover(&self) -> Result<(), JsonDataStoreError> {
+        let journal_path = self.journal_file_path();
+        if !journal_path.exists() {
+            return Ok(());
+        }
+
+        let journal: MoveJournal = serde_json::from_str(&fs::read_to_string(&journal_path)?)?;
+        self.apply_move(&journal)?;
+        fs::remove_file(journal_path)?;
+        Ok(())
+    }
+
+    /// Move an absence to its new day
+    ///
+    /// Each step can be repeated, so a partly applied move can simply be
+    /// applied again. The record is added before it's removed, so it's never
+    /// lost in between.
+    fn apply_move(&self, journal: &MoveJournal) -> Result<(), JsonDataStoreError> {
+        let new_date = journal.record.date;
+        let mut records = self.load_absence_file(new_date)?;
+        records.retain(|record| record.id != journal.record.id);
+        records.push(journal.record.clone());
+        records.sort_by_key(|r| r.id);
+        self.save_absence_file(new_date, &records)?;
+
+        self.delete_absence(journal.from, journal.record.id)?;
+        Ok(())
+    }
+
     fn year_dir(&self, year: i32) -> PathBuf {
         self.absences_dir().join(year.to_string())
     }
@@ -38,29 +228,68 @@ impl JsonDataStore {
         year_dir.join(filename)
     }
 
-    fn load_absence_file(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, JsonDataStoreError> {
-        let file_path = self.absence_file_path(date);
-
-        if !file_path.exists() {
+    /// Get the dates with an absence file in a year, in no particular order
+    fn absence_dates_in_year(&self, year: i32) -> Result<Vec<NaiveDate>, JsonDataStoreError> {
+        let year_dir = self.year_dir(year);
+        if !year_dir.exists() {
             return Ok(Vec::new());
         }
 
-        let content = fs::read_to_string(file_path)?;
-        let records: Vec<AbsenceRecord> = serde_json::from_str(&content)?;
+        let mut dates = Vec::new();
+        for entry in fs::read_dir(year_dir)? {
+            let path = entry?.path();
+            // Files not named like an absence file are ignored
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && let Some(date) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok())
+            {
+                dates.push(date);
+            }
+        }
+        Ok(dates)
+    }
+
+    /// Load the absences of the given dates, in the order of the dates
+    fn load_absences(&self, dates: &[NaiveDate]) -> Result<Vec<AbsenceRecord>, JsonDataStoreError> {
+        let mut records = Vec::new();
+        for date in dates {
+            records.extend(self.get_absence(*date)?);
+        }
         Ok(records)
     }
 
+    fn load_absence_file(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, JsonDataStoreError> {
+        Self::read_records(&self.absence_file_path(date))
+    }
+
     fn save_absence_file(
         &self,
         date: NaiveDate,
         records: &[AbsenceRecord],
     ) -> Result<(), JsonDataStoreError> {
-        let year_dir = self.year_dir(date.year());
-        fs::create_dir_all(&year_dir)?;
+        Self::write_records(&self.absence_file_path(date), records)
+    }
 
-        let file_path = self.absence_file_path(date);
+    fn read_records(path: &Path) -> Result<Vec<AbsenceRecord>, JsonDataStoreError> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(path)?;
+        let records: Vec<AbsenceRecord> = serde_json::from_str(&content)?;
+        Ok(records)
+    }
+
+    fn write_records(path: &Path, records: &[AbsenceRecord]) -> Result<(), JsonDataStoreError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
         let content = serde_json::to_string_pretty(records)?;
-        fs::write(file_path, content)?;
+        write_atomically(path, &content)?;
         Ok(())
     }
 
@@ -74,27 +303,28 @@ impl JsonDataStore {
             Ok(false)
         }
     }
-}
 
-impl WadDataStore for JsonDataStore {
-    fn open() -> Result<Self, Self::Error>
-    where
-        Self: Sized,
-    {
-        let data_dir = dirs::data_dir()
-            .ok_or(JsonDataStoreError::NoDataDir)?
-            .join("wad");
+    fn load_contracts_file(&self) -> Result<Vec<ContractPeriod>, JsonDataStoreError> {
+        let file_path = self.contracts_file_path();
 
-        fs::create_dir_all(&data_dir)?;
+        if !file_path.exists() {
+            return Ok(Vec::new());
+        }
 
-        Ok(Self { data_dir })
+        let content = fs::read_to_string(file_path)?;
+        let contracts: Vec<ContractPeriod> = serde_json::from_str(&content)?;
+        Ok(contracts)
     }
-}
 
-impl AbsenceStorage for JsonDataStore {
-    type Error = JsonDataStoreError;
+    fn save_contracts_file(&self, contracts: &[ContractPeriod]) -> Result<(), JsonDataStoreError> {
+        fs::create_dir_all(&self.data_dir)?;
 
-    fn add_absence(&self, record: AbsenceRecord) -> Result<(), Self::Error> {
+        let content = serde_json::to_string_pretty(contracts)?;
+        write_atomically(&self.contracts_file_path(), &content)?;
+        Ok(())
+    }
+
+    fn insert_absence(&self, record: AbsenceRecord) -> Result<(), JsonDataStoreError> {
         let date = record.date;
         let mut records = self.load_absence_file(date)?;
         records.push(record);
@@ -103,14 +333,7 @@ impl AbsenceStorage for JsonDataStore {
         self.save_absence_file(date, &records)
     }
 
-    fn get_absence(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, Self::Error> {
-        let mut records = self.load_absence_file(date)?;
-        // Sort by ULID to maintain chronological order
-        records.sort_by_key(|r| r.id);
-        Ok(records)
-    }
-
-    fn remove_absence(&self, date: NaiveDate, id: Ulid) -> Result<bool, Self::Error> {
+    fn delete_absence(&self, date: NaiveDate, id: Ulid) -> Result<bool, JsonDataStoreError> {
         let mut records = self.load_absence_file(date)?;
         let original_len = records.len();
 
@@ -126,6 +349,243 @@ impl AbsenceStorage for JsonDataStore {
         }
     }
 
+    /// Look through the data directory for files the store can't use as they are
+    ///
+    /// Records are only searched for in the year directories below
+    /// `absences`, as nothing else is ever read.
+    pub fn check_files(&self) -> Result<FileCheck, JsonDataStoreError> {
+        let mut check = FileCheck::default();
+        // Listed first, as the other repairs expect the current layout
+        if let Some(first) = schema::pending_migrations(&self.data_dir)?.first() {
+            check.issues.push(FileIssue::PendingMigration {
+                from: first.from,
+                to: schema::CURRENT_VERSION,
+            });
+        }
+        if self.journal_file_path().exists() {
+            check.issues.push(FileIssue::UnfinishedMove {
+                path: self.journal_file_path(),
+            });
+        }
+        Self::check_temporary_files(&self.data_dir, &mut check.issues)?;
+        Self::check_temporary_files(&self.absences_dir(), &mut check.issues)?;
+
+        if let Err(e) = self.load_contracts_file() {
+            check.issues.push(FileIssue::Unreadable {
+                path: self.contracts_file_path(),
+                error: e.to_string(),
+            });
+        }
+
+        for year_dir in Self::sorted_entries(&self.absences_dir())? {
+            let is_year = year_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.parse::<i32>().is_ok());
+            if !is_year || !year_dir.is_dir() {
+                continue;
+            }
+
+            Self::check_temporary_files(&year_dir, &mut check.issues)?;
+            for path in Self::sorted_entries(&year_dir)? {
+                if path.extension().is_none_or(|extension| extension != "json") {
+                    continue;
+                }
+                match Self::read_records(&path) {
+                    Ok(records) => {
+                        self.check_records(&path, &records, &mut check.issues);
+                        check.records.extend(records);
+                    }
+                    Err(e) => check.issues.push(FileIssue::Unreadable {
+                        path,
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        }
+
+        Ok(check)
+    }
+
+    fn check_records(&self, path: &Path, records: &[AbsenceRecord], issues: &mut Vec<FileIssue>) {
+        for (index, record) in records.iter().enumerate() {
+            // Later copies of a record are covered by the first one
+            if records[..index].contains(record) {
+                continue;
+            }
+
+            let copies = records.iter().filter(|other| *other == record).count();
+            if copies > 1 {
+                issues.push(FileIssue::Duplicated {
+                    path: path.to_path_buf(),
+                    record: record.clone(),
+                    copies,
+                });
+            }
+            if self.absence_file_path(record.date) != path {
+                issues.push(FileIssue::Misplaced {
+                    path: path.to_path_buf(),
+                    record: record.clone(),
+                });
+            }
+        }
+    }
+
+    fn check_temporary_files(dir: &Path, issues: &mut Vec<FileIssue>) -> io::Result<()> {
+        for path in Self::sorted_entries(dir)? {
+            if path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(TEMPORARY_FILE_PREFIX))
+            {
+                issues.push(FileIssue::LeftoverTemporary { path });
+            }
+        }
+        Ok(())
+    }
+
+    fn sorted_entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Resolve an issue found by [`Self::check_files`]
+    ///
+    /// Unreadable files are left alone, they have to be fixed by hand.
+    /// Repairing an issue that's already gone does nothing.
+    pub fn repair(&self, issue: &FileIssue) -> Result<(), JsonDataStoreError> {
+        // Migrating takes the lock itself, as an unfinished move has to wait for it
+        if let FileIssue::PendingMigration { .. } = issue {
+            self.migrate()?;
+            return Ok(());
+        }
+        // Taking the lock finishes an unfinished move
+        let _lock = self.lock()?;
+
+        match issue {
+            FileIssue::Unreadable { .. }
+            | FileIssue::PendingMigration { .. }
+            | FileIssue::UnfinishedMove { .. } => {}
+            FileIssue::Misplaced { path, record } => {
+                let mut records = self.load_absence_file(record.date)?;
+                match records.iter().find(|existing| existing.id == record.id) {
+                    Some(existing) if existing != record => {
+                        return Err(JsonDataStoreError::ConflictingRecord {
+                            id: record.id,
+                            date: record.date,
+                        });
+                    }
+                    Some(_) => {}
+                    None => {
+                        records.push(record.clone());
+                        records.sort_by_key(|r| r.id);
+                        self.save_absence_file(record.date, &records)?;
+                    }
+                }
+
+                // Only removed once it's safely stored for its date
+                let mut remaining = Self::read_records(path)?;
+                remaining.retain(|existing| existing != record);
+                if !remaining.is_empty() {
+                    Self::write_records(path, &remaining)?;
+                } else if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+            FileIssue::Duplicated { path, record, .. } => {
+                let mut records = Self::read_records(path)?;
+                let mut kept = false;
+                records
+                    .retain(|existing| existing != record || !std::mem::replace(&mut kept, true));
+                if kept {
+                    Self::write_records(path, &records)?;
+                }
+            }
+            FileIssue::LeftoverTemporary { path } => {
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl WadDataStore for JsonDataStore {
+    fn open() -> Result<Self, JsonDataStoreError>
+    where
+        Self: Sized,
+    {
+        Self::open_in(&default_data_dir().ok_or(JsonDataStoreError::NoDataDir)?)
+    }
+}
+
+impl AbsenceStorage for JsonDataStore {
+    type Error = JsonDataStoreError;
+
+    fn add_absence(&self, record: AbsenceRecord) -> Result<(), Self::Error> {
+        let _lock = self.lock()?;
+        self.insert_absence(record)
+    }
+
+    fn get_absence(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        let mut records = self.load_absence_file(date)?;
+        // Sort by ULID to maintain chronological order
+        records.sort_by_key(|r| r.id);
+        Ok(records)
+    }
+
+    fn get_absences(&self, range: &DateRange) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        // Only read the files that exist instead of probing every day of the range
+        let mut dates = Vec::new();
+        for year in range.from.year()..=range.to.year() {
+            dates.extend(
+                self.absence_dates_in_year(year)?
+                    .into_iter()
+                    .filter(|date| range.contains(*date)),
+            );
+        }
+        dates.sort();
+
+        self.load_absences(&dates)
+    }
+
+    fn all_absences(&self) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        let absences_dir = self.absences_dir();
+        if !absences_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut dates = Vec::new();
+        for entry in fs::read_dir(absences_dir)? {
+            // Directories not named like a year are ignored
+            if let Some(year) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<i32>().ok())
+            {
+                dates.extend(self.absence_dates_in_year(year)?);
+            }
+        }
+        dates.sort();
+
+        self.load_absences(&dates)
+    }
+
+    fn remove_absence(&self, date: NaiveDate, id: Ulid) -> Result<bool, Self::Error> {
+        let _lock = self.lock()?;
+        self.delete_absence(date, id)
+    }
+
     fn update_absence(
         &self,
         date: NaiveDate,
@@ -134,6 +594,7 @@ impl AbsenceStorage for JsonDataStore {
         let original_date = date;
         let new_date = updated_record.date;
         let record_id = updated_record.id;
+        let _lock = self.lock()?;
 
         if original_date == new_date {
             // Simple case: same date, update in place
@@ -159,30 +620,85 @@ impl AbsenceStorage for JsonDataStore {
             self.save_absence_file(original_date, &records)?;
         } else {
             // Complex case: date changed, move between files
-            // 1. Remove from original date
-            let removed = self.remove_absence(original_date, record_id)?;
-            if !removed {
+            if !self
+                .load_absence_file(original_date)?
+                .iter()
+                .any(|record| record.id == record_id)
+            {
                 return Err(JsonDataStoreError::RecordNotFound {
                     id: record_id,
                     date: original_date,
                 });
             }
 
-            // 2. Add to new date
-            self.add_absence(updated_record)?;
+            // Journal the move first, so it's finished later if it fails halfway
+            let journal = MoveJournal {
+                from: original_date,
+                record: updated_record,
+            };
+            write_atomically(
+                &self.journal_file_path(),
+                &serde_json::to_string_pretty(&journal)?,
+            )?;
+            self.apply_move(&journal)?;
+            fs::remove_file(self.journal_file_path())?;
         }
 
         Ok(())
     }
 }
 
+impl ContractStorage for JsonDataStore {
+    type Error = JsonDataStoreError;
+
+    fn add_contract(&self, contract: ContractPeriod) -> Result<(), Self::Error> {
+        let _lock = self.lock()?;
+        let mut contracts = self.load_contracts_file()?;
+
+        // Two periods starting on the same day would make the schedule ambiguous
+        if contracts
+            .iter()
+            .any(|existing| existing.valid_from == contract.valid_from)
+        {
+            return Err(JsonDataStoreError::DuplicateContract(contract.valid_from));
+        }
+
+        contracts.push(contract);
+        contracts.sort_by_key(|c| c.valid_from);
+        self.save_contracts_file(&contracts)
+    }
+
+    fn list_contracts(&self) -> Result<Vec<ContractPeriod>, Self::Error> {
+        let mut contracts = self.load_contracts_file()?;
+        contracts.sort_by_key(|c| c.valid_from);
+        Ok(contracts)
+    }
+
+    fn remove_contract(&self, id: Ulid) -> Result<bool, Self::Error> {
+        let _lock = self.lock()?;
+        let mut contracts = self.load_contracts_file()?;
+        let original_len = contracts.len();
+
+        contracts.retain(|contract| contract.id != id);
+
+        if contracts.len() == original_len {
+            return Ok(false);
+        }
+
+        self.save_contracts_file(&contracts)?;
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::NaiveDate;
     use tempfile::TempDir;
+    use test_case::test_case;
     use ulid::Ulid;
 
+    use crate::utils::schedule::WeeklySchedule;
     use crate::wad_data::{AbsenceRecord, AbsenceType};
 
     fn create_test_store() -> (JsonDataStore, TempDir) {
@@ -201,6 +717,7 @@ mod tests {
             hours,
             absence_type,
             note: Some("Test record".to_string()),
+            ical_uid: None,
         }
     }
 
@@ -233,6 +750,7 @@ mod tests {
             hours: 4.0,
             absence_type: AbsenceType::Sick,
             note: Some("Morning sick".to_string()),
+            ical_uid: None,
         };
 
         let record2 = AbsenceRecord {
@@ -241,6 +759,7 @@ mod tests {
             hours: 4.0,
             absence_type: AbsenceType::Vacation,
             note: Some("Afternoon PTO".to_string()),
+            ical_uid: None,
         };
 
         // Add both records
@@ -289,6 +808,7 @@ mod tests {
             hours: 4.0,
             absence_type: AbsenceType::Sick,
             note: Some("Morning".to_string()),
+            ical_uid: None,
         };
 
         let record2 = AbsenceRecord {
@@ -297,6 +817,7 @@ mod tests {
             hours: 4.0,
             absence_type: AbsenceType::Vacation,
             note: Some("Afternoon".to_string()),
+            ical_uid: None,
         };
 
         // Add both
@@ -332,6 +853,68 @@ mod tests {
         assert!(!removed);
     }
 
+    #[test]
+    fn test_get_absences_in_range() {
+        let (store, _temp_dir) = create_test_store();
+        for (date, absence_type) in [
+            ("2024-12-30", AbsenceType::Vacation),
+            ("2025-01-02", AbsenceType::Sick),
+            ("2024-12-31", AbsenceType::Holiday),
+            ("2025-01-06", AbsenceType::Vacation),
+        ] {
+            store
+                .add_absence(create_test_record(date, absence_type, 8.0))
+                .unwrap();
+        }
+        // Unrelated files in the year directory are ignored
+        fs::write(store.year_dir(2025).join("notes.txt"), "").unwrap();
+
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 1, 5).unwrap(),
+        )
+        .unwrap();
+        let dates: Vec<String> = store
+            .get_absences(&range)
+            .unwrap()
+            .iter()
+            .map(|record| record.date.to_string())
+            .collect();
+        assert_eq!(dates, vec!["2024-12-31", "2025-01-02"]);
+    }
+
+    #[test]
+    fn test_all_absences_across_years() {
+        let (store, _temp_dir) = create_test_store();
+        for date in ["2025-01-02", "2023-06-01", "2024-12-31"] {
+            store
+                .add_absence(create_test_record(date, AbsenceType::Vacation, 8.0))
+                .unwrap();
+        }
+        // Directories not named like a year are ignored
+        fs::create_dir_all(store.absences_dir().join("backup")).unwrap();
+
+        let dates: Vec<String> = store
+            .all_absences()
+            .unwrap()
+            .iter()
+            .map(|record| record.date.to_string())
+            .collect();
+        assert_eq!(dates, vec!["2023-06-01", "2024-12-31", "2025-01-02"]);
+    }
+
+    #[test]
+    fn test_get_absences_without_data() {
+        let (store, _temp_dir) = create_test_store();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert!(
+            store
+                .get_absences(&DateRange::day(date))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_year_directory_structure() {
         let (store, temp_dir) = create_test_store();
@@ -375,4 +958,369 @@ mod tests {
         // Verify file is deleted
         assert!(!file_path.exists());
     }
+
+    fn create_test_contract(valid_from: &str, weekly_hours: f64) -> ContractPeriod {
+        ContractPeriod {
+            id: Ulid::new(),
+            valid_from: NaiveDate::parse_from_str(valid_from, "%Y-%m-%d").unwrap(),
+            schedule: WeeklySchedule::spread_over_weekdays(weekly_hours),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_contracts_sorted_by_valid_from() {
+        let (store, temp_dir) = create_test_store();
+
+        store
+            .add_contract(create_test_contract("2025-03-01", 32.0))
+            .unwrap();
+        store
+            .add_contract(create_test_contract("2024-01-01", 40.0))
+            .unwrap();
+
+        let contracts = store.list_contracts().unwrap();
+        assert_eq!(contracts.len(), 2);
+        assert_eq!(contracts[0].schedule.weekly_hours(), 40.0);
+        assert_eq!(contracts[1].schedule.weekly_hours(), 32.0);
+        assert!(temp_dir.path().join("contracts.json").exists());
+    }
+
+    #[test]
+    fn test_duplicate_contract_start_rejected() {
+        let (store, _temp_dir) = create_test_store();
+
+        store
+            .add_contract(create_test_contract("2025-03-01", 32.0))
+            .unwrap();
+        let result = store.add_contract(create_test_contract("2025-03-01", 20.0));
+
+        assert!(matches!(
+            result,
+            Err(JsonDataStoreError::DuplicateContract(_))
+        ));
+        assert_eq!(store.list_contracts().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_remove_contract() {
+        let (store, _temp_dir) = create_test_store();
+        let contract = create_test_contract("2025-03-01", 32.0);
+        let id = contract.id;
+
+        store.add_contract(contract).unwrap();
+
+        assert!(store.remove_contract(id).unwrap());
+        assert!(!store.remove_contract(id).unwrap());
+        assert!(store.list_contracts().unwrap().is_empty());
+    }
+
+    /// Names of the files in a directory, sorted
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn move_journal(record: &AbsenceRecord, to: &str) -> MoveJournal {
+        let mut moved = record.clone();
+        moved.date = NaiveDate::parse_from_str(to, "%Y-%m-%d").unwrap();
+        MoveJournal {
+            from: record.date,
+            record: moved,
+        }
+    }
+
+    fn write_journal(store: &JsonDataStore, journal: &MoveJournal) {
+        fs::write(
+            store.journal_file_path(),
+            serde_json::to_string(journal).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_writes_leave_no_temporary_files() {
+        let (store, temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+        store
+            .add_absence(create_test_record("2024-01-15", AbsenceType::Sick, 4.0))
+            .unwrap();
+        store
+            .update_absence(record.date, move_journal(&record, "2024-01-16").record)
+            .unwrap();
+        store
+            .add_contract(create_test_contract("2024-01-01", 40.0))
+            .unwrap();
+
+        assert_eq!(
+            file_names(temp_dir.path()),
+            vec![".lock", "absences", "contracts.json"]
+        );
+        assert_eq!(
+            file_names(&store.year_dir(2024)),
+            vec!["2024-01-15.json", "2024-01-16.json"]
+        );
+    }
+
+    #[test]
+    fn test_failed_write_keeps_target_and_cleans_up() {
+        let (store, _temp_dir) = create_test_store();
+        // A directory can't be replaced by a file
+        let target = store.year_dir(2024).join("2024-01-15.json");
+        fs::create_dir_all(target.join("blocker")).unwrap();
+
+        assert!(write_atomically(&target, "[]").is_err());
+
+        assert!(target.join("blocker").exists());
+        assert_eq!(file_names(&store.year_dir(2024)), vec!["2024-01-15.json"]);
+    }
+
+    #[test_case(false; "before the record was added")]
+    #[test_case(true; "after the record was added")]
+    fn test_interrupted_move_finished_on_open(added: bool) {
+        let (store, temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+
+        // Simulate a crash during `update_absence`
+        let journal = move_journal(&record, "2024-02-01");
+        write_journal(&store, &journal);
+        if added {
+            store.insert_absence(journal.record.clone()).unwrap();
+        }
+
+        let store = JsonDataStore::open_in(temp_dir.path()).unwrap();
+
+        assert!(store.get_absence(record.date).unwrap().is_empty());
+        assert_eq!(store.all_absences().unwrap(), vec![journal.record.clone()]);
+        assert!(!store.journal_file_path().exists());
+    }
+
+    #[test]
+    fn test_failed_move_keeps_record_and_is_finished_later() {
+        let (store, temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+
+        // Writing the new day fails, e.g. because the disk is full
+        let blocker = store.year_dir(2024).join("2024-02-01.json");
+        fs::create_dir_all(blocker.join("blocker")).unwrap();
+        let moved = move_journal(&record, "2024-02-01").record;
+
+        assert!(store.update_absence(record.date, moved.clone()).is_err());
+        assert_eq!(
+            store.get_absence(record.date).unwrap(),
+            vec![record.clone()]
+        );
+        assert!(store.journal_file_path().exists());
+
+        // Once writing works again the move completes
+        fs::remove_dir_all(blocker).unwrap();
+        let store = JsonDataStore::open_in(temp_dir.path()).unwrap();
+
+        assert_eq!(store.all_absences().unwrap(), vec![moved]);
+        assert!(!store.journal_file_path().exists());
+    }
+
+    #[test]
+    fn test_interrupted_move_reported_and_finished_by_repair() {
+        let (store, temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+        let journal = move_journal(&record, "2024-02-01");
+        write_journal(&store, &journal);
+
+        let store = JsonDataStore::inspect_in(temp_dir.path()).unwrap();
+        let check = store.check_files().unwrap();
+
+        let issue = FileIssue::UnfinishedMove {
+            path: store.journal_file_path(),
+        };
+        assert_eq!(check.issues, vec![issue.clone()]);
+        assert_eq!(check.records, vec![record]);
+        assert!(store.journal_file_path().exists());
+
+        store.repair(&issue).unwrap();
+
+        assert_eq!(store.all_absences().unwrap(), vec![journal.record]);
+        assert!(store.check_files().unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn test_lock_excludes_other_processes() {
+        let (store, _temp_dir) = create_test_store();
+        let lock = store.lock().unwrap();
+
+        // Another open file, like the one of a second `wad` process
+        let other = File::open(store.lock_file_path()).unwrap();
+        assert!(matches!(
+            other.try_lock(),
+            Err(std::fs::TryLockError::WouldBlock)
+        ));
+
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+    }
+
+    /// Write records to a file below the absences directory, bypassing the store
+    fn write_raw(store: &JsonDataStore, relative: &str, records: &[AbsenceRecord]) -> PathBuf {
+        let path = store.absences_dir().join(relative);
+        JsonDataStore::write_records(&path, records).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_check_files_of_healthy_store() {
+        let (store, _temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+
+        let check = store.check_files().unwrap();
+
+        assert_eq!(check.records, vec![record]);
+        assert!(check.issues.is_empty());
+    }
+
+    #[test_case("2023/2024-01-15.json" ; "wrong year directory")]
+    #[test_case("2024/2024-01-16.json" ; "date not matching the file name")]
+    #[test_case("2024/15.01.2024.json" ; "file not named like a date")]
+    fn test_misplaced_record_is_moved_to_its_day(relative: &str) {
+        let (store, _temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        let path = write_raw(&store, relative, std::slice::from_ref(&record));
+
+        let check = store.check_files().unwrap();
+        assert_eq!(
+            check.issues,
+            vec![FileIssue::Misplaced {
+                path: path.clone(),
+                record: record.clone()
+            }]
+        );
+        assert!(check.issues[0].is_repairable());
+
+        store.repair(&check.issues[0]).unwrap();
+
+        assert_eq!(store.get_absence(record.date).unwrap(), vec![record]);
+        assert!(!path.exists());
+        assert!(store.check_files().unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn test_misplaced_record_conflicting_with_stored_one_is_kept() {
+        let (store, _temp_dir) = create_test_store();
+        let stored = create_test_record("2024-01-15", AbsenceType::Vacation, 8.0);
+        store.add_absence(stored.clone()).unwrap();
+        let misplaced = AbsenceRecord {
+            hours: 4.0,
+            ..stored.clone()
+        };
+        let path = write_raw(
+            &store,
+            "2023/2024-01-15.json",
+            std::slice::from_ref(&misplaced),
+        );
+
+        let result = store.repair(&FileIssue::Misplaced {
+            path: path.clone(),
+            record: misplaced,
+        });
+
+        assert!(matches!(
+            result,
+            Err(JsonDataStoreError::ConflictingRecord { .. })
+        ));
+        assert_eq!(store.get_absence(stored.date).unwrap(), vec![stored]);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_duplicated_record_is_kept_once() {
+        let (store, _temp_dir) = create_test_store();
+        let record = create_test_record("2024-01-15", AbsenceType::Sick, 8.0);
+        let other = create_test_record("2024-01-15", AbsenceType::Vacation, 4.0);
+        write_raw(
+            &store,
+            "2024/2024-01-15.json",
+            &[record.clone(), other.clone(), record.clone()],
+        );
+
+        let check = store.check_files().unwrap();
+        assert_eq!(
+            check.issues,
+            vec![FileIssue::Duplicated {
+                path: store.absence_file_path(record.date),
+                record: record.clone(),
+                copies: 2,
+            }]
+        );
+
+        store.repair(&check.issues[0]).unwrap();
+
+        assert_eq!(store.get_absence(record.date).unwrap().len(), 2);
+        assert!(store.check_files().unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_files_are_reported_and_left_alone() {
+        let (store, _temp_dir) = create_test_store();
+        let readable = create_test_record("2024-01-16", AbsenceType::Vacation, 8.0);
+        store.add_absence(readable.clone()).unwrap();
+        let broken = store.absence_file_path(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        fs::write(&broken, "[{\"id\": ").unwrap();
+        fs::write(store.contracts_file_path(), "{}").unwrap();
+
+        let check = store.check_files().unwrap();
+
+        // Everything that can be read is still checked
+        assert_eq!(check.records, vec![readable]);
+        let unreadable: Vec<&Path> = check
+            .issues
+            .iter()
+            .map(|issue| match issue {
+                FileIssue::Unreadable { path, .. } => path.as_path(),
+                other => panic!("Expected only unreadable files, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            unreadable,
+            vec![store.contracts_file_path().as_path(), broken.as_path()]
+        );
+        assert!(!check.issues[0].is_repairable());
+
+        store.repair(&check.issues[1]).unwrap();
+        assert_eq!(fs::read_to_string(&broken).unwrap(), "[{\"id\": ");
+    }
+
+    #[test]
+    fn test_leftover_temporary_files_are_removed() {
+        let (store, _temp_dir) = create_test_store();
+        fs::create_dir_all(store.year_dir(2024)).unwrap();
+        let leftovers = vec![
+            store.data_dir.join(".tmpA1b2C3"),
+            store.year_dir(2024).join(".tmpD4e5F6"),
+        ];
+        for path in &leftovers {
+            fs::write(path, "[]").unwrap();
+        }
+
+        let check = store.check_files().unwrap();
+        assert_eq!(
+            check.issues,
+            leftovers
+                .iter()
+                .map(|path| FileIssue::LeftoverTemporary { path: path.clone() })
+                .collect::<Vec<_>>()
+        );
+
+        for issue in &check.issues {
+            store.repair(issue).unwrap();
+        }
+        assert!(leftovers.iter().all(|path| !path.exists()));
+    }
 }
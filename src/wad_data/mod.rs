@@ -1,11 +1,27 @@
+use std::path::PathBuf;
+
 pub mod absence;
+pub mod contract;
 pub mod json_store;
+pub mod schema;
+pub mod sqlite_store;
+pub mod store;
 
 pub use absence::*;
+pub use contract::*;
 pub use json_store::*;
+pub use sqlite_store::*;
+pub use store::*;
 
-pub trait WadDataStore: AbsenceStorage {
-    fn open() -> Result<Self, Self::Error>
+pub trait WadDataStore:
+    AbsenceStorage + ContractStorage<Error = <Self as AbsenceStorage>::Error>
+{
+    fn open() -> Result<Self, <Self as AbsenceStorage>::Error>
     where
         Self: Sized;
 }
+
+/// Get the platform-specific directory all data stores keep their files in
+pub fn default_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("wad"))
+}
@@ -0,0 +1,373 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use thiserror::Error;
+
+use super::json_store::write_atomically;
+
+/// Version of the data directory layout written by this version of wad
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Version of data directories written before the version file existed
+const UNVERSIONED_VERSION: u32 = 1;
+
+const VERSION_FILE_NAME: &str = "version";
+const BACKUPS_DIR_NAME: &str = "backups";
+
+/// Files that are never part of a backup
+const NOT_BACKED_UP: &[&str] = &[BACKUPS_DIR_NAME, ".lock"];
+
+#[derive(Error, Debug)]
+pub enum SchemaError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("JSON serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Invalid data directory version: {0:?}")]
+    InvalidVersion(String),
+    #[error(
+        "The data directory has version {found}, but this version of wad only supports up to version {supported}. Please update wad."
+    )]
+    TooNew { found: u32, supported: u32 },
+    #[error("No migration from data directory version {0}")]
+    MissingMigration(u32),
+    #[error(
+        "Migrating the data directory from version {from} failed: {source}. The data before migrating is in {}", backup.display()
+    )]
+    MigrationFailed {
+        from: u32,
+        backup: PathBuf,
+        source: Box<SchemaError>,
+    },
+}
+
+/// A step upgrading the data directory from one version to the next
+pub struct Migration {
+    /// Version this migration upgrades from, it leaves the directory at the next one
+    pub from: u32,
+    /// What changes, shown by `wad data migrate --dry-run`
+    pub description: &'static str,
+    pub apply: fn(&Path) -> Result<(), SchemaError>,
+}
+
+/// All migrations, one per version below [`CURRENT_VERSION`]
+///
+/// Changing the layout or the serialized form of a record means bumping
+/// `CURRENT_VERSION` and adding a migration from the previous version here.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Get the version stored in the data directory, if any
+fn stored_version(data_dir: &Path) -> Result<Option<u32>, SchemaError> {
+    let path = data_dir.join(VERSION_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)?;
+    content
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|_| SchemaError::InvalidVersion(content))
+}
+
+/// Check whether the data directory holds any data besides wad's own bookkeeping
+fn has_data(data_dir: &Path) -> Result<bool, SchemaError> {
+    if !data_dir.exists() {
+        return Ok(false);
+    }
+    for entry in fs::read_dir(data_dir)? {
+        let name = entry?.file_name();
+        if !NOT_BACKED_UP.iter().any(|skipped| name == *skipped) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn write_version(data_dir: &Path, version: u32) -> Result<(), SchemaError> {
+    write_atomically(&data_dir.join(VERSION_FILE_NAME), &format!("{}\n", version))?;
+    Ok(())
+}
+
+/// Get the version of the data directory
+///
+/// Directories without data are treated as being at `latest`, as there's
+/// nothing to migrate.
+fn data_version(data_dir: &Path, latest: u32) -> Result<u32, SchemaError> {
+    match stored_version(data_dir)? {
+        Some(version) => Ok(version),
+        None if has_data(data_dir)? => Ok(UNVERSIONED_VERSION),
+        None => Ok(latest),
+    }
+}
+
+/// Get the migrations needed to bring the data directory to `latest`, in order
+fn pending_in<'a>(
+    data_dir: &Path,
+    migrations: &'a [Migration],
+    latest: u32,
+) -> Result<Vec<&'a Migration>, SchemaError> {
+    let version = data_version(data_dir, latest)?;
+    if version > latest {
+        return Err(SchemaError::TooNew {
+            found: version,
+            supported: latest,
+        });
+    }
+
+    (version..latest)
+        .map(|from| {
+            migrations
+                .iter()
+                .find(|migration| migration.from == from)
+                .ok_or(SchemaError::MissingMigration(from))
+        })
+        .collect()
+}
+
+/// Copy the data directory into a new directory below `backups`
+fn back_up(data_dir: &Path, version: u32) -> Result<PathBuf, SchemaError> {
+    let backup = data_dir.join(BACKUPS_DIR_NAME).join(format!(
+        "version-{}-{}",
+        version,
+        Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+    ));
+    fs::create_dir_all(&backup)?;
+
+    for entry in fs::read_dir(data_dir)? {
+        let entry = entry?;
+        if !NOT_BACKED_UP
+            .iter()
+            .any(|skipped| entry.file_name() == *skipped)
+        {
+            copy_recursively(&entry.path(), &backup.join(entry.file_name()))?;
+        }
+    }
+    Ok(backup)
+}
+
+fn copy_recursively(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+fn upgrade_in(
+    data_dir: &Path,
+    migrations: &[Migration],
+    latest: u32,
+) -> Result<Option<PathBuf>, SchemaError> {
+    let pending = pending_in(data_dir, migrations, latest)?;
+    let Some(first) = pending.first() else {
+        if stored_version(data_dir)?.is_none() {
+            write_version(data_dir, latest)?;
+        }
+        return Ok(None);
+    };
+
+    let backup = back_up(data_dir, first.from)?;
+    for migration in pending {
+        (migration.apply)(data_dir).map_err(|e| SchemaError::MigrationFailed {
+            from: migration.from,
+            backup: backup.clone(),
+            source: Box::new(e),
+        })?;
+        // Recorded after each step, so a failed step is retried from where it stopped
+        write_version(data_dir, migration.from + 1)?;
+    }
+    Ok(Some(backup))
+}
+
+/// Get the migrations needed to bring the data directory up to date, in order
+pub fn pending_migrations(data_dir: &Path) -> Result<Vec<&'static Migration>, SchemaError> {
+    pending_in(data_dir, MIGRATIONS, CURRENT_VERSION)
+}
+
+/// Get the version the data directory is at
+pub fn current_data_version(data_dir: &Path) -> Result<u32, SchemaError> {
+    data_version(data_dir, CURRENT_VERSION)
+}
+
+/// Bring the data directory up to date, taking a backup first
+///
+/// Returns the path of the backup if anything was migrated. Must only be
+/// called while holding the data directory lock.
+pub(super) fn upgrade(data_dir: &Path) -> Result<Option<PathBuf>, SchemaError> {
+    upgrade_in(data_dir, MIGRATIONS, CURRENT_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Appends the step to `steps.log`, to check which migrations ran in which order
+    fn log_step(data_dir: &Path, step: &str) -> Result<(), SchemaError> {
+        let log = data_dir.join("steps.log");
+        let mut content = fs::read_to_string(&log).unwrap_or_default();
+        content.push_str(step);
+        fs::write(log, content)?;
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            from: 1,
+            description: "First step",
+            apply: |data_dir| log_step(data_dir, "1-2 "),
+        },
+        Migration {
+            from: 2,
+            description: "Rename contracts",
+            apply: |data_dir| {
+                fs::rename(
+                    data_dir.join("contracts.json"),
+                    data_dir.join("contract_periods.json"),
+                )?;
+                log_step(data_dir, "2-3 ")
+            },
+        },
+        Migration {
+            from: 3,
+            description: "Always fails",
+            apply: |_| Err(SchemaError::InvalidVersion("broken".to_string())),
+        },
+    ];
+
+    fn create_unversioned_dir() -> TempDir {
+        let data_dir = TempDir::new().unwrap();
+        fs::create_dir_all(data_dir.path().join("absences/2024")).unwrap();
+        fs::write(data_dir.path().join("absences/2024/2024-01-15.json"), "[]").unwrap();
+        fs::write(data_dir.path().join("contracts.json"), "[]").unwrap();
+        fs::write(data_dir.path().join(".lock"), "").unwrap();
+        data_dir
+    }
+
+    #[test]
+    fn test_migrations_reach_current_version() {
+        // Every version since versioning started needs a migration to the next one
+        let data_dir = create_unversioned_dir();
+        let pending = pending_migrations(data_dir.path()).unwrap();
+        assert_eq!(
+            pending.len(),
+            (CURRENT_VERSION - UNVERSIONED_VERSION) as usize
+        );
+    }
+
+    #[test]
+    fn test_new_directory_starts_at_latest_version() {
+        let data_dir = TempDir::new().unwrap();
+
+        assert!(
+            pending_in(data_dir.path(), TEST_MIGRATIONS, 3)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            upgrade_in(data_dir.path(), TEST_MIGRATIONS, 3).unwrap(),
+            None
+        );
+        assert_eq!(stored_version(data_dir.path()).unwrap(), Some(3));
+        assert!(!data_dir.path().join(BACKUPS_DIR_NAME).exists());
+    }
+
+    #[test]
+    fn test_unversioned_directory_is_migrated_after_backup() {
+        let data_dir = create_unversioned_dir();
+
+        let pending: Vec<&str> = pending_in(data_dir.path(), TEST_MIGRATIONS, 3)
+            .unwrap()
+            .iter()
+            .map(|migration| migration.description)
+            .collect();
+        assert_eq!(pending, vec!["First step", "Rename contracts"]);
+
+        let backup = upgrade_in(data_dir.path(), TEST_MIGRATIONS, 3)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(stored_version(data_dir.path()).unwrap(), Some(3));
+        assert_eq!(
+            fs::read_to_string(data_dir.path().join("steps.log")).unwrap(),
+            "1-2 2-3 "
+        );
+        assert!(data_dir.path().join("contract_periods.json").exists());
+        // The backup holds the data as it was before migrating
+        assert!(backup.starts_with(data_dir.path().join(BACKUPS_DIR_NAME)));
+        assert!(backup.join("contracts.json").exists());
+        assert!(backup.join("absences/2024/2024-01-15.json").exists());
+        assert!(!backup.join(".lock").exists());
+        assert!(!backup.join("steps.log").exists());
+
+        // Nothing is left to do afterwards
+        assert_eq!(
+            upgrade_in(data_dir.path(), TEST_MIGRATIONS, 3).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_failed_migration_keeps_completed_steps() {
+        let data_dir = create_unversioned_dir();
+
+        let result = upgrade_in(data_dir.path(), TEST_MIGRATIONS, 4);
+
+        let Err(SchemaError::MigrationFailed { from, backup, .. }) = result else {
+            panic!("Expected the last migration to fail, got {:?}", result);
+        };
+        assert_eq!(from, 3);
+        assert!(backup.join("contracts.json").exists());
+        assert_eq!(stored_version(data_dir.path()).unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_newer_directory_is_rejected() {
+        let data_dir = create_unversioned_dir();
+        write_version(data_dir.path(), 5).unwrap();
+
+        assert!(matches!(
+            upgrade_in(data_dir.path(), TEST_MIGRATIONS, 3),
+            Err(SchemaError::TooNew {
+                found: 5,
+                supported: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_missing_migration_is_reported() {
+        let data_dir = create_unversioned_dir();
+
+        assert!(matches!(
+            pending_in(data_dir.path(), TEST_MIGRATIONS, 5),
+            Err(SchemaError::MissingMigration(4))
+        ));
+        // Nothing is touched when the migrations can't reach the version
+        assert!(matches!(
+            upgrade_in(data_dir.path(), TEST_MIGRATIONS, 5),
+            Err(SchemaError::MissingMigration(4))
+        ));
+        assert_eq!(stored_version(data_dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_version_file() {
+        let data_dir = TempDir::new().unwrap();
+        fs::write(data_dir.path().join(VERSION_FILE_NAME), "two").unwrap();
+
+        assert!(matches!(
+            pending_in(data_dir.path(), TEST_MIGRATIONS, 3),
+            Err(SchemaError::InvalidVersion(_))
+        ));
+    }
+}
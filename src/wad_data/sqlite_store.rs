@@ -0,0 +1,699 @@
+use std::error::Error as StdError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use rusqlite::types::Type;
+use rusqlite::{Connection, ErrorCode, OpenFlags, Row, Transaction, TransactionBehavior, params};
+use thiserror::Error;
+use ulid::Ulid;
+
+use super::{
+    AbsenceRecord, AbsenceStorage, ContractPeriod, ContractStorage, WadDataStore, default_data_dir,
+};
+use crate::utils::date::DateRange;
+
+#[derive(Error, Debug)]
+pub enum SqliteDataStoreError {
+    #[error("No data directory available")]
+    NoDataDir,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("JSON serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Absence record with ID {id} not found on {date}")]
+    RecordNotFound { id: Ulid, date: NaiveDate },
+    #[error("A contract period starting on {0} already exists")]
+    DuplicateContract(NaiveDate),
+    #[error(
+        "The database has schema version {found}, but this version of wad only supports up to version {supported}. Please update wad."
+    )]
+    TooNew { found: u32, supported: u32 },
+    #[error("No migration from database schema version {0}")]
+    MissingMigration(u32),
+}
+
+/// Name of the database file within the data directory
+pub const SQLITE_FILE_NAME: &str = "wad.sqlite3";
+
+/// Dates are stored as `YYYY-MM-DD` text, so they sort and compare like dates
+/// and ranged queries can use the index
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS absences (
+        id TEXT PRIMARY KEY NOT NULL,
+        date TEXT NOT NULL,
+        hours REAL NOT NULL,
+        absence_type TEXT NOT NULL,
+        note TEXT,
+        ical_uid TEXT
+    );
+    CREATE INDEX IF NOT EXISTS absences_by_date ON absences (date, id);
+    CREATE TABLE IF NOT EXISTS contracts (
+        id TEXT PRIMARY KEY NOT NULL,
+        valid_from TEXT NOT NULL UNIQUE,
+        schedule TEXT NOT NULL,
+        note TEXT
+    );
+";
+
+/// Version of the database schema written by this version of wad
+///
+/// It is kept in `PRAGMA user_version`, which SQLite starts at 0.
+pub const SQLITE_SCHEMA_VERSION: u32 = 1;
+
+/// Version of databases created before the schema version was recorded
+const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// A step upgrading the database schema from one version to the next
+pub struct SqliteMigration {
+    /// Version this migration upgrades from, it leaves the database at the next one
+    pub from: u32,
+    /// What changes, shown by `wad data migrate --dry-run`
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// All migrations, one per version below [`SQLITE_SCHEMA_VERSION`]
+///
+/// Changing `SCHEMA` means bumping `SQLITE_SCHEMA_VERSION` and adding a
+/// migration from the previous version here.
+pub const SQLITE_MIGRATIONS: &[SqliteMigration] = &[];
+
+/// How long to wait for another wad process to finish writing before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const ABSENCE_COLUMNS: &str = "id, date, hours, absence_type, note, ical_uid";
+
+pub struct SqliteDataStore {
+    connection: Connection,
+}
+
+impl SqliteDataStore {
+    /// Open the database kept in the given directory, creating it if missing
+    pub fn open_in(data_dir: &Path) -> Result<Self, SqliteDataStoreError> {
+        fs::create_dir_all(data_dir)?;
+        Self::with_connection(Connection::open(data_dir.join(SQLITE_FILE_NAME))?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, SqliteDataStoreError> {
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        upgrade_schema(&connection, SQLITE_MIGRATIONS, SQLITE_SCHEMA_VERSION)?;
+        Ok(Self { connection })
+    }
+
+    /// Get the migrations needed to bring the database in the given directory up to date
+    ///
+    /// Nothing is changed, a missing database needs no migrations.
+    pub fn pending_migrations_in(
+        data_dir: &Path,
+    ) -> Result<Vec<&'static SqliteMigration>, SqliteDataStoreError> {
+        let path = data_dir.join(SQLITE_FILE_NAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        match schema_version(&connection)? {
+            Some(version) => pending_in(version, SQLITE_MIGRATIONS, SQLITE_SCHEMA_VERSION),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get the path of the database file
+    pub fn database_path(&self) -> PathBuf {
+        PathBuf::from(self.connection.path().unwrap_or_default())
+    }
+
+    /// Run several changes as one transaction
+    ///
+    /// If `changes` fails, everything it wrote through this store is rolled
+    /// back.
+    pub fn in_transaction<T, E>(&self, changes: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<SqliteDataStoreError>,
+    {
+        let transaction = self
+            .connection
+            .unchecked_transaction()
+            .map_err(SqliteDataStoreError::from)?;
+        let result = changes()?;
+        transaction.commit().map_err(SqliteDataStoreError::from)?;
+        Ok(result)
+    }
+
+    fn query_absences(
+        &self,
+        condition: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<AbsenceRecord>, SqliteDataStoreError> {
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT {} FROM absences {} ORDER BY date, id",
+            ABSENCE_COLUMNS, condition
+        ))?;
+        let records = statement
+            .query_map(params, absence_from_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(records)
+    }
+}
+
+/// Parse a text column, e.g. a ULID or a date
+fn parse_column<T>(row: &Row, index: usize) -> rusqlite::Result<T>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn StdError + Send + Sync>>,
+{
+    let text: String = row.get(index)?;
+    text.parse()
+        .map_err(|e: T::Err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, e.into()))
+}
+
+fn absence_from_row(row: &Row) -> rusqlite::Result<AbsenceRecord> {
+    Ok(AbsenceRecord {
+        id: parse_column(row, 0)?,
+        date: parse_column(row, 1)?,
+        hours: row.get(2)?,
+        absence_type: parse_column(row, 3)?,
+        note: row.get(4)?,
+        ical_uid: row.get(5)?,
+    })
+}
+
+fn contract_from_row(row: &Row) -> rusqlite::Result<ContractPeriod> {
+    let schedule: String = row.get(2)?;
+    Ok(ContractPeriod {
+        id: parse_column(row, 0)?,
+        valid_from: parse_column(row, 1)?,
+        schedule: serde_json::from_str(&schedule)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, Type::Text, e.into()))?,
+        note: row.get(3)?,
+    })
+}
+
+/// Get the schema version of the database, or `None` if it has no tables yet
+fn schema_version(connection: &Connection) -> Result<Option<u32>, SqliteDataStoreError> {
+    let version: u32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > 0 {
+        return Ok(Some(version));
+    }
+
+    let has_tables: bool = connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'absences')",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(has_tables.then_some(UNVERSIONED_SCHEMA_VERSION))
+}
+
+/// Get the migrations needed to bring a database at `version` to `latest`, in order
+fn pending_in(
+    version: u32,
+    migrations: &[SqliteMigration],
+    latest: u32,
+) -> Result<Vec<&SqliteMigration>, SqliteDataStoreError> {
+    if version > latest {
+        return Err(SqliteDataStoreError::TooNew {
+            found: version,
+            supported: latest,
+        });
+    }
+
+    (version..latest)
+        .map(|from| {
+            migrations
+                .iter()
+                .find(|migration| migration.from == from)
+                .ok_or(SqliteDataStoreError::MissingMigration(from))
+        })
+        .collect()
+}
+
+/// Create the schema of a new database, or bring an existing one to `latest`
+///
+/// Everything happens in one transaction, so a failed migration leaves the
+/// database as it was.
+fn upgrade_schema(
+    connection: &Connection,
+    migrations: &[SqliteMigration],
+    latest: u32,
+) -> Result<(), SqliteDataStoreError> {
+    let stored: u32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if stored == latest {
+        return Ok(());
+    }
+    if stored > latest {
+        return Err(SqliteDataStoreError::TooNew {
+            found: stored,
+            supported: latest,
+        });
+    }
+
+    // Another process may be upgrading as well, so look again once holding the write lock
+    let transaction = Transaction::new_unchecked(connection, TransactionBehavior::Immediate)?;
+    match schema_version(&transaction)? {
+        None => transaction.execute_batch(SCHEMA)?,
+        Some(version) => {
+            for migration in pending_in(version, migrations, latest)? {
+                transaction.execute_batch(migration.sql)?;
+            }
+        }
+    }
+    transaction.pragma_update(None, "user_version", latest)?;
+    transaction.commit()?;
+    Ok(())
+}
+
+impl WadDataStore for SqliteDataStore {
+    fn open() -> Result<Self, SqliteDataStoreError>
+    where
+        Self: Sized,
+    {
+        Self::open_in(&default_data_dir().ok_or(SqliteDataStoreError::NoDataDir)?)
+    }
+}
+
+impl AbsenceStorage for SqliteDataStore {
+    type Error = SqliteDataStoreError;
+
+    fn add_absence(&self, record: AbsenceRecord) -> Result<(), Self::Error> {
+        self.connection.execute(
+            &format!(
+                "INSERT INTO absences ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                ABSENCE_COLUMNS
+            ),
+            params![
+                record.id.to_string(),
+                record.date.to_string(),
+                record.hours,
+                record.absence_type.to_string(),
+                record.note,
+                record.ical_uid,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_absence(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        self.query_absences("WHERE date = ?1", [date.to_string()])
+    }
+
+    fn get_absences(&self, range: &DateRange) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        self.query_absences(
+            "WHERE date BETWEEN ?1 AND ?2",
+            [range.from.to_string(), range.to.to_string()],
+        )
+    }
+
+    fn all_absences(&self) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        self.query_absences("", [])
+    }
+
+    fn remove_absence(&self, date: NaiveDate, id: Ulid) -> Result<bool, Self::Error> {
+        let removed = self.connection.execute(
+            "DELETE FROM absences WHERE id = ?1 AND date = ?2",
+            [id.to_string(), date.to_string()],
+        )?;
+        Ok(removed > 0)
+    }
+
+    fn update_absence(
+        &self,
+        date: NaiveDate,
+        updated_record: AbsenceRecord,
+    ) -> Result<(), Self::Error> {
+        // A single statement, so moving a record to another date either
+        // happens completely or not at all
+        let updated = self.connection.execute(
+            "UPDATE absences
+             SET date = ?1, hours = ?2, absence_type = ?3, note = ?4, ical_uid = ?5
+             WHERE id = ?6 AND date = ?7",
+            params![
+                updated_record.date.to_string(),
+                updated_record.hours,
+                updated_record.absence_type.to_string(),
+                updated_record.note,
+                updated_record.ical_uid,
+                updated_record.id.to_string(),
+                date.to_string(),
+            ],
+        )?;
+
+        if updated == 0 {
+            return Err(SqliteDataStoreError::RecordNotFound {
+                id: updated_record.id,
+                date,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl ContractStorage for SqliteDataStore {
+    type Error = SqliteDataStoreError;
+
+    fn add_contract(&self, contract: ContractPeriod) -> Result<(), Self::Error> {
+        let result = self.connection.execute(
+            "INSERT INTO contracts (id, valid_from, schedule, note) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                contract.id.to_string(),
+                contract.valid_from.to_string(),
+                serde_json::to_string(&contract.schedule)?,
+                contract.note,
+            ],
+        );
+
+        match result {
+            // Two periods starting on the same day would make the schedule ambiguous
+            Err(rusqlite::Error::SqliteFailure(error, _))
+                if error.code == ErrorCode::ConstraintViolation =>
+            {
+                Err(SqliteDataStoreError::DuplicateContract(contract.valid_from))
+            }
+            result => result.map(|_| ()).map_err(SqliteDataStoreError::from),
+        }
+    }
+
+    fn list_contracts(&self) -> Result<Vec<ContractPeriod>, Self::Error> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT id, valid_from, schedule, note FROM contracts ORDER BY valid_from")?;
+        let contracts = statement
+            .query_map([], contract_from_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(contracts)
+    }
+
+    fn remove_contract(&self, id: Ulid) -> Result<bool, Self::Error> {
+        let removed = self
+            .connection
+            .execute("DELETE FROM contracts WHERE id = ?1", [id.to_string()])?;
+        Ok(removed > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    use crate::utils::schedule::WeeklySchedule;
+    use crate::wad_data::AbsenceType;
+
+    fn create_test_store() -> SqliteDataStore {
+        SqliteDataStore::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn create_test_record(date_str: &str, absence_type: AbsenceType, hours: f64) -> AbsenceRecord {
+        AbsenceRecord {
+            id: Ulid::new(),
+            date: date(date_str),
+            hours,
+            absence_type,
+            note: Some("Test record".to_string()),
+            ical_uid: None,
+        }
+    }
+
+    fn create_test_contract(valid_from: &str, weekly_hours: f64) -> ContractPeriod {
+        ContractPeriod {
+            id: Ulid::new(),
+            valid_from: date(valid_from),
+            schedule: WeeklySchedule::spread_over_weekdays(weekly_hours),
+            note: None,
+        }
+    }
+
+    #[test_case(AbsenceType::Vacation; "vacation")]
+    #[test_case(AbsenceType::OvertimeReduction; "overtime reduction")]
+    #[test_case(AbsenceType::Other("Moving Day".to_string()); "custom type")]
+    fn test_record_round_trip(absence_type: AbsenceType) {
+        let store = create_test_store();
+        let mut record = create_test_record("2025-03-14", absence_type, 4.5);
+        record.ical_uid = Some("event-1@example.com".to_string());
+
+        store.add_absence(record.clone()).unwrap();
+
+        assert_eq!(store.get_absence(record.date).unwrap(), vec![record]);
+    }
+
+    #[test]
+    fn test_records_of_a_day_ordered_by_id() {
+        let store = create_test_store();
+        let first = create_test_record("2025-03-14", AbsenceType::Sick, 4.0);
+        // IDs created within the same millisecond aren't ordered
+        let second = AbsenceRecord {
+            id: Ulid::from_parts(first.id.timestamp_ms() + 1, 0),
+            ..create_test_record("2025-03-14", AbsenceType::Vacation, 4.0)
+        };
+
+        store.add_absence(second.clone()).unwrap();
+        store.add_absence(first.clone()).unwrap();
+
+        let ids: Vec<Ulid> = store
+            .get_absence(first.date)
+            .unwrap()
+            .iter()
+            .map(|record| record.id)
+            .collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+    }
+
+    #[test]
+    fn test_get_absences_in_range() {
+        let store = create_test_store();
+        for date in ["2024-12-30", "2025-01-02", "2024-12-31", "2025-01-06"] {
+            store
+                .add_absence(create_test_record(date, AbsenceType::Vacation, 8.0))
+                .unwrap();
+        }
+
+        let range = DateRange::new(date("2024-12-31"), date("2025-01-05")).unwrap();
+        let dates: Vec<String> = store
+            .get_absences(&range)
+            .unwrap()
+            .iter()
+            .map(|record| record.date.to_string())
+            .collect();
+        assert_eq!(dates, vec!["2024-12-31", "2025-01-02"]);
+
+        let all: Vec<String> = store
+            .all_absences()
+            .unwrap()
+            .iter()
+            .map(|record| record.date.to_string())
+            .collect();
+        assert_eq!(
+            all,
+            vec!["2024-12-30", "2024-12-31", "2025-01-02", "2025-01-06"]
+        );
+    }
+
+    #[test]
+    fn test_remove_absence() {
+        let store = create_test_store();
+        let record = create_test_record("2025-03-14", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+
+        // The date has to match as well
+        assert!(!store.remove_absence(date("2025-03-15"), record.id).unwrap());
+        assert!(store.remove_absence(record.date, record.id).unwrap());
+        assert!(!store.remove_absence(record.date, record.id).unwrap());
+        assert!(store.all_absences().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_update_absence_moves_record_to_new_date() {
+        let store = create_test_store();
+        let record = create_test_record("2025-03-14", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+
+        let mut updated = record.clone();
+        updated.date = date("2025-03-17");
+        updated.hours = 4.0;
+        store.update_absence(record.date, updated.clone()).unwrap();
+
+        assert!(store.get_absence(record.date).unwrap().is_empty());
+        assert_eq!(store.get_absence(updated.date).unwrap(), vec![updated]);
+    }
+
+    #[test]
+    fn test_update_missing_absence_changes_nothing() {
+        let store = create_test_store();
+        let record = create_test_record("2025-03-14", AbsenceType::Vacation, 8.0);
+        store.add_absence(record.clone()).unwrap();
+
+        let mut updated = record.clone();
+        updated.date = date("2025-03-17");
+        let result = store.update_absence(date("2025-03-13"), updated);
+
+        assert!(matches!(
+            result,
+            Err(SqliteDataStoreError::RecordNotFound { .. })
+        ));
+        assert_eq!(store.all_absences().unwrap(), vec![record]);
+    }
+
+    #[test]
+    fn test_contracts_sorted_by_valid_from() {
+        let store = create_test_store();
+        let later = create_test_contract("2025-03-01", 32.0);
+        let earlier = create_test_contract("2024-01-01", 40.0);
+
+        store.add_contract(later.clone()).unwrap();
+        store.add_contract(earlier.clone()).unwrap();
+
+        assert_eq!(store.list_contracts().unwrap(), vec![earlier, later]);
+    }
+
+    #[test]
+    fn test_duplicate_contract_start_rejected() {
+        let store = create_test_store();
+
+        store
+            .add_contract(create_test_contract("2025-03-01", 32.0))
+            .unwrap();
+        let result = store.add_contract(create_test_contract("2025-03-01", 20.0));
+
+        assert!(matches!(
+            result,
+            Err(SqliteDataStoreError::DuplicateContract(_))
+        ));
+        assert_eq!(store.list_contracts().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_remove_contract() {
+        let store = create_test_store();
+        let contract = create_test_contract("2025-03-01", 32.0);
+        let id = contract.id;
+
+        store.add_contract(contract).unwrap();
+
+        assert!(store.remove_contract(id).unwrap());
+        assert!(!store.remove_contract(id).unwrap());
+        assert!(store.list_contracts().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_transaction_rolls_back() {
+        let store = create_test_store();
+        let record = create_test_record("2025-03-14", AbsenceType::Vacation, 8.0);
+
+        let result: Result<(), SqliteDataStoreError> = store.in_transaction(|| {
+            store.add_absence(record.clone())?;
+            store.add_contract(create_test_contract("2025-03-01", 32.0))?;
+            store.add_contract(create_test_contract("2025-03-01", 20.0))
+        });
+
+        assert!(result.is_err());
+        assert!(store.all_absences().unwrap().is_empty());
+        assert!(store.list_contracts().unwrap().is_empty());
+    }
+
+    fn user_version(connection: &Connection) -> u32 {
+        connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    const TEST_MIGRATIONS: &[SqliteMigration] = &[
+        SqliteMigration {
+            from: 1,
+            description: "Add a color to contracts",
+            sql: "ALTER TABLE contracts ADD COLUMN color TEXT",
+        },
+        SqliteMigration {
+            from: 2,
+            description: "Default the color",
+            sql: "UPDATE contracts SET color = 'blue'",
+        },
+    ];
+
+    #[test]
+    fn test_new_database_gets_current_version() {
+        let store = create_test_store();
+        assert_eq!(user_version(&store.connection), SQLITE_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_unversioned_database_keeps_data() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        connection
+            .execute(
+                "INSERT INTO contracts (id, valid_from, schedule) VALUES ('a', '2025-03-01', '{}')",
+                [],
+            )
+            .unwrap();
+
+        upgrade_schema(&connection, TEST_MIGRATIONS, 3).unwrap();
+
+        assert_eq!(user_version(&connection), 3);
+        let color: String = connection
+            .query_row("SELECT color FROM contracts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(color, "blue");
+    }
+
+    #[test]
+    fn test_failed_migration_leaves_database_unchanged() {
+        let connection = Connection::open_in_memory().unwrap();
+        upgrade_schema(&connection, TEST_MIGRATIONS, 1).unwrap();
+        let broken = [SqliteMigration {
+            from: 1,
+            description: "Broken",
+            sql: "ALTER TABLE contracts ADD COLUMN color TEXT; SELECT * FROM missing",
+        }];
+
+        assert!(upgrade_schema(&connection, &broken, 2).is_err());
+        assert_eq!(user_version(&connection), 1);
+        assert!(connection.prepare("SELECT color FROM contracts").is_err());
+    }
+
+    #[test]
+    fn test_missing_migration_is_rejected() {
+        let connection = Connection::open_in_memory().unwrap();
+        upgrade_schema(&connection, TEST_MIGRATIONS, 1).unwrap();
+
+        let result = upgrade_schema(&connection, &TEST_MIGRATIONS[..1], 3);
+
+        assert!(matches!(
+            result,
+            Err(SqliteDataStoreError::MissingMigration(2))
+        ));
+        assert_eq!(user_version(&connection), 1);
+    }
+
+    #[test]
+    fn test_too_new_database_is_not_opened() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .pragma_update(None, "user_version", SQLITE_SCHEMA_VERSION + 1)
+            .unwrap();
+
+        assert!(matches!(
+            SqliteDataStore::with_connection(connection),
+            Err(SqliteDataStoreError::TooNew { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pending_migrations_without_database() {
+        let data_dir = tempfile::TempDir::new().unwrap();
+        assert!(
+            SqliteDataStore::pending_migrations_in(data_dir.path())
+                .unwrap()
+                .is_empty()
+        );
+        assert!(!data_dir.path().join(SQLITE_FILE_NAME).exists());
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+use ulid::Ulid;
+
+use super::{
+    AbsenceRecord, AbsenceStorage, ContractPeriod, ContractStorage, JsonDataStore,
+    JsonDataStoreError, SqliteDataStore, SqliteDataStoreError, WadDataStore,
+};
+use crate::config::DataStoreKind;
+use crate::utils::date::DateRange;
+
+#[derive(Error, Debug)]
+pub enum DataStoreError {
+    #[error(transparent)]
+    Json(#[from] JsonDataStoreError),
+    #[error(transparent)]
+    Sqlite(#[from] SqliteDataStoreError),
+}
+
+/// All available data stores
+///
+/// Commands open the configured one through [`DataStores::open`] and only
+/// talk to the storage traits.
+pub enum DataStores {
+    Json(JsonDataStore),
+    Sqlite(SqliteDataStore),
+}
+
+/// Call the same method on whichever store is wrapped
+macro_rules! delegate {
+    ($stores:expr, $store:ident => $call:expr) => {
+        match $stores {
+            DataStores::Json($store) => Ok($call?),
+            DataStores::Sqlite($store) => Ok($call?),
+        }
+    };
+}
+
+impl DataStores {
+    pub fn open(kind: DataStoreKind) -> Result<Self, DataStoreError> {
+        Ok(match kind {
+            DataStoreKind::Json => Self::Json(JsonDataStore::open()?),
+            DataStoreKind::Sqlite => Self::Sqlite(SqliteDataStore::open()?),
+        })
+    }
+
+    /// Replace everything stored with the given absences and contract periods
+    ///
+    /// In SQLite this is all-or-nothing. The JSON store has no transactions,
+    /// so a failure leaves part of the records written.
+    pub fn replace_contents(
+        &self,
+        absences: &[AbsenceRecord],
+        contracts: &[ContractPeriod],
+    ) -> Result<(), DataStoreError> {
+        let replace = || -> Result<(), DataStoreError> {
+            for record in self.all_absences()? {
+                self.remove_absence(record.date, record.id)?;
+            }
+            for contract in self.list_contracts()? {
+                self.remove_contract(contract.id)?;
+            }
+            for record in absences {
+                self.add_absence(record.clone())?;
+            }
+            for contract in contracts {
+                self.add_contract(contract.clone())?;
+            }
+            Ok(())
+        };
+
+        match self {
+            DataStores::Json(_) => replace(),
+            DataStores::Sqlite(store) => store.in_transaction(replace),
+        }
+    }
+}
+
+impl AbsenceStorage for DataStores {
+    type Error = DataStoreError;
+
+    fn add_absence(&self, record: AbsenceRecord) -> Result<(), Self::Error> {
+        delegate!(self, store => store.add_absence(record))
+    }
+
+    fn get_absence(&self, date: NaiveDate) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        delegate!(self, store => store.get_absence(date))
+    }
+
+    fn get_absences(&self, range: &DateRange) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        delegate!(self, store => store.get_absences(range))
+    }
+
+    fn all_absences(&self) -> Result<Vec<AbsenceRecord>, Self::Error> {
+        delegate!(self, store => store.all_absences())
+    }
+
+    fn remove_absence(&self, date: NaiveDate, id: Ulid) -> Result<bool, Self::Error> {
+        delegate!(self, store => store.remove_absence(date, id))
+    }
+
+    fn update_absence(
+        &self,
+        date: NaiveDate,
+        updated_record: AbsenceRecord,
+    ) -> Result<(), Self::Error> {
+        delegate!(self, store => store.update_absence(date, updated_record))
+    }
+}
+
+impl ContractStorage for DataStores {
+    type Error = DataStoreError;
+
+    fn add_contract(&self, contract: ContractPeriod) -> Result<(), Self::Error> {
+        delegate!(self, store => store.add_contract(contract))
+    }
+
+    fn list_contracts(&self) -> Result<Vec<ContractPeriod>, Self::Error> {
+        delegate!(self, store => store.list_contracts())
+    }
+
+    fn remove_contract(&self, id: Ulid) -> Result<bool, Self::Error> {
+        delegate!(self, store => store.remove_contract(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    use crate::utils::schedule::WeeklySchedule;
+    use crate::wad_data::AbsenceType;
+
+    fn open_stores(data_dir: &TempDir) -> (DataStores, DataStores) {
+        (
+            DataStores::Json(JsonDataStore::open_in(data_dir.path()).unwrap()),
+            DataStores::Sqlite(SqliteDataStore::open_in(data_dir.path()).unwrap()),
+        )
+    }
+
+    fn create_test_record(date: &str, absence_type: AbsenceType) -> AbsenceRecord {
+        AbsenceRecord {
+            id: Ulid::new(),
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            hours: 8.0,
+            absence_type,
+            note: None,
+            ical_uid: None,
+        }
+    }
+
+    fn create_test_contract(valid_from: &str) -> ContractPeriod {
+        ContractPeriod {
+            id: Ulid::new(),
+            valid_from: NaiveDate::parse_from_str(valid_from, "%Y-%m-%d").unwrap(),
+            schedule: WeeklySchedule::spread_over_weekdays(32.0),
+            note: Some("Part-time".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_migration_round_trip() {
+        let data_dir = TempDir::new().unwrap();
+        let (json, sqlite) = open_stores(&data_dir);
+        let absences = vec![
+            create_test_record("2024-12-31", AbsenceType::Holiday),
+            create_test_record("2025-01-02", AbsenceType::Other("Moving".to_string())),
+        ];
+        let contracts = vec![create_test_contract("2025-01-01")];
+        json.replace_contents(&absences, &contracts).unwrap();
+
+        sqlite
+            .replace_contents(
+                &json.all_absences().unwrap(),
+                &json.list_contracts().unwrap(),
+            )
+            .unwrap();
+        json.replace_contents(&[], &[]).unwrap();
+        json.replace_contents(
+            &sqlite.all_absences().unwrap(),
+            &sqlite.list_contracts().unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(json.all_absences().unwrap(), absences);
+        assert_eq!(json.list_contracts().unwrap(), contracts);
+    }
+
+    #[test]
+    fn test_failed_replace_keeps_sqlite_contents() {
+        let data_dir = TempDir::new().unwrap();
+        let (_, sqlite) = open_stores(&data_dir);
+        let existing = vec![create_test_record("2025-01-02", AbsenceType::Vacation)];
+        sqlite.replace_contents(&existing, &[]).unwrap();
+
+        // Two contract periods starting on the same day can't be stored
+        let result = sqlite.replace_contents(
+            &[create_test_record("2025-02-03", AbsenceType::Sick)],
+            &[
+                create_test_contract("2025-01-01"),
+                create_test_contract("2025-01-01"),
+            ],
+        );
+
+        assert!(matches!(
+            result,
+            Err(DataStoreError::Sqlite(
+                SqliteDataStoreError::DuplicateContract(_)
+            ))
+        ));
+        assert_eq!(sqlite.all_absences().unwrap(), existing);
+        assert!(sqlite.list_contracts().unwrap().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use super::error::WatsonError;
-use super::frame::{Frame, Frames};
+use super::frame::{CURRENT_FRAME_ID, Frame, Frames};
 use super::query::LogQuery;
+use super::source::FrameSource;
 use std::process::Command;
 use which::which;
 
@@ -93,9 +94,11 @@ impl WatsonClient {
 
         Ok(path.to_string_lossy().to_string())
     }
+}
 
+impl FrameSource for WatsonClient {
     /// Execute Watson log command and return parsed frames
-    pub fn log(&self, query: LogQuery) -> Result<Frames, WatsonError> {
+    fn log(&self, query: LogQuery) -> Result<Frames, WatsonError> {
         let args = query.to_args();
 
         let output = Command::new("watson")
@@ -110,9 +113,18 @@ impl WatsonClient {
         }
 
         let json_output = String::from_utf8_lossy(&output.stdout);
-        let frames: Vec<Frame> = serde_json::from_str(&json_output)
+        let mut frames: Vec<Frame> = serde_json::from_str(&json_output)
             .map_err(|e| WatsonError::JsonParseError(e.to_string()))?;
 
-        Ok(Frames::from(frames))
+        // Watson reports the running frame with a stop time of "now". Treat it
+        // as active, so it looks the same as frames from every other source.
+        for frame in &mut frames {
+            if frame.id == CURRENT_FRAME_ID {
+                frame.stop = None;
+            }
+        }
+
+        // The queried range was widened, see `LogQuery::to_args`
+        Ok(query.filter(Frames::from(frames)))
     }
 }
@@ -13,4 +13,13 @@ pub enum WatsonError {
 
     #[error("Failed to parse Watson JSON output: {0}")]
     JsonParseError(String),
+
+    #[error("Watson data directory not found: {0}")]
+    DataDirNotFound(String),
+
+    #[error("Failed to read Watson data file: {0}")]
+    DataFileRead(String),
+
+    #[error("Failed to parse Watson data file: {0}")]
+    DataFileParse(String),
 }
@@ -0,0 +1,318 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::error::WatsonError;
+use super::frame::{CURRENT_FRAME_ID, Frame, Frames};
+use super::query::LogQuery;
+use super::source::FrameSource;
+
+/// Reads frames directly from Watson's data directory instead of shelling out
+/// to the Watson CLI.
+///
+/// Watson keeps all finished frames in a `frames` file and the currently
+/// running frame (if any) in a `state` file, both as JSON.
+pub struct WatsonDataFiles {
+    watson_dir: PathBuf,
+}
+
+impl WatsonDataFiles {
+    /// Open the Watson data directory, respecting the `WATSON_DIR` environment variable
+    pub fn open() -> Result<Self, WatsonError> {
+        let watson_dir = Self::default_dir()?;
+
+        if !watson_dir.is_dir() {
+            return Err(WatsonError::DataDirNotFound(
+                watson_dir.display().to_string(),
+            ));
+        }
+
+        Ok(Self { watson_dir })
+    }
+
+    /// Point at the Watson data directory without requiring it to exist
+    ///
+    /// Reading frames fails later if it's missing, which is what `wad doctor`
+    /// wants to report instead of exiting right away.
+    pub fn locate() -> Result<Self, WatsonError> {
+        Ok(Self {
+            watson_dir: Self::default_dir()?,
+        })
+    }
+
+    /// Resolve the directory Watson stores its data in
+    fn default_dir() -> Result<PathBuf, WatsonError> {
+        if let Some(dir) = std::env::var_os("WATSON_DIR") {
+            return Ok(PathBuf::from(dir));
+        }
+
+        dirs::config_dir()
+            .map(|dir| dir.join("watson"))
+            .ok_or_else(|| {
+                WatsonError::DataDirNotFound("Could not determine config directory".to_string())
+            })
+    }
+
+    pub fn watson_dir(&self) -> &Path {
+        &self.watson_dir
+    }
+
+    fn frames_file_path(&self) -> PathBuf {
+        self.watson_dir.join("frames")
+    }
+
+    fn state_file_path(&self) -> PathBuf {
+        self.watson_dir.join("state")
+    }
+
+    fn read_file(path: &Path) -> Result<Option<String>, WatsonError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        fs::read_to_string(path)
+            .map(Some)
+            .map_err(|e| WatsonError::DataFileRead(format!("{}: {}", path.display(), e)))
+    }
+
+    fn load_frames(&self) -> Result<Vec<Frame>, WatsonError> {
+        match Self::read_file(&self.frames_file_path())? {
+            Some(content) => parse_frames(&content),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn load_current_frame(&self) -> Result<Option<Frame>, WatsonError> {
+        match Self::read_file(&self.state_file_path())? {
+            Some(content) => parse_state(&content),
+            None => Ok(None),
+        }
+    }
+}
+
+impl FrameSource for WatsonDataFiles {
+    fn log(&self, query: LogQuery) -> Result<Frames, WatsonError> {
+        let mut frames = self.load_frames()?;
+
+        if query.include_current
+            && let Some(current) = self.load_current_frame()?
+        {
+            frames.push(current);
+        }
+
+        let mut frames = query.filter(Frames::from(frames));
+        frames.frames.sort_by_key(|frame| frame.start);
+
+        Ok(frames)
+    }
+}
+
+/// Convert a Watson unix timestamp (integer or float seconds) into a `DateTime`
+fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    let seconds = value.as_f64()?;
+    DateTime::from_timestamp(seconds.trunc() as i64, (seconds.fract() * 1e9) as u32)
+}
+
+fn parse_tags(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|tag| tag.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the contents of Watson's `frames` file
+///
+/// Each frame is stored as `[start, stop, project, id, tags, updated_at]`.
+fn parse_frames(content: &str) -> Result<Vec<Frame>, WatsonError> {
+    let rows: Vec<Vec<Value>> =
+        serde_json::from_str(content).map_err(|e| WatsonError::DataFileParse(e.to_string()))?;
+
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| {
+            parse_frame_row(row).ok_or_else(|| {
+                WatsonError::DataFileParse(format!("Invalid frame at index {}", index))
+            })
+        })
+        .collect()
+}
+
+fn parse_frame_row(row: &[Value]) -> Option<Frame> {
+    Some(Frame {
+        start: parse_timestamp(row.first()?)?,
+        stop: Some(parse_timestamp(row.get(1)?)?),
+        project: row.get(2)?.as_str()?.to_string(),
+        id: row.get(3)?.as_str()?.to_string(),
+        tags: parse_tags(row.get(4)),
+    })
+}
+
+/// Parse the contents of Watson's `state` file
+///
+/// An empty object means that no frame is currently running.
+fn parse_state(content: &str) -> Result<Option<Frame>, WatsonError> {
+    let state: Value =
+        serde_json::from_str(content).map_err(|e| WatsonError::DataFileParse(e.to_string()))?;
+
+    let Some(project) = state.get("project").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+
+    let start = state
+        .get("start")
+        .and_then(parse_timestamp)
+        .ok_or_else(|| WatsonError::DataFileParse("Invalid start time in state".to_string()))?;
+
+    Ok(Some(Frame {
+        id: CURRENT_FRAME_ID.to_string(),
+        project: project.to_string(),
+        start,
+        stop: None,
+        tags: parse_tags(state.get("tags")),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::date::Timezone;
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+    use test_case::test_case;
+
+    // 2024-01-15 12:00:00 UTC and following hours
+    const NOON: i64 = 1705320000;
+    const HOUR: i64 = 3600;
+    const DAY: i64 = 24 * HOUR;
+
+    fn create_test_files(frames: &str, state: Option<&str>) -> (WatsonDataFiles, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("frames"), frames).unwrap();
+        if let Some(state) = state {
+            fs::write(temp_dir.path().join("state"), state).unwrap();
+        }
+
+        let files = WatsonDataFiles {
+            watson_dir: temp_dir.path().to_path_buf(),
+        };
+        (files, temp_dir)
+    }
+
+    #[test]
+    fn test_parse_frames() {
+        let content = format!(
+            r#"[
+                [{}, {}, "wad", "abc123", ["rust", "cli"], {}],
+                [{}.0, {}, "other", "def456", [], {}]
+            ]"#,
+            NOON,
+            NOON + HOUR,
+            NOON + HOUR,
+            NOON + 2 * HOUR,
+            NOON + 2 * HOUR + 1800,
+            NOON + 3 * HOUR
+        );
+
+        let frames = parse_frames(&content).unwrap();
+        assert_eq!(frames.len(), 2);
+
+        assert_eq!(frames[0].id, "abc123");
+        assert_eq!(frames[0].project, "wad");
+        assert_eq!(frames[0].tags, vec!["rust".to_string(), "cli".to_string()]);
+        assert_eq!(frames[0].duration(), chrono::Duration::hours(1));
+
+        assert_eq!(frames[1].project, "other");
+        assert!(frames[1].tags.is_empty());
+        assert_eq!(frames[1].duration(), chrono::Duration::minutes(30));
+    }
+
+    #[test_case("[[1, 2, \"project\"]]"; "missing id")]
+    #[test_case("[[\"start\", 2, \"project\", \"id\", []]]"; "invalid timestamp")]
+    #[test_case("{}"; "not a list")]
+    fn test_parse_frames_invalid(content: &str) {
+        assert!(parse_frames(content).is_err());
+    }
+
+    #[test]
+    fn test_parse_state() {
+        let content = format!(
+            r#"{{"project": "wad", "start": {}, "tags": ["rust"]}}"#,
+            NOON
+        );
+        let frame = parse_state(&content).unwrap().unwrap();
+
+        assert_eq!(frame.id, CURRENT_FRAME_ID);
+        assert_eq!(frame.project, "wad");
+        assert_eq!(frame.tags, vec!["rust".to_string()]);
+        assert!(frame.is_active());
+    }
+
+    #[test]
+    fn test_parse_empty_state() {
+        assert!(parse_state("{}").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_log_filters_by_query_span() {
+        let frames = format!(
+            r#"[
+                [{}, {}, "yesterday", "a", []],
+                [{}, {}, "today", "b", []],
+                [{}, {}, "tomorrow", "c", []]
+            ]"#,
+            NOON - DAY,
+            NOON - DAY + HOUR,
+            NOON,
+            NOON + HOUR,
+            NOON + DAY,
+            NOON + DAY + HOUR
+        );
+        let (files, _temp_dir) = create_test_files(&frames, None);
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let result = files
+            .log(LogQuery::new(date, date, Timezone::Local))
+            .unwrap();
+
+        assert_eq!(result.frames.len(), 1);
+        assert_eq!(result.frames[0].project, "today");
+    }
+
+    #[test]
+    fn test_log_includes_current_frame_only_when_requested() {
+        let state = format!(r#"{{"project": "running", "start": {}, "tags": []}}"#, NOON);
+        let (files, _temp_dir) = create_test_files("[]", Some(&state));
+
+        let from = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let to = Utc::now().date_naive() + chrono::Duration::days(1);
+
+        let without_current = files.log(LogQuery::new(from, to, Timezone::Local)).unwrap();
+        assert!(without_current.frames.is_empty());
+
+        let with_current = files
+            .log(LogQuery::new(from, to, Timezone::Local).with_current())
+            .unwrap();
+        assert_eq!(with_current.frames.len(), 1);
+        assert!(with_current.has_active_frames());
+    }
+
+    #[test]
+    fn test_log_without_data_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = WatsonDataFiles {
+            watson_dir: temp_dir.path().to_path_buf(),
+        };
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let result = files
+            .log(LogQuery::new(date, date, Timezone::Local).with_current())
+            .unwrap();
+        assert!(result.frames.is_empty());
+    }
+}
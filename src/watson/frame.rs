@@ -2,6 +2,11 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::utils::date::{TimeWindow, Timezone, Week};
+
+/// Id Watson uses for the currently running frame
+pub const CURRENT_FRAME_ID: &str = "current";
+
 /// A Watson time tracking frame
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Frame {
@@ -15,10 +20,33 @@ pub struct Frame {
 impl Frame {
     /// Calculate the duration of this frame
     pub fn duration(&self) -> chrono::Duration {
-        match self.stop {
-            Some(stop) => stop - self.start,
-            None => chrono::Utc::now() - self.start,
+        self.end() - self.start
+    }
+
+    /// Get the end of this frame, which is now for active frames
+    pub fn end(&self) -> DateTime<Utc> {
+        self.stop.unwrap_or_else(Utc::now)
+    }
+
+    /// Cut this frame to the part lying within the given window
+    ///
+    /// Returns `None` if the frame doesn't overlap the window at all. Active
+    /// frames stay active, unless they are cut off by the end of the window.
+    pub fn clip(&self, window: &TimeWindow) -> Option<Frame> {
+        let end = self.end();
+        if end <= window.start || self.start >= window.end {
+            return None;
         }
+
+        Some(Frame {
+            start: self.start.max(window.start),
+            stop: if end > window.end {
+                Some(window.end)
+            } else {
+                self.stop
+            },
+            ..self.clone()
+        })
     }
 
     /// Check if this frame is currently active (no stop time)
@@ -27,6 +55,7 @@ impl Frame {
     }
 
     /// Get a human-readable duration string
+    #[allow(dead_code)]
     pub fn duration_string(&self) -> String {
         let duration = self.duration();
         let hours = duration.num_hours();
@@ -36,8 +65,39 @@ impl Frame {
     }
 }
 
+/// Projects, tags and time window a frame has to match, empty lists and no
+/// window match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameFilter {
+    /// Keep only frames of one of these projects
+    pub projects: Vec<String>,
+    /// Drop frames of these projects
+    pub exclude_projects: Vec<String>,
+    /// Keep only frames with at least one of these tags
+    pub tags: Vec<String>,
+    /// Drop frames with any of these tags
+    pub exclude_tags: Vec<String>,
+    /// Keep only the part of frames lying within this window
+    pub window: Option<TimeWindow>,
+}
+
+impl FrameFilter {
+    /// Check whether a frame passes the filter, overlapping the window if there is one
+    pub fn matches(&self, frame: &Frame) -> bool {
+        let has_tag = |tags: &[String]| frame.tags.iter().any(|tag| tags.contains(tag));
+
+        (self.projects.is_empty() || self.projects.contains(&frame.project))
+            && !self.exclude_projects.contains(&frame.project)
+            && (self.tags.is_empty() || has_tag(&self.tags))
+            && !has_tag(&self.exclude_tags)
+            && self
+                .window
+                .is_none_or(|window| frame.clip(&window).is_some())
+    }
+}
+
 /// Collection of frames with helper methods
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Frames {
     pub frames: Vec<Frame>,
 }
@@ -71,7 +131,71 @@ impl Frames {
             .collect()
     }
 
+    /// Group frames by tag
+    ///
+    /// Frames with several tags are part of each of their groups, so the
+    /// groups can add up to more than the total. Untagged frames are left out.
+    pub fn by_tag(&self) -> HashMap<String, Frames> {
+        let mut grouped: HashMap<String, Vec<Frame>> = HashMap::new();
+        for frame in &self.frames {
+            // Watson doesn't stop a tag from being given twice
+            let mut tags: Vec<&String> = frame.tags.iter().collect();
+            tags.sort();
+            tags.dedup();
+            for tag in tags {
+                grouped.entry(tag.clone()).or_default().push(frame.clone());
+            }
+        }
+
+        grouped
+            .into_iter()
+            .map(|(tag, frames)| (tag, Frames::from(frames)))
+            .collect()
+    }
+
+    /// Group frames by project and tag
+    ///
+    /// Like [`Frames::by_tag`], frames are part of a group for each of their
+    /// tags and untagged frames are left out.
+    pub fn by_project_and_tag(&self) -> HashMap<(String, String), Frames> {
+        self.by_project()
+            .into_iter()
+            .flat_map(|(project, frames)| {
+                frames
+                    .by_tag()
+                    .into_iter()
+                    .map(move |(tag, frames)| ((project.clone(), tag), frames))
+            })
+            .collect()
+    }
+
+    /// Get the frames without any tag
+    pub fn untagged(&self) -> Frames {
+        self.filter_by(|frame| frame.tags.is_empty())
+    }
+
+    /// Keep the frames passing the filter
+    ///
+    /// With a window, frames are cut to it like [`Frames::clip`] does.
+    pub fn filter(&self, filter: &FrameFilter) -> Frames {
+        let frames = self.filter_by(|frame| filter.matches(frame));
+        match &filter.window {
+            Some(window) => frames.clip(window),
+            None => frames,
+        }
+    }
+
+    fn filter_by(&self, predicate: impl Fn(&Frame) -> bool) -> Frames {
+        self.frames
+            .iter()
+            .filter(|frame| predicate(frame))
+            .cloned()
+            .collect::<Vec<_>>()
+            .into()
+    }
+
     /// Get currently active frames
+    #[allow(dead_code)]
     pub fn active_frames(&self) -> Vec<&Frame> {
         self.frames
             .iter()
@@ -84,12 +208,70 @@ impl Frames {
         self.frames.iter().any(|frame| frame.is_active())
     }
 
-    /// Group frames by date (ignoring time)
-    pub fn by_date(&self) -> HashMap<NaiveDate, Frames> {
+    /// Get the gaps between frames, i.e. the breaks taken between the first
+    /// start and the last end
+    ///
+    /// Overlapping frames are merged, so only time without any frame counts.
+    pub fn gaps(&self) -> Vec<TimeWindow> {
+        let mut frames: Vec<&Frame> = self.frames.iter().collect();
+        frames.sort_by_key(|frame| frame.start);
+
+        let mut gaps = Vec::new();
+        let mut covered_until: Option<DateTime<Utc>> = None;
+        for frame in frames {
+            if let Some(until) = covered_until
+                && frame.start > until
+            {
+                gaps.push(TimeWindow::new(until, frame.start));
+            }
+
+            let end = frame.end();
+            covered_until = Some(covered_until.map_or(end, |until| until.max(end)));
+        }
+
+        gaps
+    }
+
+    /// Get the total duration of all gaps between frames
+    pub fn break_duration(&self) -> chrono::Duration {
+        self.break_duration_of_segments(chrono::Duration::zero())
+    }
+
+    /// Get the total duration of the gaps lasting at least `minimum`
+    ///
+    /// Shorter gaps, like a minute between switching projects, don't count as a break.
+    pub fn break_duration_of_segments(&self, minimum: chrono::Duration) -> chrono::Duration {
+        self.gaps()
+            .iter()
+            .map(|gap| gap.end - gap.start)
+            .filter(|duration| *duration >= minimum)
+            .fold(chrono::Duration::zero(), |acc, duration| acc + duration)
+    }
+
+    /// Cut all frames to the given window, dropping frames outside of it
+    pub fn clip(&self, window: &TimeWindow) -> Frames {
+        self.frames
+            .iter()
+            .filter_map(|frame| frame.clip(window))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// Group frames by date in the given timezone
+    ///
+    /// Frames crossing midnight are split up across all days they cover.
+    pub fn by_date(&self, timezone: Timezone) -> HashMap<NaiveDate, Frames> {
         let mut grouped: HashMap<NaiveDate, Vec<Frame>> = HashMap::new();
         for frame in &self.frames {
-            let date = frame.start.date_naive();
-            grouped.entry(date).or_default().push(frame.clone());
+            let last_date = timezone.date_of(&frame.end());
+            let mut date = timezone.date_of(&frame.start);
+
+            while date <= last_date {
+                if let Some(part) = frame.clip(&TimeWindow::day(date, timezone)) {
+                    grouped.entry(date).or_default().push(part);
+                }
+                date += chrono::Duration::days(1);
+            }
         }
 
         grouped
@@ -97,6 +279,29 @@ impl Frames {
             .map(|(date, frames)| (date, Frames::from(frames)))
             .collect()
     }
+
+    /// Group frames by week (Monday to Sunday) in the given timezone
+    ///
+    /// Frames crossing the start of a week are split up across all weeks they cover.
+    pub fn by_week(&self, timezone: Timezone) -> HashMap<Week, Frames> {
+        let mut grouped: HashMap<Week, Vec<Frame>> = HashMap::new();
+        for frame in &self.frames {
+            let last_week = Week::containing(timezone.date_of(&frame.end()));
+            let mut week = Week::containing(timezone.date_of(&frame.start));
+
+            while week.start <= last_week.start {
+                if let Some(part) = frame.clip(&TimeWindow::week(&week, timezone)) {
+                    grouped.entry(week.clone()).or_default().push(part);
+                }
+                week = Week::new(week.start + chrono::Duration::weeks(1));
+            }
+        }
+
+        grouped
+            .into_iter()
+            .map(|(week, frames)| (week, Frames::from(frames)))
+            .collect()
+    }
 }
 
 impl From<Vec<Frame>> for Frames {
@@ -104,3 +309,334 @@ impl From<Vec<Frame>> for Frames {
         Self::new(frames)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+    use test_case::test_case;
+
+    fn create_test_frame(project: &str, start: DateTime<Utc>, hours: i64) -> Frame {
+        Frame {
+            id: format!("{}-{}", project, start.timestamp()),
+            project: project.to_string(),
+            start,
+            stop: Some(start + chrono::Duration::hours(hours)),
+            tags: vec![],
+        }
+    }
+
+    fn utc() -> Timezone {
+        Timezone::Fixed("+00:00".parse().unwrap())
+    }
+
+    fn window(start: (u32, u32), end: (u32, u32)) -> TimeWindow {
+        TimeWindow::new(
+            Utc.with_ymd_and_hms(2024, 1, start.0, start.1, 0, 0)
+                .unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, end.0, end.1, 0, 0).unwrap(),
+        )
+    }
+
+    // Frame from 2024-01-15 22:00 to 2024-01-16 02:00 UTC
+    #[test_case((15, 0), (16, 0), Some(((15, 22), (16, 0))); "clipped at end")]
+    #[test_case((16, 0), (17, 0), Some(((16, 0), (16, 2))); "clipped at start")]
+    #[test_case((15, 23), (16, 1), Some(((15, 23), (16, 1))); "clipped at both ends")]
+    #[test_case((15, 0), (17, 0), Some(((15, 22), (16, 2))); "completely within window")]
+    #[test_case((16, 2), (17, 0), None; "window after frame")]
+    #[test_case((15, 0), (15, 22), None; "window before frame")]
+    fn test_frame_clip(
+        window_start: (u32, u32),
+        window_end: (u32, u32),
+        expected: Option<((u32, u32), (u32, u32))>,
+    ) {
+        let frame = create_test_frame(
+            "night",
+            Utc.with_ymd_and_hms(2024, 1, 15, 22, 0, 0).unwrap(),
+            4,
+        );
+        let clipped = frame.clip(&window(window_start, window_end));
+
+        let expected = expected.map(|(start, stop)| {
+            let window = window(start, stop);
+            (window.start, Some(window.end))
+        });
+        assert_eq!(clipped.map(|frame| (frame.start, frame.stop)), expected);
+    }
+
+    #[test]
+    fn test_active_frame_clip() {
+        let start = Utc::now() - chrono::Duration::hours(2);
+        let frame = Frame {
+            stop: None,
+            ..create_test_frame("active", start, 0)
+        };
+
+        // A window reaching into the future keeps the frame active
+        let open_window = TimeWindow::new(start, Utc::now() + chrono::Duration::hours(1));
+        assert!(frame.clip(&open_window).unwrap().is_active());
+
+        // A window ending in the past cuts the frame off
+        let past_window = TimeWindow::new(start, start + chrono::Duration::hours(1));
+        let clipped = frame.clip(&past_window).unwrap();
+        assert!(!clipped.is_active());
+        assert_eq!(clipped.duration(), chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn test_by_date_splits_frames_crossing_midnight() {
+        let frames = Frames::from(vec![create_test_frame(
+            "night",
+            Utc.with_ymd_and_hms(2024, 1, 15, 22, 0, 0).unwrap(),
+            28,
+        )]);
+
+        let by_date = frames.by_date(utc());
+        assert_eq!(by_date.len(), 3);
+
+        let day = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        assert_eq!(
+            by_date[&day(15)].total_duration(),
+            chrono::Duration::hours(2)
+        );
+        assert_eq!(
+            by_date[&day(16)].total_duration(),
+            chrono::Duration::hours(24)
+        );
+        assert_eq!(
+            by_date[&day(17)].total_duration(),
+            chrono::Duration::hours(2)
+        );
+    }
+
+    #[test]
+    fn test_by_date_frame_ending_at_midnight() {
+        let frames = Frames::from(vec![create_test_frame(
+            "evening",
+            Utc.with_ymd_and_hms(2024, 1, 15, 20, 0, 0).unwrap(),
+            4,
+        )]);
+
+        let by_date = frames.by_date(utc());
+        assert_eq!(by_date.len(), 1);
+        assert_eq!(
+            by_date[&NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()].total_duration(),
+            chrono::Duration::hours(4)
+        );
+    }
+
+    #[test]
+    fn test_by_date_uses_timezone() {
+        let frames = Frames::from(vec![
+            // Late evening in UTC, already the next day east of UTC
+            create_test_frame(
+                "late",
+                Utc.with_ymd_and_hms(2024, 1, 15, 23, 0, 0).unwrap(),
+                1,
+            ),
+            // Early morning in UTC, still the previous day west of UTC
+            create_test_frame(
+                "early",
+                Utc.with_ymd_and_hms(2024, 1, 16, 3, 0, 0).unwrap(),
+                1,
+            ),
+        ]);
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 1, 16).unwrap();
+
+        let utc = frames.by_date(utc());
+        assert_eq!(utc[&monday].frames[0].project, "late");
+        assert_eq!(utc[&tuesday].frames[0].project, "early");
+
+        let east = frames.by_date(Timezone::Fixed("+02:00".parse().unwrap()));
+        assert!(!east.contains_key(&monday));
+        assert_eq!(east[&tuesday].frames.len(), 2);
+
+        let west = frames.by_date(Timezone::Fixed("-05:00".parse().unwrap()));
+        assert_eq!(west[&monday].frames.len(), 2);
+        assert!(!west.contains_key(&tuesday));
+    }
+
+    #[test]
+    fn test_by_week() {
+        let frames = Frames::from(vec![
+            // Monday and Sunday of the same week
+            create_test_frame(
+                "wad",
+                Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap(),
+                2,
+            ),
+            create_test_frame(
+                "wad",
+                Utc.with_ymd_and_hms(2024, 1, 21, 9, 0, 0).unwrap(),
+                1,
+            ),
+            // Monday of the following week
+            create_test_frame(
+                "wad",
+                Utc.with_ymd_and_hms(2024, 1, 22, 9, 0, 0).unwrap(),
+                3,
+            ),
+        ]);
+
+        let by_week = frames.by_week(utc());
+        assert_eq!(by_week.len(), 2);
+
+        let first_week = Week::new(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let second_week = Week::new(NaiveDate::from_ymd_opt(2024, 1, 22).unwrap());
+
+        assert_eq!(by_week[&first_week].frames.len(), 2);
+        assert_eq!(
+            by_week[&first_week].total_duration(),
+            chrono::Duration::hours(3)
+        );
+        assert_eq!(by_week[&second_week].frames.len(), 1);
+        assert_eq!(
+            by_week[&second_week].total_duration(),
+            chrono::Duration::hours(3)
+        );
+    }
+
+    #[test]
+    fn test_by_week_splits_frames_crossing_week_boundary() {
+        // Sunday 22:00 to Monday 02:00
+        let frames = Frames::from(vec![create_test_frame(
+            "night",
+            Utc.with_ymd_and_hms(2024, 1, 21, 22, 0, 0).unwrap(),
+            4,
+        )]);
+
+        let by_week = frames.by_week(utc());
+        let first_week = Week::new(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let second_week = Week::new(NaiveDate::from_ymd_opt(2024, 1, 22).unwrap());
+
+        assert_eq!(
+            by_week[&first_week].total_duration(),
+            chrono::Duration::hours(2)
+        );
+        assert_eq!(
+            by_week[&second_week].total_duration(),
+            chrono::Duration::hours(2)
+        );
+    }
+
+    fn create_frame_at(start: (u32, u32), end: (u32, u32)) -> Frame {
+        let window = window(start, end);
+        Frame {
+            id: format!("frame-{}", window.start.timestamp()),
+            project: "project".to_string(),
+            start: window.start,
+            stop: Some(window.end),
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_by_tag() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap();
+        let tagged = |hours, tags: &[&str]| Frame {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..create_test_frame("wad", start, hours)
+        };
+        let frames = Frames::from(vec![
+            tagged(1, &["ABC-1"]),
+            tagged(2, &["ABC-1", "review", "ABC-1"]),
+            tagged(4, &[]),
+        ]);
+
+        let by_tag = frames.by_tag();
+
+        assert_eq!(by_tag.len(), 2);
+        assert_eq!(by_tag["ABC-1"].total_duration(), chrono::Duration::hours(3));
+        assert_eq!(
+            by_tag["review"].total_duration(),
+            chrono::Duration::hours(2)
+        );
+    }
+
+    #[test]
+    fn test_by_project_and_tag() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap();
+        let frame = |project, hours, tags: &[&str]| Frame {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..create_test_frame(project, start, hours)
+        };
+        let frames = Frames::from(vec![
+            frame("wad", 1, &["ABC-1"]),
+            frame("wad", 2, &["ABC-1", "review"]),
+            frame("ops", 3, &["ABC-1"]),
+            frame("ops", 4, &[]),
+        ]);
+
+        let grouped = frames.by_project_and_tag();
+        let hours = |project: &str, tag: &str| {
+            grouped[&(project.to_string(), tag.to_string())]
+                .total_duration()
+                .num_hours()
+        };
+
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(hours("wad", "ABC-1"), 3);
+        assert_eq!(hours("wad", "review"), 2);
+        assert_eq!(hours("ops", "ABC-1"), 3);
+        assert_eq!(frames.untagged().total_duration().num_hours(), 4);
+    }
+
+    #[test_case(FrameFilter::default(), 10 ; "no filter")]
+    #[test_case(FrameFilter { projects: vec!["wad".into()], ..Default::default() }, 3 ; "include project")]
+    #[test_case(FrameFilter { exclude_projects: vec!["wad".into()], ..Default::default() }, 7 ; "exclude project")]
+    #[test_case(FrameFilter { tags: vec!["ABC-1".into(), "ABC-2".into()], ..Default::default() }, 6 ; "include any of the tags")]
+    #[test_case(FrameFilter { exclude_tags: vec!["review".into()], ..Default::default() }, 8 ; "exclude tag")]
+    #[test_case(FrameFilter { projects: vec!["ops".into()], tags: vec!["ABC-1".into()], ..Default::default() }, 3 ; "project and tag")]
+    #[test_case(FrameFilter { window: Some(window((15, 10), (15, 11))), ..Default::default() }, 2 ; "window cuts frames")]
+    #[test_case(FrameFilter { projects: vec!["ops".into()], window: Some(window((15, 9), (16, 0))), ..Default::default() }, 5 ; "project and window")]
+    #[test_case(FrameFilter { window: Some(window((16, 8), (16, 9))), ..Default::default() }, 0 ; "window without frames")]
+    fn test_filter(filter: FrameFilter, expected_hours: i64) {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap();
+        let frame = |project, hours, tags: &[&str]| Frame {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..create_test_frame(project, start, hours)
+        };
+        let frames = Frames::from(vec![
+            frame("wad", 1, &["ABC-1"]),
+            frame("wad", 2, &["ABC-2", "review"]),
+            frame("ops", 3, &["ABC-1"]),
+            frame("ops", 4, &[]),
+        ]);
+
+        assert_eq!(
+            frames.filter(&filter).total_duration().num_hours(),
+            expected_hours
+        );
+    }
+
+    #[test]
+    fn test_adjacent_frames_have_no_gaps() {
+        let frames = Frames::from(vec![
+            create_frame_at((15, 12), (15, 17)),
+            create_frame_at((15, 8), (15, 12)),
+        ]);
+
+        assert!(frames.gaps().is_empty());
+    }
+
+    #[test]
+    fn test_gaps_between_frames() {
+        // Unsorted, with a frame lying within another one
+        let frames = Frames::from(vec![
+            create_frame_at((15, 13), (15, 17)),
+            create_frame_at((15, 8), (15, 12)),
+            create_frame_at((15, 10), (15, 11)),
+            create_frame_at((15, 17), (15, 18)),
+        ]);
+
+        assert_eq!(frames.gaps(), vec![window((15, 12), (15, 13))]);
+        assert_eq!(frames.break_duration(), chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn test_no_gaps_without_frames() {
+        assert!(Frames::default().gaps().is_empty());
+        assert_eq!(Frames::default().break_duration(), chrono::Duration::zero());
+    }
+}
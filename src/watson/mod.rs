@@ -1,7 +1,11 @@
 pub mod client;
 pub mod error;
+pub mod files;
 pub mod frame;
 pub mod query;
+pub mod source;
 
 pub use client::WatsonClient;
+pub use files::WatsonDataFiles;
 pub use query::LogQuery;
+pub use source::{FrameSource, FrameSources};
@@ -1,6 +1,18 @@
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate};
 
-use crate::utils::date::Week;
+use super::frame::{Frame, Frames};
+use crate::utils::date::{DateRange, TimeWindow, Timezone};
+
+/// Earliest date passed to Watson for unbounded queries
+///
+/// Watson stores frames as Unix timestamps, so none can start before it.
+const UNBOUNDED_FROM: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+
+/// Latest date passed to Watson for unbounded queries
+///
+/// One day before the end of Python's date range, so the end of the day
+/// still fits after converting from the system's timezone.
+const UNBOUNDED_TO: NaiveDate = NaiveDate::from_ymd_opt(9999, 12, 30).unwrap();
 
 /// Parameters for Watson log command
 #[derive(Debug, Clone)]
@@ -8,27 +20,27 @@ pub struct LogQuery {
     pub from: NaiveDate,
     pub to: NaiveDate,
     pub include_current: bool,
+    pub include_partial: bool,
+    pub timezone: Timezone,
 }
 
 impl LogQuery {
     /// Create a new log query
-    pub fn new(from: NaiveDate, to: NaiveDate) -> Self {
+    ///
+    /// The dates are interpreted in the given timezone.
+    pub fn new(from: NaiveDate, to: NaiveDate, timezone: Timezone) -> Self {
         Self {
             from,
             to,
             include_current: false,
+            include_partial: false,
+            timezone,
         }
     }
 
-    /// Create a log query for today
-    pub fn today() -> Self {
-        let today = chrono::Utc::now().date_naive();
-        Self::new(today, today)
-    }
-
-    /// Create a log query for the given Week
-    pub fn week(week: &Week) -> Self {
-        Self::new(week.start, week.end)
+    /// Create a log query spanning all days of the given range
+    pub fn range(range: &DateRange, timezone: Timezone) -> Self {
+        Self::new(range.from, range.to, timezone)
     }
 
     /// Include current/active frames in the query
@@ -37,14 +49,69 @@ impl LogQuery {
         self
     }
 
+    /// Include frames that only partially overlap the query's span, clipped to the span
+    pub fn with_partial_frames(mut self) -> Self {
+        self.include_partial = true;
+        self
+    }
+
+    /// Get the time span covered by this query
+    ///
+    /// Like `watson log`, the span starts at midnight of `from` and ends at
+    /// midnight after `to`, both in the query's timezone.
+    pub fn span(&self) -> TimeWindow {
+        TimeWindow::dates(self.from, self.to, self.timezone)
+    }
+
+    /// Check if a frame lies completely within this query's span
+    ///
+    /// Active frames are treated as ending now, matching `watson log --current`.
+    pub fn contains(&self, frame: &Frame) -> bool {
+        let span = self.span();
+        frame.start >= span.start && frame.end() < span.end
+    }
+
+    /// Reduce frames to the ones matching this query
+    ///
+    /// Frames have to lie completely within the query's span, unless partial
+    /// frames are requested. Those are clipped to the span instead.
+    pub fn filter(&self, frames: Frames) -> Frames {
+        if self.include_partial {
+            frames.clip(&self.span())
+        } else {
+            frames
+                .frames
+                .into_iter()
+                .filter(|frame| self.contains(frame))
+                .collect::<Vec<_>>()
+                .into()
+        }
+    }
+
     /// Convert to Watson command line arguments
+    ///
+    /// Watson interprets dates in the system's timezone, which may differ from
+    /// the query's, and only returns frames completely within the range. The
+    /// range is therefore widened by two days on each side, so frames crossing
+    /// midnight are found as well, and the result has to be narrowed down
+    /// again using `filter`.
+    ///
+    /// Frames have no maximum length, so a partial frame may start or end any
+    /// number of days outside the query. Queries including partial frames
+    /// therefore ask Watson for all frames and leave the clipping to `filter`.
     pub fn to_args(&self) -> Vec<String> {
+        let (from, to) = if self.include_partial {
+            (UNBOUNDED_FROM, UNBOUNDED_TO)
+        } else {
+            (self.from - Duration::days(2), self.to + Duration::days(2))
+        };
+
         let mut args = vec![
             "log".to_string(),
             "--from".to_string(),
-            self.from.format("%Y-%m-%d").to_string(),
+            from.format("%Y-%m-%d").to_string(),
             "--to".to_string(),
-            self.to.format("%Y-%m-%d").to_string(),
+            to.format("%Y-%m-%d").to_string(),
             "--json".to_string(),
         ];
 
@@ -55,3 +122,144 @@ impl LogQuery {
         args
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+    use test_case::test_case;
+
+    fn create_test_frame(start: DateTime<Utc>, stop: DateTime<Utc>) -> Frame {
+        Frame {
+            id: "test".to_string(),
+            project: "wad".to_string(),
+            start,
+            stop: Some(stop),
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_span_uses_timezone() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let query = LogQuery::new(date, date, Timezone::Fixed("+02:00".parse().unwrap()));
+
+        let span = query.span();
+        assert_eq!(
+            span.start,
+            Utc.with_ymd_and_hms(2024, 1, 14, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            span.end,
+            Utc.with_ymd_and_hms(2024, 1, 15, 22, 0, 0).unwrap()
+        );
+    }
+
+    // Query for 2024-01-15 in +02:00, which spans 2024-01-14 22:00 to 2024-01-15 22:00 UTC
+    #[test_case((14, 22, 0), (14, 23, 0), true; "starts at beginning of span")]
+    #[test_case((15, 21, 0), (15, 21, 59), true; "ends right before end of span")]
+    #[test_case((14, 21, 0), (14, 23, 0), false; "starts before span")]
+    #[test_case((15, 21, 0), (15, 23, 0), false; "ends after span")]
+    #[test_case((15, 23, 0), (15, 23, 30), false; "completely after span")]
+    fn test_contains(start: (u32, u32, u32), stop: (u32, u32, u32), expected: bool) {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let query = LogQuery::new(date, date, Timezone::Fixed("+02:00".parse().unwrap()));
+
+        let at = |(day, hour, minute)| Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap();
+        let frame = create_test_frame(at(start), at(stop));
+
+        assert_eq!(query.contains(&frame), expected);
+    }
+
+    #[test]
+    fn test_filter_with_partial_frames() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let query = LogQuery::new(date, date, Timezone::Fixed("+00:00".parse().unwrap()))
+            .with_partial_frames();
+
+        let at = |day, hour| Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap();
+        let frames = vec![
+            create_test_frame(at(14, 22), at(15, 2)),
+            create_test_frame(at(15, 9), at(15, 17)),
+            create_test_frame(at(15, 22), at(16, 2)),
+            create_test_frame(at(16, 9), at(16, 17)),
+        ];
+
+        let filtered = query.filter(Frames::from(frames.clone())).frames;
+        assert_eq!(filtered.len(), 3);
+        assert_eq!(filtered[0].start, at(15, 0));
+        assert_eq!(filtered[0].stop, Some(at(15, 2)));
+        assert_eq!(filtered[1].start, at(15, 9));
+        assert_eq!(filtered[1].stop, Some(at(15, 17)));
+        assert_eq!(filtered[2].start, at(15, 22));
+        assert_eq!(filtered[2].stop, Some(at(16, 0)));
+
+        // Without partial frames only the frame completely within the day remains
+        let query = LogQuery::new(date, date, Timezone::Fixed("+00:00".parse().unwrap()));
+        let filtered = query.filter(Frames::from(frames)).frames;
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].start, at(15, 9));
+    }
+
+    #[test]
+    fn test_to_args_widens_range() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 21).unwrap();
+
+        let args = LogQuery::new(from, to, Timezone::Local)
+            .with_current()
+            .to_args();
+        assert_eq!(
+            args,
+            vec![
+                "log",
+                "--from",
+                "2024-01-13",
+                "--to",
+                "2024-01-23",
+                "--json",
+                "--current"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_args_unbounded_for_partial_frames() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        let args = LogQuery::new(date, date, Timezone::Local)
+            .with_partial_frames()
+            .to_args();
+        assert_eq!(
+            args,
+            vec![
+                "log",
+                "--from",
+                "1970-01-01",
+                "--to",
+                "9999-12-30",
+                "--json"
+            ]
+        );
+    }
+
+    // Frames spanning three days, which reach further than the two days the range is widened by
+    #[test_case((12, 9), (15, 9); "starts three days before")]
+    #[test_case((15, 9), (18, 9); "ends three days after")]
+    fn test_to_args_finds_long_partial_frames(start: (u32, u32), stop: (u32, u32)) {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let timezone = Timezone::Fixed("+00:00".parse().unwrap());
+        let query = LogQuery::new(date, date, timezone).with_partial_frames();
+
+        let at = |(day, hour)| Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap();
+        let frame = create_test_frame(at(start), at(stop));
+
+        // Watson only returns frames lying completely within the requested dates
+        let args = query.to_args();
+        let requested = LogQuery::new(args[2].parse().unwrap(), args[4].parse().unwrap(), timezone);
+        assert!(requested.contains(&frame));
+
+        let filtered = query.filter(Frames::from(vec![frame])).frames;
+        assert_eq!(filtered.len(), 1);
+    }
+}
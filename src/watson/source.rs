@@ -0,0 +1,24 @@
+use enum_dispatch::enum_dispatch;
+
+use super::client::WatsonClient;
+use super::error::WatsonError;
+use super::files::WatsonDataFiles;
+use super::frame::Frames;
+use super::query::LogQuery;
+
+/// A source of Watson frames
+///
+/// Commands only talk to this trait, so it doesn't matter whether the frames
+/// come from the Watson CLI or are read from Watson's data files directly.
+#[enum_dispatch]
+pub trait FrameSource {
+    /// Fetch all frames matching the given query
+    fn log(&self, query: LogQuery) -> Result<Frames, WatsonError>;
+}
+
+/// All available frame sources
+#[enum_dispatch(FrameSource)]
+pub enum FrameSources {
+    Cli(WatsonClient),
+    Files(WatsonDataFiles),
+}